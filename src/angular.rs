@@ -0,0 +1,166 @@
+use crate::{
+    gen::{accept_header, first_upper, ws_protocols, ws_url},
+    path,
+    types::{Kind, RequestInfo},
+};
+
+pub(crate) const BASE: &str = include_str!("base/angular.ts");
+
+pub(crate) const IMPORTS: &str = "import { HttpClient, HttpParams, HttpResponse } from \
+                                  '@angular/common/http';
+import { Injectable } from '@angular/core';
+import { Observable, map } from 'rxjs';
+import { webSocket } from 'rxjs/webSocket';
+";
+
+/// Wraps the methods of one namespace into an injectable service.
+pub(crate) fn service(tag: &str, methods: &[String]) -> String {
+    format!(
+        "    @Injectable({{ providedIn: 'root' }})
+    export class {}Service {{
+        constructor(private http: HttpClient) {{}}
+
+{}
+    }}\n",
+        first_upper(tag),
+        methods.join("\n"),
+    )
+}
+
+/// Generates the service method of a single route, the observable counterpart
+/// of the fetch based function.
+pub(crate) fn method(
+    v: &RequestInfo,
+    name: &str,
+    struct_name: &str,
+    doc: &str,
+) -> String {
+    let req_params = if v.req_params.is_some() {
+        format!("params: {struct_name}Params, ")
+    } else {
+        String::new()
+    };
+    let path_params = if v.path_params.is_some() {
+        format!("pathParams: {struct_name}PathParams, ")
+    } else {
+        String::new()
+    };
+
+    let params = if v.req_params.is_some() {
+        format!(
+            "options.unsafe ? params as {struct_name}Params : \
+             {name}ParamsSchema.parse(params)"
+        )
+    } else {
+        String::new()
+    };
+
+    match &v.res_body {
+        Kind::SSE(_) => format!(
+            "{doc}    {name}({path_params}{req_params}): Observable<{struct_name}Msg> {{
+        return eventSource(
+            baseUrlOf('{tag}') + {path}{query},
+            (data) => options.unsafe ? data as {struct_name}Msg : \
+             {name}Msg.parse(data),{credentials}
+        );
+    }}\n",
+            tag = v.tag,
+            path = path::expr(v, name),
+            credentials = match v.credentials {
+                true => "",
+                false => "\n            false,",
+            },
+            query = if params.is_empty() {
+                String::new()
+            } else {
+                format!(" + makeQuery({params})")
+            },
+        ),
+        Kind::Websocket { .. } => format!(
+            "{doc}    {name}({path_params}{req_params}): {struct_name}Websocket {{
+        return socket(
+            {url},
+            (data) => options.unsafe ? data as {struct_name}ClientMsg : \
+             {name}ClientMsgSchema.parse(data),
+            (data) => options.unsafe ? data as {struct_name}ServerMsg : \
+             {name}ServerMsgSchema.parse(data){protocols},
+        );
+    }}\n",
+            url = ws_url(
+                v,
+                format!(
+                    "wsBaseUrlOf('{}') + {}{}",
+                    v.tag,
+                    path::expr(v, name),
+                    if params.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" + makeQuery({params})")
+                    }
+                )
+            ),
+            protocols = ws_protocols(v),
+        ),
+        res_body => {
+            let (res_name, response_type, parse) = match res_body {
+                Kind::Schema(_) => (
+                    format!("{struct_name}Res"),
+                    "'json'",
+                    format!(
+                        ".pipe(map((data) => options.unsafe ? data as {struct_name}Res \
+                         : {name}ResSchema.parse(data)))"
+                    ),
+                ),
+                Kind::Any | Kind::Text => ("string".to_string(), "'text'", String::new()),
+                Kind::Binary => ("Blob".to_string(), "'blob'", String::new()),
+                Kind::None if v.no_content => (
+                    "void".to_string(),
+                    "'text'",
+                    ".pipe(map(() => undefined))".to_string(),
+                ),
+                _ => ("HttpResponse<string>".to_string(), "'text'", String::new()),
+            };
+
+            format!(
+                "{doc}    {name}({path_params}{req_json}{req_params}): \
+                 Observable<{res_name}> {{
+        return this.http.request('{method}', baseUrlOf('{tag}') + {path}, {{
+            body: {body},{params}
+            withCredentials: {credentials},{headers}
+            responseType: {response_type},{observe}
+        }}){parse};
+    }}\n",
+                req_json = if v.req_body.is_some() {
+                    format!("req: {struct_name}Req, ")
+                } else {
+                    String::new()
+                },
+                method = v.method,
+                tag = v.tag,
+                path = path::expr(v, name),
+                credentials = v.credentials,
+                body = match &v.req_body {
+                    Kind::Schema(_) => format!("{name}ReqSchema.parse(req)"),
+                    Kind::Any | Kind::Text => "req".to_string(),
+                    _ => "null".to_string(),
+                },
+                params = if params.is_empty() {
+                    String::new()
+                } else {
+                    format!("\n            params: makeParams({params}),")
+                },
+                // the HttpClient already accepts JSON by default
+                headers = match accept_header(v, false) {
+                    Some(accept) =>
+                        format!("\n            headers: {{ Accept: '{accept}' }},"),
+                    None => String::new(),
+                },
+                observe = if res_body.is_none() && !v.no_content {
+                    "\n            observe: 'response',"
+                } else {
+                    ""
+                },
+            )
+        },
+    }
+}
@@ -0,0 +1,18 @@
+
+const inFlight = new Set<AbortController>();
+
+/** Aborts every request that is still in flight, e.g. when navigating away */
+export const abortAll = () => {
+    inFlight.forEach(controller => controller.abort());
+    inFlight.clear();
+};
+
+const tracked = (init: RequestInit, run: (signal: AbortSignal) => Promise<Response>) => {
+    const controller = new AbortController();
+    // an aborted signal does not fire anymore
+    if (init.signal?.aborted) controller.abort(init.signal.reason);
+    init.signal?.addEventListener('abort', () => controller.abort(init.signal?.reason));
+
+    inFlight.add(controller);
+    return run(controller.signal).finally(() => inFlight.delete(controller));
+};
@@ -0,0 +1,72 @@
+interface Options {
+    baseUrl: string;
+    /** Base urls of single namespaces, falling back to `baseUrl` */
+    baseUrls: Record<string, string>;
+    unsafe: boolean;
+    /** Resolves the token of websockets sending one as query parameter */
+    wsToken?(tag: string): string | undefined;
+}
+
+export const options: Options = {
+    baseUrl: '',
+    baseUrls: {},
+    unsafe: false,
+}
+
+const baseUrlOf = (tag: string) => options.baseUrls[tag] ?? options.baseUrl;
+
+const wsBaseUrlOf = (tag: string) => {
+    const baseUrl = baseUrlOf(tag);
+    const protocol = location.protocol === 'https:' ? 'wss://' : 'ws://';
+
+    return (!baseUrl || baseUrl.startsWith('/'))
+        ? `${protocol}${location.host}${baseUrl}`
+        : protocol + baseUrl.replace(/^https?:\/\//, '');
+};
+
+const withWsToken = (url: string, tag: string, param: string) => {
+    const token = options.wsToken?.(tag);
+    if (token === undefined) return url;
+
+    return `${url}${url.includes('?') ? '&' : '?'}${encodeURIComponent(param)}=${encodeURIComponent(token)}`;
+};
+
+const makeParams = (params: Record<string, any>) =>
+    new HttpParams({
+        fromObject: Object.fromEntries(Object.entries(params).filter(([_, v]) => v !== undefined)),
+    });
+
+const makeQuery = (params: Record<string, any>) => '?' + makeParams(params).toString();
+
+const eventSource = <Message>(url: string, parse: (data: unknown) => Message, withCredentials = true) =>
+    new Observable<Message>(subscriber => {
+        const sse = new EventSource(url, { withCredentials });
+
+        sse.addEventListener('message', ev => subscriber.next(parse(JSON.parse(ev.data))));
+        sse.addEventListener('error', ev => subscriber.error(ev));
+
+        return () => sse.close();
+    });
+
+interface SocketConnection<Client, Server> {
+    messages: Observable<Server>;
+
+    send(message: Client): void;
+
+    close(): void;
+}
+
+const socket = <Client, Server>(
+    url: string,
+    parseClient: (data: unknown) => Client,
+    parseServer: (data: unknown) => Server,
+    protocol?: string[],
+): SocketConnection<Client, Server> => {
+    const subject = webSocket<unknown>({ url, protocol });
+
+    return {
+        messages: subject.pipe(map(parseServer)),
+        send: message => subject.next(parseClient(message)),
+        close: () => subject.complete(),
+    };
+};
@@ -1,46 +1,279 @@
+/** Why an event source or websocket is about to reconnect */
+type ConnectionError =
+    | { kind: 'transport'; event: Event }
+    | { kind: 'parse'; error: unknown };
+
+/**
+ * How event sources and websockets reconnect, the delay doubling from `baseDelayMs` up to
+ * `maxDelayMs` with every attempt.
+ */
+interface ReconnectPolicy {
+    maxRetries: number;
+    baseDelayMs: number;
+    maxDelayMs: number;
+    /** Whether to reconnect after the error, by default only after transport errors */
+    shouldReconnect?(error: ConnectionError): boolean;
+}
+
 interface Options {
     baseUrl: string;
+    /** Base urls of single namespaces, falling back to `baseUrl` */
+    baseUrls: Record<string, string>;
     globalInit: RequestInit;
     unsafe: boolean;
+    /**
+     * Whether concurrent GET and HEAD requests to the same url share a single request,
+     * until it settles
+     */
+    dedupe: boolean;
+    /**
+     * Milliseconds a websocket or event source may take to open before it is closed.
+     * A timed out websocket counts as a failed connection attempt, so it is retried
+     * when auto reconnecting.
+     */
+    connectTimeoutMs?: number;
+
+    /**
+     * Resolves the token of websockets sending one as query parameter, called with the
+     * namespace on every connection attempt. Without a token none is sent.
+     */
+    wsToken?(tag: string): string | undefined;
+
+    /**
+     * Reconnects event sources and websockets without a policy of their own. Without any,
+     * event sources retry by themselves and websockets reconnect 5 times right away after
+     * `autoReconnect`.
+     */
+    reconnect?: ReconnectPolicy;
 
     onHttpError?(res: Response, text: string): MaybePromise;
 
     onNetworkError?(res: Error): MaybePromise;
 
+    /**
+     * Awaited when a request responds with 401, resolving to `true` (e.g. after refreshing
+     * a token) sends the request once more. The retry reuses the headers of the first
+     * attempt, so changed credentials have to be cookies or be set in `fetch`.
+     */
+    onUnauthorized?(res: Response): Promise<boolean>;
+
+    /** Awaited before every request is sent, resolving to a request replaces it */
+    onRequest?(req: Request): MaybePromise<Request | void>;
+
+    /**
+     * Awaited with every response before it is told apart as success or error, resolving to a
+     * response replaces it
+     */
+    onResponse?(res: Response): MaybePromise<Response | void>;
+
+    /**
+     * Applied to every JSON response body before it is validated. `Response.json()`
+     * does not accept a reviver, so setting this reads the body with `res.text()` and
+     * parses it with `JSON.parse`, which is slightly slower for large payloads.
+     */
+    jsonReviver?(this: any, key: string, value: any): any;
+
+    /**
+     * Resolves the url of every request, event source and websocket from its namespace and
+     * path, e.g. to route namespaces to different hosts. Defaults to the base url of the
+     * namespace followed by the path.
+     */
+    resolveUrl?(tag: string, path: string): string;
+
     fetch(req: Request): Promise<Response>
 }
 
 export const options: Options = {
     baseUrl: '',
+    baseUrls: {},
     unsafe: false,
+    dedupe: false,
     globalInit: {},
     fetch: globalThis.fetch.bind(globalThis),
 }
 
+const baseUrlOf = (tag: string) => options.baseUrls[tag] ?? options.baseUrl;
+const urlOf = (tag: string, path: string) =>
+    options.resolveUrl ? options.resolveUrl(tag, path) : baseUrlOf(tag) + path;
+
 type MaybePromise<T = unknown> = T | Promise<T>;
 
-interface Ok<T> {
+const backoff = (policy: ReconnectPolicy, attempt: number) =>
+    Math.min(policy.baseDelayMs * 2 ** attempt, policy.maxDelayMs);
+
+const shouldReconnect = (policy: ReconnectPolicy, error: ConnectionError) => {
+    const decide = policy.shouldReconnect ?? options.reconnect?.shouldReconnect;
+    return decide ? decide(error) : error.kind === 'transport';
+};
+
+interface Ok<T, H = undefined> {
     success: true;
     value: T;
+    response: Response;
+    /** The parsed headers of routes with a response headers schema */
+    headers?: H;
 }
 
-interface Err {
+interface Err<E = unknown> {
     success: false;
     response: Response;
+    /** The parsed body of error responses with a schema */
+    error?: E;
 }
 
-type Result<T> = Ok<T> | Err;
-const ok = <T>(value: T) => ({success: true, value} satisfies Ok<T>);
+type Result<T, E = unknown, H = undefined> = Ok<T, H> | Err<E>;
+const ok = <T, H = undefined>(value: T, response: Response, headers?: H) =>
+    ({success: true, value, response, headers} satisfies Ok<T, H>);
+const okWith = <H = undefined>(response: Response, headers?: H) => <T>(value: T) =>
+    ok(value, response, headers);
+
+type HeaderKind = 'string' | 'number' | 'boolean';
+
+/** Reads the named headers, converting numbers and booleans, absent ones being undefined */
+const readHeaders = (headers: Headers, kinds: Record<string, HeaderKind>) =>
+    Object.fromEntries(Object.entries(kinds).map(([name, kind]) => {
+        const value = headers.get(name);
+        if (value === null) return [name, undefined];
+        return [name, kind === 'number' ? Number(value) : kind === 'boolean' ? value === 'true' : value];
+    }));
 const err = (response: Response) => ({success: false, response} satisfies Err);
 
-const makeQuery = (params: Record<string, any>) =>
-    '?' + new URLSearchParams(Object.fromEntries(Object.entries(params).filter(([_, v]) => v !== undefined)));
+/**
+ * Parses the body of error responses with the schema of their status, `null` skipping
+ * validation. Bodies failing to parse are left out.
+ */
+const errWith = <E>(
+    response: Response,
+    schemas: Record<number, { parse(data: unknown): unknown } | null>,
+): Err<E> | Promise<Err<E>> => {
+    const schema = schemas[response.status];
+    if (schema === undefined) return err(response);
+
+    return readJson(response.clone())
+        .then(body => options.unsafe || !schema ? body : schema.parse(body))
+        .then(
+            body => ({success: false, response, error: {status: response.status, body} as E} satisfies Err<E>),
+            () => err(response),
+        );
+};
+
+const urlEncoded = (params: Record<string, any>) =>
+    new URLSearchParams(Object.fromEntries(Object.entries(params).filter(([_, v]) => v !== undefined)));
+
+type QueryStyle = 'comma' | 'repeat' | 'brackets';
+
+/** Joins arrays with commas, repeats their key or appends `[]` to it, objects are JSON unless in brackets */
+const makeQuery = (params: Record<string, any>, style: QueryStyle = 'comma') => {
+    const query = new URLSearchParams();
+    const append = (key: string, value: any) => {
+        if (value === undefined) return;
+        if (Array.isArray(value) && style !== 'comma') {
+            for (const item of value) append(style === 'brackets' ? `${key}[]` : key, item);
+        } else if (value !== null && typeof value === 'object' && !(value instanceof Date) && !Array.isArray(value)) {
+            if (style !== 'brackets') query.append(key, JSON.stringify(value));
+            else for (const [name, item] of Object.entries(value)) append(`${key}[${name}]`, item);
+        } else {
+            query.append(key, String(value));
+        }
+    };
+    for (const [key, value] of Object.entries(params)) append(key, value);
+    return '?' + query;
+};
+
+/** Appends arrays once per item, objects that are no files as JSON */
+const formData = (fields: Record<string, any>) => {
+    const data = new FormData();
+    for (const [name, value] of Object.entries(fields)) {
+        for (const item of Array.isArray(value) ? value : [value]) {
+            if (item === undefined || item === null) continue;
+            data.append(
+                name,
+                item instanceof Blob ? item : typeof item === 'object' ? JSON.stringify(item) : String(item),
+            );
+        }
+    }
+    return data;
+};
+
+const readJson = (res: Response): Promise<unknown> =>
+    options.jsonReviver
+        ? res.text().then(text => JSON.parse(text, options.jsonReviver))
+        : res.json();
+
+/** Parses the body with the schema of the status, `null` skipping validation */
+const readStatus = <T>(
+    response: Response,
+    schemas: Record<number, { parse(data: unknown): unknown } | null>,
+): Promise<T> =>
+    readJson(response).then(body => {
+        const schema = schemas[response.status];
+        return {status: response.status, body: options.unsafe || !schema ? body : schema.parse(body)} as T;
+    });
+
+const pending = new Map<string, Promise<Result<any, any, any>>>();
+
+/**
+ * Hands every caller of a deduped request its own copy of an unread response, bodies only
+ * being readable once. Parsed bodies are shared as they are.
+ */
+const shared = <T, E, H>(result: Result<T, E, H>): Result<T, E, H> => {
+    if (result.response.bodyUsed) return result;
+
+    const response = result.response.clone();
+    return result.success && (result.value as unknown) === result.response
+        ? { ...result, value: response as T, response }
+        : { ...result, response };
+};
+
+const deduped = <T, E, H>(
+    request: Request,
+    run: () => Promise<Result<T, E, H>>,
+): Promise<Result<T, E, H>> => {
+    if (!options.dedupe || (request.method !== 'GET' && request.method !== 'HEAD')) {
+        return run();
+    }
+
+    const key = `${request.method} ${request.url}`;
+    const existing = pending.get(key);
+    if (existing) return existing.then(shared);
+
+    const promise = run().finally(() => pending.delete(key));
+    pending.set(key, promise);
+    return promise.then(shared);
+};
+
+/** Sends the request, once more if `onUnauthorized` allows it */
+const sent = async (request: Request): Promise<Response> => {
+    if (!options.onUnauthorized) return options.fetch(request);
+
+    // the body of a request can only be sent once
+    const res = await options.fetch(request.clone());
+    if (res.status !== 401 || !(await options.onUnauthorized(res))) return res;
+
+    return options.fetch(request);
+};
+
+const authorized = async (request: Request): Promise<Response> => {
+    if (options.onRequest) request = (await options.onRequest(request)) ?? request;
+
+    const res = await sent(request);
+    return options.onResponse ? ((await options.onResponse(res)) ?? res) : res;
+};
 
 type RepresentsHeader = Headers | [string, string][] | Record<string, string>;
 
-const jsonContentTypeHeader = (
-    firstInit?: RepresentsHeader,
-    secondInit?: RepresentsHeader,
+interface DefaultHeaders {
+    contentType?: string;
+    /** Only applied when neither init sets an `Accept` header */
+    accept?: string;
+    /** The typed headers of the route, overriding both inits */
+    route?: Record<string, string | number | boolean | null | undefined>;
+}
+
+const mergeHeaders = (
+    firstInit: RepresentsHeader | undefined,
+    secondInit: RepresentsHeader | undefined,
+    { contentType, accept, route }: DefaultHeaders,
 ) => {
     const firstHeaders = new Headers(firstInit);
     const secondHeaders = new Headers(secondInit);
@@ -49,12 +282,16 @@ const jsonContentTypeHeader = (
         ...Array.from(secondHeaders.entries()),
     ]);
 
-    headers.set('Content-Type', 'application/json');
+    if (contentType) headers.set('Content-Type', contentType);
+    if (accept && !headers.has('Accept')) headers.set('Accept', accept);
+    for (const [name, value] of Object.entries(route ?? {})) {
+        if (value !== undefined && value !== null) headers.set(name, String(value));
+    }
     return headers;
 };
 
-class PromiseWrapper<T> implements PromiseLike<T> {
-    promise: Promise<Result<T>>;
+class PromiseWrapper<T, E = never, H = undefined> implements PromiseLike<T> {
+    promise: Promise<Result<T, E, H>>;
     end: (() => void) | null = null;
     private isSilent = false;
     private isDebug = false;
@@ -69,6 +306,20 @@ class PromiseWrapper<T> implements PromiseLike<T> {
         return this;
     }
 
+    /** Resolves to the parsed data together with the response it was read from */
+    raw(): Promise<{ response: Response; data: T }> {
+        return this.promise.then(res =>
+            res.success
+                ? { response: res.response, data: res.value }
+                : Promise.reject(res.response));
+    }
+
+    /** Resolves to the parsed headers of routes with a response headers schema */
+    headers(): Promise<H> {
+        return this.promise.then(res =>
+            res.success ? res.headers as H : Promise.reject(res.response));
+    }
+
     failsafe(): Promise<void> {
         return new Promise<void>(r => {
             this.end = r;
@@ -84,7 +335,7 @@ class PromiseWrapper<T> implements PromiseLike<T> {
             .then(onFulfilled, onRejected);
     }
 
-    constructor(promise: Promise<Result<T>>) {
+    constructor(promise: Promise<Result<T, E, H>>) {
         let start = Date.now();
         this.promise = promise;
         promise
@@ -96,6 +347,9 @@ class PromiseWrapper<T> implements PromiseLike<T> {
                         await (this.successCallback &&
                             this.successCallback(res.value));
                     } else {
+                        await (res.error !== undefined && this.errorCallback &&
+                            this.errorCallback(res.error, res.response));
+
                         const text = await res.response.text();
 
                         this.isDebug && console.warn(res.response, text);
@@ -136,6 +390,8 @@ class PromiseWrapper<T> implements PromiseLike<T> {
         | undefined = undefined;
     private networkErrorCallback: ((res: Error) => MaybePromise) | undefined =
         undefined;
+    private errorCallback: ((error: E, res: Response) => MaybePromise) | undefined =
+        undefined;
     private finallyCallback: (() => MaybePromise) | undefined = undefined;
 
     success(callback: (res: T) => MaybePromise) {
@@ -149,6 +405,12 @@ class PromiseWrapper<T> implements PromiseLike<T> {
         return this;
     }
 
+    /** Called with the parsed body of error responses with a schema, before `httpFailure` */
+    error(callback: (error: E, res: Response) => MaybePromise) {
+        this.errorCallback = callback;
+        return this;
+    }
+
     httpFailure(callback: (res: Response, text: string) => MaybePromise) {
         this.networkFailureCallback = callback;
         return this;
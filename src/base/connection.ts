@@ -0,0 +1,6 @@
+export type ConnectionState =
+    | { status: 'connecting' }
+    | { status: 'open' }
+    | { status: 'reconnecting'; attempt: number }
+    | { status: 'closed'; reason: string };
+
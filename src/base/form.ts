@@ -0,0 +1,13 @@
+export interface FormField {
+    name: string;
+    type: 'string' | 'number' | 'boolean' | 'array' | 'object' | 'enum' | 'unknown';
+    required: boolean;
+    description?: string;
+    options?: unknown[];
+    /** The minimum value, length or item count */
+    min?: number;
+    /** The maximum value, length or item count */
+    max?: number;
+    fields?: FormField[];
+}
+
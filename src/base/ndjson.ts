@@ -0,0 +1,28 @@
+
+/** Reads a newline delimited JSON response body, parsing every non-empty line */
+async function* readNdjson<T>(response: Response, parse: (data: unknown) => T): AsyncGenerator<T> {
+    if (!response.body) return;
+
+    const reader = response.body.getReader();
+    const decoder = new TextDecoder();
+    let buffer = '';
+
+    try {
+        while (true) {
+            const { done, value } = await reader.read();
+            buffer += decoder.decode(value, { stream: !done });
+
+            const lines = buffer.split('\n');
+            buffer = done ? '' : lines.pop()!;
+
+            for (const line of lines) {
+                if (line.trim()) yield parse(JSON.parse(line, options.jsonReviver));
+            }
+
+            if (done) return;
+        }
+    } finally {
+        // stops the download when the consumer breaks out early
+        reader.cancel().catch(() => {});
+    }
+}
@@ -0,0 +1,38 @@
+const isFunction = (v: unknown) => typeof v === 'function';
+const optionalFunction = (name: string) =>
+    z.custom<(...args: any[]) => any>(v => v === undefined || isFunction(v), `options.${name} must be a function`);
+
+export const optionsSchema = z.object({
+    baseUrl: z.string({ invalid_type_error: 'options.baseUrl must be a string' }),
+    baseUrls: z.record(z.string({ invalid_type_error: 'options.baseUrls must map tags to strings' })),
+    globalInit: z.object({}).passthrough(),
+    unsafe: z.boolean(),
+    dedupe: z.boolean(),
+    connectTimeoutMs: z.number().nonnegative().optional(),
+    wsToken: optionalFunction('wsToken'),
+    reconnect: z
+        .object({
+            maxRetries: z.number().int().nonnegative(),
+            baseDelayMs: z.number().nonnegative(),
+            maxDelayMs: z.number().nonnegative(),
+            shouldReconnect: optionalFunction('reconnect.shouldReconnect'),
+        })
+        .optional(),
+    onHttpError: optionalFunction('onHttpError'),
+    onNetworkError: optionalFunction('onNetworkError'),
+    onUnauthorized: optionalFunction('onUnauthorized'),
+    onRequest: optionalFunction('onRequest'),
+    onResponse: optionalFunction('onResponse'),
+    jsonReviver: optionalFunction('jsonReviver'),
+    resolveUrl: optionalFunction('resolveUrl'),
+    fetch: z.custom<Options['fetch']>(
+        isFunction,
+        'options.fetch must be a function, provide one where there is no global fetch',
+    ),
+});
+
+/** Validates and applies the given options, throwing a `ZodError` describing invalid ones */
+export function configure(next: Partial<Options>) {
+    Object.assign(options, optionsSchema.parse({ ...options, ...next }));
+}
+
@@ -0,0 +1,51 @@
+
+// The hooks close their connection when unmounting, so the double mount of the
+// StrictMode opens a fresh connection instead of leaking the first one.
+
+const useSSE = <Message>(connect: () => SSE<Message>, deps: unknown[]) => {
+    const [message, setMessage] = useState<Message>();
+    const [state, setState] = useState<ConnectionState>({ status: 'connecting' });
+
+    useEffect(() => {
+        const sse = connect();
+
+        setState(sse.state);
+        const unsubscribe = sse.subscribe(setState);
+        sse.onMessage(data => setMessage(() => data));
+
+        return () => {
+            sse.close();
+            unsubscribe();
+        };
+    }, deps);
+
+    return { message, open: state.status === 'open', state };
+};
+
+const useWebsocket = <Client, Server>(
+    connect: () => WebsocketWrapper<Client, Server>,
+    deps: unknown[],
+) => {
+    const [message, setMessage] = useState<Server>();
+    const [open, setOpen] = useState(false);
+    const ref = useRef<WebsocketWrapper<Client, Server>>();
+
+    useEffect(() => {
+        const ws = connect()
+            .connect(() => setOpen(true))
+            .disconnect(() => setOpen(false))
+            .message(data => setMessage(() => data));
+
+        ref.current = ws;
+
+        return () => {
+            ws.destroy();
+            ref.current = undefined;
+            setOpen(false);
+        };
+    }, deps);
+
+    const send = useCallback((message: Client) => ref.current?.send(message), []);
+
+    return { message, open, send };
+};
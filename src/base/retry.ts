@@ -0,0 +1,30 @@
+
+/** Waits before the `attempt`th retry, doubling the delay with every attempt */
+const backoff = (attempt: number, signal: AbortSignal): Promise<void> =>
+    new Promise((resolve, reject) => {
+        if (signal.aborted) return reject(signal.reason);
+
+        const timer = setTimeout(resolve, 100 * 2 ** attempt);
+        signal.addEventListener('abort', () => {
+            clearTimeout(timer);
+            reject(signal.reason);
+        }, { once: true });
+    });
+
+const retried = (
+    request: Request,
+    retries: number,
+    run: (request: Request) => Promise<Response>,
+    attempt: number = 0,
+): Promise<Response> => {
+    const retry = () => backoff(attempt, request.signal)
+        .then(() => retried(request, retries, run, attempt + 1));
+
+    return run(request.clone()).then(
+        (res) => res.status >= 500 && attempt < retries ? retry() : res,
+        (e) => {
+            if (request.signal.aborted || attempt >= retries) throw e;
+            return retry();
+        },
+    );
+};
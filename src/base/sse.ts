@@ -1,42 +1,158 @@
+/** The data of the messages of a named event */
+type EventData<Message, Event> = Message extends { event: Event; data: infer Data }
+    ? Data
+    : never;
+
 class SSE<Message> {
     public open = false;
+    public state: ConnectionState = { status: 'connecting' };
+    private stateListeners = new Set<(state: ConnectionState) => void>();
+
     public sse: EventSource;
-    private messageHandler: ((ev: MessageEvent) => void) | null = null;
+    private closed = false;
+    private messageHandler: ((message: Message) => void) | null = null;
+    private eventHandlers = new Map<string, (data: unknown) => void>();
     private closeHandler: ((ev: Event) => void) | null = null;
+    private reconnectTries = 0;
+    private reconnectTimer: ReturnType<typeof setTimeout> | undefined;
 
     constructor(
         private init: () => EventSource,
-        private parse: (data: unknown) => Message,
+        private parse: (data: unknown, event: string) => Message,
+        private events: string[] = ['message'],
+        private policy?: ReconnectPolicy,
     ) {
-        this.sse = init();
-        this.sse.addEventListener('open', () => {
+        this.sse = this.connect();
+    }
+
+    /**
+     * Schedules the next connection attempt if there is a policy allowing it, without one the
+     * event source retries by itself
+     */
+    private retry(error: ConnectionError) {
+        const policy = this.policy ?? options.reconnect;
+        if (!policy || this.reconnectTries >= policy.maxRetries || !shouldReconnect(policy, error)) {
+            return false;
+        }
+
+        this.open = false;
+        this.sse.close();
+        const delay = backoff(policy, this.reconnectTries++);
+        this.setState({ status: 'reconnecting', attempt: this.reconnectTries });
+        this.reconnectTimer = setTimeout(() => {
+            if (this.closed) return;
+            this.sse = this.connect();
+        }, delay);
+        return true;
+    }
+
+    private connect() {
+        const sse = this.init();
+        sse.addEventListener('open', () => {
+            if (this.sse !== sse) return;
             this.open = true;
+            this.reconnectTries = 0;
+            this.setState({ status: 'open' });
         });
-        this.sse.addEventListener('error', (ev: unknown) => {
+        sse.addEventListener('error', (ev: unknown) => {
+            if (this.sse !== sse) return;
             this.open = false;
+            if (this.retry({ kind: 'transport', event: ev as Event })) return;
+            // without a policy the event source retries by itself unless it closed
+            this.setState(sse.readyState === EventSource.CLOSED
+                ? { status: 'closed', reason: '' }
+                : { status: 'connecting' });
             this.closeHandler && this.closeHandler(ev as Event);
         });
-        this.sse.addEventListener('message', (ev: unknown) => {
-            this.messageHandler && this.messageHandler(ev as MessageEvent);
-        });
+        for (const event of this.events) {
+            sse.addEventListener(event, (ev: unknown) => {
+                if (this.sse !== sse) return;
+                this.dispatch(ev as MessageEvent);
+            });
+        }
+
+        if (options.connectTimeoutMs !== undefined) {
+            setTimeout(() => {
+                if (this.sse !== sse || this.open || this.closed) return;
+
+                // stops the event source from retrying by itself
+                sse.close();
+                const ev = new Event('timeout');
+                if (this.retry({ kind: 'transport', event: ev })) return;
+                this.setState({ status: 'closed', reason: 'timeout' });
+                this.closeHandler && this.closeHandler(ev);
+            }, options.connectTimeoutMs);
+        }
+
+        return sse;
+    }
+
+    private dispatch(ev: MessageEvent) {
+        const eventHandler = this.eventHandlers.get(ev.type);
+        if (!this.messageHandler && !eventHandler) return;
+
+        let message: Message;
+        try {
+            message = this.parse(JSON.parse(ev.data!), ev.type);
+        } catch (error) {
+            if (this.retry({ kind: 'parse', error })) return;
+            throw error;
+        }
+        this.messageHandler && this.messageHandler(message);
+        eventHandler && eventHandler((message as { data: unknown }).data);
     }
 
     onMessage(handler: (this: SSE<Message>, data: Message) => void) {
-        this.messageHandler = (ev) => {
-            handler.call(this, this.parse(JSON.parse(ev.data!)));
+        this.messageHandler = (message) => {
+            handler.call(this, message);
         }
     }
 
+    /** Listens to the messages of a single named event */
+    on<Event extends Message extends { event: infer Name } ? Name & string : never>(
+        event: Event,
+        handler: (this: SSE<Message>, data: EventData<Message, Event>) => void,
+    ) {
+        this.eventHandlers.set(event, (data) => {
+            handler.call(this, data as EventData<Message, Event>);
+        });
+    }
+
     onClose(handler: (this: SSE<Message>, reason: Event) => void) {
         this.closeHandler = (ev) => {
             handler.call(this, ev);
         }
     }
 
+    private setState(state: ConnectionState) {
+        this.state = state;
+        this.stateListeners.forEach(listener => listener(state));
+    }
+
+    /** Calls `listener` on every change of the connection state, returns the unsubscribe function */
+    subscribe(listener: (state: ConnectionState) => void) {
+        this.stateListeners.add(listener);
+        return () => {
+            this.stateListeners.delete(listener);
+        };
+    }
+
     reconnect() {
+        if (this.closed) return;
+        clearTimeout(this.reconnectTimer);
         if (!this.open) {
-            this.sse = this.init();
+            this.sse.close();
+            this.sse = this.connect();
         }
     }
+
+    /** Closes the event source for good, including a pending reconnect */
+    close() {
+        this.closed = true;
+        clearTimeout(this.reconnectTimer);
+        this.sse.close();
+        this.open = false;
+        this.setState({ status: 'closed', reason: '' });
+    }
 }
 
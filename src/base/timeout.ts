@@ -0,0 +1,25 @@
+
+/** The reason requests are aborted with once their timeout elapsed */
+export class TimeoutError extends Error {
+    constructor(readonly timeoutMs: number) {
+        super(`Request timed out after ${timeoutMs}ms`);
+        this.name = 'TimeoutError';
+    }
+}
+
+const timed = (
+    request: Request,
+    timeoutMs: number | undefined,
+    run: (request: Request) => Promise<Response>,
+): Promise<Response> => {
+    if (timeoutMs === undefined) return run(request);
+
+    const controller = new AbortController();
+    // an aborted signal does not fire anymore
+    if (request.signal.aborted) controller.abort(request.signal.reason);
+    request.signal.addEventListener('abort', () => controller.abort(request.signal.reason));
+    const timer = setTimeout(() => controller.abort(new TimeoutError(timeoutMs)), timeoutMs);
+
+    return run(new Request(request, { signal: controller.signal }))
+        .finally(() => clearTimeout(timer));
+};
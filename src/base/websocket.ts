@@ -4,12 +4,24 @@ const hasId = (v: unknown): v is { id: number } => {
     return v instanceof Object && 'id' in v && typeof v.id === 'number';
 };
 
+const withWsToken = (url: string, tag: string, param: string) => {
+    const token = options.wsToken?.(tag);
+    if (token === undefined) return url;
+
+    return `${url}${url.includes('?') ? '&' : '?'}${encodeURIComponent(param)}=${encodeURIComponent(token)}`;
+};
+
 class WebsocketWrapper<Client, Server> {
     public open = false;
+    public state: ConnectionState = { status: 'connecting' };
+    private stateListeners = new Set<(state: ConnectionState) => void>();
 
     private disconnectReason: string | undefined;
+    /** Whether any attempt opened, `onConnect` only being called for the first */
+    private connected = false;
     public reconnect = false;
     private reconnectTries = 0;
+    private reconnectTimer: ReturnType<typeof setTimeout> | undefined;
 
     private pending = new Map<number, (message: Server) => MaybePromiseWs>();
     public ws: WebSocket | undefined;
@@ -18,27 +30,68 @@ class WebsocketWrapper<Client, Server> {
         private websocketConstructor: () => WebSocket,
         private parseClient: (data: unknown) => Client,
         private parseServer: (data: unknown) => Server,
+        private policy?: ReconnectPolicy,
     ) {
+        this.reconnect = (policy ?? options.reconnect) !== undefined;
         this.initWebsocket();
     }
 
+    /** Schedules the next connection attempt if the policy allows it */
+    private retry(error: ConnectionError) {
+        const policy = this.policy ?? options.reconnect ?? { maxRetries: 5, baseDelayMs: 0, maxDelayMs: 0 };
+        if (!this.reconnect || this.reconnectTries >= policy.maxRetries || !shouldReconnect(policy, error)) {
+            return false;
+        }
+
+        // detaches the handlers of the failed websocket
+        const ws = this.ws;
+        this.ws = undefined;
+        ws?.close();
+
+        this.open = false;
+        const delay = backoff(policy, this.reconnectTries++);
+        this.setState({ status: 'reconnecting', attempt: this.reconnectTries });
+        this.reconnectTimer = setTimeout(() => this.initWebsocket(), delay);
+        return true;
+    }
+
     private initWebsocket() {
         if (this.ws) {
             this.ws.close();
         }
 
         this.open = false;
+        if (!this.reconnectTries) this.setState({ status: 'connecting' });
 
         const ws = (this.ws = this.websocketConstructor());
 
+        if (options.connectTimeoutMs !== undefined) {
+            setTimeout(() => {
+                if (this.ws !== ws || this.open) return;
+
+                if (!this.retry({ kind: 'transport', event: new Event('timeout') })) {
+                    // detaches the handlers of the timed out websocket
+                    this.ws = undefined;
+                    ws.close();
+
+                    this.setState({ status: 'closed', reason: 'timeout' });
+                    this.onFailure && this.onFailure(new Event('timeout'));
+                }
+            }, options.connectTimeoutMs);
+        }
+
         ws.onopen = () => {
             // reconnected
             if (this.ws !== ws) return;
 
             this.open = true;
             this.disconnectReason = undefined;
+            this.setState({ status: 'open' });
 
-            if (!this.reconnectTries) this.onConnect && this.onConnect();
+            if (!this.connected) {
+                this.connected = true;
+                this.onConnect && this.onConnect();
+            }
 
             this.reconnectTries = 0;
         };
@@ -49,14 +102,15 @@ class WebsocketWrapper<Client, Server> {
 
             if (!this.open) {
                 if (this.reconnectTries) {
-                    if (this.reconnect && this.reconnectTries++ < 5) {
-                        this.initWebsocket();
+                    if (this.retry({ kind: 'transport', event: ev })) {
                         return;
                     } else {
+                        this.setState({ status: 'closed', reason: this.disconnectReason ?? '' });
                         this.onDisconnect &&
                         this.onDisconnect(this.disconnectReason ?? '');
                     }
                 } else {
+                    this.setState({ status: 'closed', reason: '' });
                     this.onFailure && this.onFailure(ev);
                 }
             }
@@ -66,13 +120,13 @@ class WebsocketWrapper<Client, Server> {
             // reconnected
             if (this.ws !== ws) return;
 
-            if (this.reconnect && this.reconnectTries++ < 5) {
-                this.disconnectReason = ev.reason;
-                this.initWebsocket();
+            this.disconnectReason = ev.reason;
+            if (this.retry({ kind: 'transport', event: ev })) {
                 return;
             }
 
             this.open = false;
+            this.setState({ status: 'closed', reason: ev.reason ?? '' });
             this.onDisconnect && this.onDisconnect(ev.reason ?? '');
         };
 
@@ -81,7 +135,13 @@ class WebsocketWrapper<Client, Server> {
             if (this.ws !== ws) return;
 
             if (typeof ev.data === 'string' && this.onMessage) {
-                const data: Server = this.parseServer(JSON.parse(ev.data));
+                let data: Server;
+                try {
+                    data = this.parseServer(JSON.parse(ev.data));
+                } catch (error) {
+                    if (this.retry({ kind: 'parse', error })) return;
+                    throw error;
+                }
 
                 if (hasId(data)) {
                     const resolve = this.pending.get(data.id);
@@ -138,6 +198,20 @@ class WebsocketWrapper<Client, Server> {
         return promise;
     }
 
+    private setState(state: ConnectionState) {
+        this.state = state;
+        this.stateListeners.forEach(listener => listener(state));
+    }
+
+    /** Calls `listener` on every change of the connection state, returns the unsubscribe function */
+    subscribe(listener: (state: ConnectionState) => void) {
+        this.stateListeners.add(listener);
+        return () => {
+            this.stateListeners.delete(listener);
+        };
+    }
+
+    /** Reconnects by the policy of the route or `options.reconnect`, else 5 times right away */
     autoReconnect() {
         this.reconnect = true;
         return this;
@@ -164,10 +238,12 @@ class WebsocketWrapper<Client, Server> {
     }
 
     destroy() {
+        clearTimeout(this.reconnectTimer);
         this.ws?.close();
         // stops all event handlers
         this.open = false;
         this.ws = undefined;
+        this.setState({ status: 'closed', reason: '' });
     }
 }
 
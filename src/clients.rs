@@ -0,0 +1,41 @@
+use crate::{
+    encoding::{BodyEncoding, QueryStyle},
+    gen::first_upper,
+    types::{Kind, RequestInfo},
+};
+
+/// What of the route the dart and python clients can not call yet, e.g.
+/// `websockets`, the route being generated as a method throwing instead.
+pub(crate) fn unimplemented(v: &RequestInfo) -> Option<&'static str> {
+    if v.query_style
+        .is_some_and(|style| style != QueryStyle::Comma)
+    {
+        return Some("query styles");
+    }
+    if !v.req_body.is_none() && v.body_encoding != BodyEncoding::Json {
+        return Some("url encoded and multipart bodies");
+    }
+
+    match v.res_body {
+        Kind::Websocket { .. } => Some("websockets"),
+        Kind::SSE(_) | Kind::SSEEvents(_) => Some("event sources"),
+        Kind::StatusUnion(_) => Some("status unions"),
+        Kind::Ndjson(_) => Some("NDJSON streams"),
+        _ => None,
+    }
+}
+
+/// The identifier, suffixed with `_` if it is one of the keywords.
+pub(crate) fn ident(name: &str, keywords: &[&str]) -> String {
+    match keywords.contains(&name) {
+        true => format!("{name}_"),
+        false => name.to_string(),
+    }
+}
+
+pub(crate) fn pascal_case(s: &str) -> String {
+    s.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(first_upper)
+        .collect()
+}
@@ -0,0 +1,204 @@
+use std::sync::Arc;
+
+use schemars_to_zod::Config;
+
+use crate::{
+    naming::{DefaultNames, NameStrategy},
+    QueryStyle,
+};
+
+/// The line ending used in the generated file.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum Eol {
+    #[default]
+    Lf,
+    Crlf,
+}
+
+impl Eol {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Eol::Lf => "\n",
+            Eol::Crlf => "\r\n",
+        }
+    }
+}
+
+/// The kind of client that is generated.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum Target {
+    /// Free functions based on `fetch`, returning promises.
+    #[default]
+    Fetch,
+    /// Injectable services based on the Angular `HttpClient`, returning
+    /// observables.
+    Angular,
+}
+
+/// How the routes are exported from the generated module.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum Layout {
+    /// Nested in `export namespace client`, one namespace per tag segment,
+    /// e.g. `client.users.getList`.
+    #[default]
+    Namespace,
+    /// Named exports at module level, prefixed with their tag, e.g.
+    /// `usersGetList`, for tree-shaking bundlers.
+    Flat,
+    /// Directly in `export namespace client`, prefixed with their tag as with
+    /// `Flat`, e.g. `client.usersGetList`.
+    Prefixed,
+}
+
+#[derive(Debug, Clone)]
+pub struct GenerateConfig {
+    /// The kind of client that is generated.
+    pub target: Target,
+    /// How the routes are exported from the generated module.
+    pub layout: Layout,
+    /// The line ending of the generated file.
+    pub eol: Eol,
+    /// Whether the generated file ends with a line ending.
+    pub final_newline: bool,
+    /// Whether deprecation notes include the call signature of the replacement
+    /// route.
+    pub verbose_deprecation: bool,
+    /// Whether generation fails when schema information (bounds, defaults,
+    /// examples, descriptions) would be silently dropped in the zod
+    /// translation, or when a deprecation note refers to a route which is not
+    /// generated.
+    pub strict: bool,
+    /// Whether strings with a `uuid`, `email`, `uri` or `url` format are
+    /// validated with `z.string().uuid()`, `.email()` or `.url()`.
+    pub enforce_formats: bool,
+    /// Whether in-flight requests are tracked so they can be cancelled at once
+    /// with `client.abortAll()`.
+    pub abort_all: bool,
+    /// Whether the base classes and helpers (`PromiseWrapper`, `options`, `ok`,
+    /// `err`, ...) are emitted. When disabled, the symbols listed by
+    /// `base_symbols` have to be provided by another module.
+    pub include_base: bool,
+    /// The module the base symbols are imported from when `include_base` is
+    /// disabled.
+    pub base_import: Option<String>,
+    /// Whether schema defaults of request bodies are emitted as zod
+    /// `.default(...)`, making the fields optional and completing them
+    /// client-side.
+    pub apply_body_defaults: bool,
+    /// Whether a `latest` namespace re-exporting only the routes that are not
+    /// deprecated is emitted.
+    pub emit_latest: bool,
+    /// Whether a `...FormMeta` array describing the fields of object request
+    /// bodies is emitted, for building forms without inspecting zod schemas.
+    pub emit_form_meta: bool,
+    /// Whether every fetch function exposes a `.request(...)` building the
+    /// `Request` it sends, e.g. for caching in service workers.
+    pub emit_request_builders: bool,
+    /// Whether the allowed values of enum fields of request params and bodies
+    /// are exported as `...Values` arrays.
+    pub emit_enum_values: bool,
+    /// Whether string enums of request params, bodies and responses are
+    /// exported as const objects naming their values, e.g.
+    /// `GetUserResStatus.Active`.
+    pub emit_enum_objects: bool,
+    /// Whether the generated functions annotate their return type instead of
+    /// relying on inference.
+    pub explicit_return_types: bool,
+    /// Whether an `optionsSchema` and a `configure` function validating the
+    /// client options with it are emitted.
+    pub validate_options: bool,
+    /// Whether routes with `Stability::Internal` are left out of the generated
+    /// client.
+    pub exclude_internal: bool,
+    /// Whether `OPTIONS`, `TRACE` and `CONNECT` routes are left out of the
+    /// generated client.
+    pub exclude_meta_methods: bool,
+    /// Whether routes with a JSON response send `Accept: application/json`
+    /// unless the init or global init set one, so servers negotiating the
+    /// content type don't answer with an HTML page.
+    pub accept_json: bool,
+    /// Whether each namespace is preceded by a doc comment listing the method
+    /// and path of its routes.
+    pub emit_tag_docs: bool,
+    /// The base configuration of the zod translation, adjusted per slot (e.g.
+    /// dates are only coerced in responses).
+    pub zod: Config,
+    /// The line width of the formatted output.
+    pub line_width: u32,
+    /// The indent width of the formatted output.
+    pub indent_width: u8,
+    /// Whether request types use `z.input` and response types `z.output`,
+    /// instead of `z.infer` for both.
+    pub io_types: bool,
+    /// Chooses the names of the generated functions, see [`DefaultNames`] for
+    /// the path prefixes stripped by default.
+    pub names: Arc<dyn NameStrategy>,
+    /// Whether routes of the same tag sharing a function name are suffixed
+    /// with a number instead of failing the generation.
+    pub disambiguate_names: bool,
+    /// Whether requests and responses are validated with zod. Without it, plain
+    /// TypeScript types are emitted and zod is not imported.
+    pub runtime_validation: bool,
+    /// How arrays and objects of query params are serialized, comma-joined by
+    /// default. Overridable per route, not supported by the angular target.
+    pub query_style: QueryStyle,
+    /// Whether schemas defined identically by several routes are emitted once
+    /// as a shared constant, shrinking APIs with many routes of the same types.
+    pub share_schemas: bool,
+    /// The module zod is imported from, e.g. `zod/v4`.
+    pub zod_module: String,
+    /// The symbol imported from `zod_module`, bound to `z`.
+    pub zod_symbol: String,
+    /// The module the mock generated by `generate_mock_with` imports the
+    /// client from.
+    pub mock_import: String,
+}
+
+impl Default for GenerateConfig {
+    fn default() -> Self {
+        GenerateConfig {
+            target: Target::Fetch,
+            layout: Layout::Namespace,
+            eol: Eol::Lf,
+            final_newline: true,
+            verbose_deprecation: false,
+            strict: false,
+            enforce_formats: false,
+            abort_all: false,
+            include_base: true,
+            base_import: None,
+            apply_body_defaults: false,
+            emit_latest: false,
+            emit_form_meta: false,
+            emit_request_builders: false,
+            emit_enum_values: false,
+            emit_enum_objects: false,
+            explicit_return_types: true,
+            validate_options: false,
+            exclude_internal: false,
+            exclude_meta_methods: true,
+            accept_json: true,
+            emit_tag_docs: true,
+            zod: Config {
+                use_coerce_date: false,
+                array_wrapper: false,
+                explicit_min_max: false,
+                add_descriptions: true,
+                union_first: true,
+                add_default: false,
+                ignore_undefined: false,
+            },
+            line_width: 90,
+            indent_width: 4,
+            io_types: true,
+            names: Arc::new(DefaultNames::default()),
+            disambiguate_names: false,
+            runtime_validation: true,
+            query_style: QueryStyle::Comma,
+            share_schemas: false,
+            zod_module: "zod".to_string(),
+            zod_symbol: "z".to_string(),
+            mock_import: "./client".to_string(),
+        }
+    }
+}
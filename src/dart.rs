@@ -0,0 +1,732 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error::Error as StdError,
+    fmt::{Display, Formatter},
+};
+
+use schemars::schema::{InstanceType, Schema, SchemaObject, SingleOrVec};
+
+use crate::{
+    clients::{self, ident, pascal_case},
+    config::GenerateConfig,
+    deprecated::Deprecated,
+    error::{GenError, Slot},
+    gen::{finish, first_upper, included},
+    naming,
+    nullable::{normalize_request, unwrap_nullable},
+    stability::Stability,
+    status,
+    types::{Kind, RequestInfo, Requests},
+};
+
+const CLIENT: &str = r#"import 'dart:convert';
+import 'dart:typed_data';
+
+import 'package:http/http.dart' as http;
+
+/// Thrown for responses with a status outside of 2xx.
+class ApiException implements Exception {
+  final http.Response response;
+
+  const ApiException(this.response);
+
+  @override
+  String toString() => 'ApiException(${response.statusCode}): ${response.body}';
+}
+
+class ApiClient {
+  final Uri baseUrl;
+
+  /// Base urls of single tags, falling back to `baseUrl`.
+  final Map<String, Uri> baseUrls;
+
+  /// Sent with every request.
+  final Map<String, String> headers;
+
+  final http.Client _http;
+
+  ApiClient(this.baseUrl, {this.baseUrls = const {}, this.headers = const {}, http.Client? client})
+      : _http = client ?? http.Client();
+
+  void close() => _http.close();
+
+  Future<http.Response> send(
+    String tag,
+    String method,
+    String path, {
+    Map<String, dynamic>? query,
+    Map<String, dynamic>? headers,
+    Object? body,
+    bool json = true,
+    Duration? timeout,
+  }) async {
+    final base = baseUrls[tag] ?? baseUrl;
+    final uri = base.replace(
+      path: base.path.replaceAll(RegExp(r'/$'), '') + path,
+      queryParameters: query == null ? null : _strings(query),
+    );
+
+    final request = http.Request(method, uri)..headers.addAll(this.headers);
+    for (final header in _strings(headers ?? {}).entries) {
+      request.headers[header.key] = '${header.value}';
+    }
+    if (body != null) {
+      request.headers['Content-Type'] = json ? 'application/json' : 'text/plain';
+      request.body = json ? jsonEncode(body) : '$body';
+    }
+
+    final sent = _http.send(request);
+    final res = await http.Response.fromStream(await (timeout == null ? sent : sent.timeout(timeout)));
+    if (res.statusCode < 200 || res.statusCode >= 300) throw ApiException(res);
+    return res;
+  }
+
+  /// Leaves out nulls, lists are sent once per item.
+  static Map<String, dynamic> _strings(Map<String, dynamic> values) => {
+        for (final entry in values.entries)
+          if (entry.value != null)
+            entry.key: entry.value is List ? [for (final v in entry.value) '$v'] : '${entry.value}',
+      };
+}
+"#;
+
+const KEYWORDS: &[&str] = &[
+    "assert", "break", "case", "catch", "class", "const", "continue", "default", "do",
+    "else", "enum", "extends", "false", "final", "finally", "for", "if", "in", "is",
+    "new", "null", "rethrow", "return", "super", "switch", "this", "throw", "true",
+    "try", "var", "void", "while", "with",
+];
+
+pub fn generate_dart(requests: Requests) -> Result<String, Box<dyn StdError>> {
+    generate_dart_with(requests, &GenerateConfig::default())
+}
+
+/// Generates a Dart client based on the `http` package, an extension on
+/// `ApiClient` giving access to a class of methods per tag, and a class with
+/// `fromJson` and `toJson` per object schema. The config only names the
+/// methods, sets the line endings and leaves out internal routes. Routes the
+/// client can not call yet, e.g. websockets, are generated as methods throwing
+/// an `UnimplementedError`.
+pub fn generate_dart_with(
+    Requests {
+        mut requests,
+        tag_base_urls,
+    }: Requests,
+    config: &GenerateConfig,
+) -> Result<String, Box<dyn StdError>> {
+    requests.retain(|v| included(v, config));
+    requests.iter_mut().for_each(normalize_request);
+    if config.exclude_internal {
+        requests.retain(|v| v.stability != Stability::Internal);
+    }
+
+    let names = naming::resolve(&requests, config)?;
+
+    let mut classes = Classes::default();
+    let mut tags = BTreeMap::<&str, Vec<String>>::new();
+    for (v, name) in requests.iter().zip(&names) {
+        let method = method(v, name, &mut classes)?;
+        tags.entry(&v.tag).or_default().push(method);
+    }
+
+    let mut out = CLIENT.to_string();
+
+    out.push_str("\nextension Apis on ApiClient {\n");
+    for tag in tags.keys() {
+        out.push_str(&format!(
+            "  {}Api get {} => {0}Api(this);\n",
+            pascal_case(tag),
+            ident(&camel_case(tag), KEYWORDS)
+        ));
+    }
+    out.push_str("}\n");
+
+    if !tag_base_urls.is_empty() {
+        out.push_str("\n/// The base urls of the tags, e.g. for `ApiClient.baseUrls`.\n");
+        out.push_str("final tagBaseUrls = {\n");
+        for (tag, url) in &tag_base_urls {
+            out.push_str(&format!("  {}: Uri.parse({}),\n", string(tag), string(url)));
+        }
+        out.push_str("};\n");
+    }
+
+    for (tag, methods) in &tags {
+        out.push_str(&format!(
+            "\nclass {}Api {{\n  final ApiClient _client;\n\n  {0}Api(this._client);\n",
+            pascal_case(tag)
+        ));
+        for method in methods {
+            out.push('\n');
+            out.push_str(method);
+        }
+        out.push_str("}\n");
+    }
+
+    for class in &classes.classes {
+        out.push('\n');
+        out.push_str(class);
+    }
+
+    Ok(finish(out, config))
+}
+
+/// Generates the method of a route, adding the classes of its schemas.
+fn method(
+    v: &RequestInfo,
+    name: &str,
+    classes: &mut Classes,
+) -> Result<String, Box<dyn StdError>> {
+    if let Some(what) = clients::unimplemented(v) {
+        return Ok(stub(v, name, what));
+    }
+
+    let struct_name = first_upper(name);
+    let mut args = Vec::new();
+    let mut send = vec![string(&v.tag), string(v.method.as_str()), path(v)];
+    let mut body = String::new();
+
+    match &v.path_params {
+        Kind::None => {},
+        Kind::Any => args.push("Map<String, String> pathParams".to_string()),
+        Kind::Schema(schema) => {
+            let ty = classes.root(&schema.schema, &format!("{struct_name}PathParams"));
+            args.push(format!("{ty} pathParams"));
+            body.push_str(&format!(
+                "    final path = {};\n",
+                encode(&ty, "pathParams")
+            ));
+        },
+        kind => return Err(GenError::unexpected_kind(v, Slot::PathParams, kind).into()),
+    }
+
+    match &v.req_body {
+        Kind::None => {},
+        Kind::Any => {
+            args.push("Object? req".to_string());
+            send.push("body: req".to_string());
+        },
+        Kind::Text => {
+            args.push("String req".to_string());
+            send.push("body: req".to_string());
+            send.push("json: false".to_string());
+        },
+        Kind::Schema(schema) => {
+            let ty = classes.root(&schema.schema, &format!("{struct_name}Req"));
+            args.push(format!("{ty} req"));
+            send.push(format!("body: {}", encode(&ty, "req")));
+        },
+        kind => return Err(GenError::unexpected_kind(v, Slot::Body, kind).into()),
+    }
+
+    for (kind, slot, suffix, arg) in [
+        (&v.req_params, Slot::Params, "Params", "params"),
+        (&v.req_headers, Slot::Headers, "Headers", "headers"),
+    ] {
+        let target = match arg {
+            "params" => "query",
+            _ => "headers",
+        };
+        match kind {
+            Kind::None => {},
+            Kind::Any => {
+                args.push(format!("Map<String, String> {arg}"));
+                send.push(format!("{target}: {arg}"));
+            },
+            Kind::Schema(schema) => {
+                let ty = classes.root(&schema.schema, &format!("{struct_name}{suffix}"));
+                args.push(format!("{ty} {arg}"));
+                send.push(format!("{target}: {}", encode(&ty, arg)));
+            },
+            kind => return Err(GenError::unexpected_kind(v, slot, kind).into()),
+        }
+    }
+
+    if let Some(timeout_ms) = v.timeout_ms {
+        send.push(format!(
+            "timeout: const Duration(milliseconds: {timeout_ms})"
+        ));
+    }
+
+    let (returns, result) = match &v.res_body {
+        Kind::None if v.no_content => ("void".to_string(), None),
+        Kind::None => ("http.Response".to_string(), Some("res".to_string())),
+        Kind::Any => (
+            "dynamic".to_string(),
+            Some("jsonDecode(res.body)".to_string()),
+        ),
+        Kind::Text | Kind::TextSchema(_) =>
+            ("String".to_string(), Some("res.body".to_string())),
+        Kind::Binary => ("Uint8List".to_string(), Some("res.bodyBytes".to_string())),
+        Kind::Schema(schema) => {
+            let ty = classes.root(&schema.schema, &format!("{struct_name}Res"));
+            let result = decode(&ty, "jsonDecode(res.body)");
+            (ty.to_string(), Some(result))
+        },
+        Kind::Websocket { .. }
+        | Kind::SSE(_)
+        | Kind::SSEEvents(_)
+        | Kind::StatusUnion(_)
+        | Kind::Ndjson(_) => unreachable!(),
+    };
+
+    let call = format!("_client.send(\n      {},\n    )", send.join(",\n      "));
+    match result {
+        Some(result) => body.push_str(&format!(
+            "    final res = await {call};\n    return {result};\n"
+        )),
+        None => body.push_str(&format!("    await {call};\n")),
+    }
+
+    Ok(format!(
+        "{}  Future<{returns}> {name}({}) async {{\n{body}  }}\n",
+        doc(v),
+        args.join(", ")
+    ))
+}
+
+/// A method throwing instead of calling the route, see
+/// [`clients::unimplemented`].
+fn stub(v: &RequestInfo, name: &str, what: &str) -> String {
+    format!(
+        "{}  Never {name}() => throw UnimplementedError({});\n",
+        doc(v),
+        string(&format!("{what} are not supported by the dart client yet"))
+    )
+}
+
+/// The route, its deprecation and its error responses as doc comments.
+fn doc(v: &RequestInfo) -> String {
+    let mut doc = format!("  /// `{} {}`\n", v.method.as_str(), v.path);
+    if !v.error_codes.is_empty() {
+        doc.push_str("  ///\n  /// Error responses:\n");
+        for (codes, info) in v.error_ranges() {
+            doc.push_str(&format!("  /// - {}: {info}\n", status::label(&codes)));
+        }
+    }
+
+    match &v.deprecated {
+        Deprecated::Simple(false) => {},
+        Deprecated::Simple(true) => doc.push_str("  @deprecated\n"),
+        Deprecated::WithInfo(path, method, _) => doc.push_str(&format!(
+            "  @Deprecated({})\n",
+            string(&format!("Use {} {path} instead", method.as_str()))
+        )),
+        Deprecated::WithReason { .. } => doc.push_str(&format!(
+            "  @Deprecated({})\n",
+            string(&v.deprecated.reason().unwrap_or_default())
+        )),
+    }
+
+    doc
+}
+
+/// The path as a string literal, its placeholders interpolated from the JSON
+/// of the path params.
+fn path(v: &RequestInfo) -> String {
+    let path = v
+        .path
+        .split('/')
+        .map(|segment| {
+            match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(name) if v.path_params.is_schema() =>
+                    format!("${{Uri.encodeComponent('${{path[{}]}}')}}", string(name)),
+                Some(name) =>
+                    format!("${{Uri.encodeComponent(pathParams[{}]!)}}", string(name)),
+                None => escape(segment),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("/");
+
+    format!("'{path}'")
+}
+
+/// The Dart type of a schema.
+#[derive(Debug, Clone)]
+enum DartType {
+    Dynamic,
+    String,
+    Int,
+    Double,
+    Bool,
+    List(Box<DartType>),
+    Map(Box<DartType>),
+    Class(String),
+    Nullable(Box<DartType>),
+}
+
+impl DartType {
+    fn nullable(self) -> Self {
+        match self {
+            DartType::Dynamic | DartType::Nullable(_) => self,
+            _ => DartType::Nullable(Box::new(self)),
+        }
+    }
+
+    /// Whether values are the same in Dart and in JSON.
+    fn is_plain(&self) -> bool {
+        match self {
+            DartType::Dynamic
+            | DartType::String
+            | DartType::Int
+            | DartType::Double
+            | DartType::Bool => true,
+            DartType::List(_) | DartType::Map(_) | DartType::Class(_) => false,
+            DartType::Nullable(inner) => inner.is_plain(),
+        }
+    }
+}
+
+impl Display for DartType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DartType::Dynamic => write!(f, "dynamic"),
+            DartType::String => write!(f, "String"),
+            DartType::Int => write!(f, "int"),
+            DartType::Double => write!(f, "double"),
+            DartType::Bool => write!(f, "bool"),
+            DartType::List(item) => write!(f, "List<{item}>"),
+            DartType::Map(value) => write!(f, "Map<String, {value}>"),
+            DartType::Class(name) => write!(f, "{name}"),
+            DartType::Nullable(inner) => write!(f, "{inner}?"),
+        }
+    }
+}
+
+/// Reads a value of the type from the JSON expression.
+fn decode(ty: &DartType, expr: &str) -> String {
+    match ty {
+        DartType::Dynamic => expr.to_string(),
+        DartType::String => format!("{expr} as String"),
+        DartType::Int => format!("({expr} as num).toInt()"),
+        DartType::Double => format!("({expr} as num).toDouble()"),
+        DartType::Bool => format!("{expr} as bool"),
+        DartType::List(item) => format!(
+            "({expr} as List<dynamic>).map((e) => {}).toList()",
+            decode(item, "e")
+        ),
+        DartType::Map(value) => format!(
+            "({expr} as Map<String, dynamic>).map((k, e) => MapEntry(k, {}))",
+            decode(value, "e")
+        ),
+        DartType::Class(name) =>
+            format!("{name}.fromJson({expr} as Map<String, dynamic>)"),
+        DartType::Nullable(inner) =>
+            format!("{expr} == null ? null : {}", decode(inner, expr)),
+    }
+}
+
+/// Converts the expression of the type into JSON.
+fn encode(ty: &DartType, expr: &str) -> String {
+    match ty {
+        _ if ty.is_plain() => expr.to_string(),
+        DartType::List(item) =>
+            format!("{expr}.map((e) => {}).toList()", encode(item, "e")),
+        DartType::Map(value) =>
+            format!("{expr}.map((k, e) => MapEntry(k, {}))", encode(value, "e")),
+        DartType::Class(_) => format!("{expr}.toJson()"),
+        DartType::Nullable(inner) => format!(
+            "{expr} == null ? null : {}",
+            encode(inner, &format!("{expr}!"))
+        ),
+        _ => expr.to_string(),
+    }
+}
+
+/// Collects the classes of object schemas, named after the route slot and the
+/// path of properties leading to them.
+#[derive(Default)]
+struct Classes {
+    classes: Vec<String>,
+    names: BTreeSet<String>,
+}
+
+impl Classes {
+    fn root(&mut self, schema: &SchemaObject, name: &str) -> DartType {
+        self.object_type(schema, name)
+    }
+
+    fn schema_type(&mut self, schema: &Schema, name: &str) -> DartType {
+        match schema {
+            Schema::Bool(_) => DartType::Dynamic,
+            Schema::Object(schema) => self.object_type(schema, name),
+        }
+    }
+
+    fn object_type(&mut self, schema: &SchemaObject, name: &str) -> DartType {
+        let inner = unwrap_nullable(schema);
+        if !std::ptr::eq(inner, schema) {
+            return self.object_type(inner, name).nullable();
+        }
+
+        if let Some(values) = &schema.enum_values {
+            return match values.iter().all(|v| v.is_string() || v.is_null()) {
+                true if values.iter().any(|v| v.is_null()) => DartType::String.nullable(),
+                true => DartType::String,
+                false => DartType::Dynamic,
+            };
+        }
+        if let Some(value) = &schema.const_value {
+            return match value.is_string() {
+                true => DartType::String,
+                false => DartType::Dynamic,
+            };
+        }
+
+        if let Some(subschemas) = &schema.subschemas {
+            return match subschemas.all_of.as_deref() {
+                Some([single]) => self.schema_type(single, name),
+                _ => DartType::Dynamic,
+            };
+        }
+
+        match &schema.instance_type {
+            Some(SingleOrVec::Single(t)) => self.instance_type(schema, **t, name),
+            Some(SingleOrVec::Vec(types)) => {
+                let not_null = types
+                    .iter()
+                    .filter(|t| **t != InstanceType::Null)
+                    .collect::<Vec<&InstanceType>>();
+                match not_null.as_slice() {
+                    [t] => self.instance_type(schema, **t, name).nullable(),
+                    _ => DartType::Dynamic,
+                }
+            },
+            None if schema.object.is_some() =>
+                self.instance_type(schema, InstanceType::Object, name),
+            None => DartType::Dynamic,
+        }
+    }
+
+    fn instance_type(
+        &mut self,
+        schema: &SchemaObject,
+        instance_type: InstanceType,
+        name: &str,
+    ) -> DartType {
+        match instance_type {
+            InstanceType::Null => DartType::Dynamic,
+            InstanceType::Boolean => DartType::Bool,
+            InstanceType::Integer => DartType::Int,
+            InstanceType::Number => DartType::Double,
+            InstanceType::String => DartType::String,
+            InstanceType::Array =>
+                match schema.array.as_ref().and_then(|a| a.items.as_ref()) {
+                    Some(SingleOrVec::Single(item)) => DartType::List(Box::new(
+                        self.schema_type(item, &format!("{name}Item")),
+                    )),
+                    _ => DartType::List(Box::new(DartType::Dynamic)),
+                },
+            InstanceType::Object => match &schema.object {
+                Some(object) if !object.properties.is_empty() => self.class(schema, name),
+                Some(object) => match &object.additional_properties {
+                    Some(value) => DartType::Map(Box::new(
+                        self.schema_type(value, &format!("{name}Value")),
+                    )),
+                    None => DartType::Map(Box::new(DartType::Dynamic)),
+                },
+                None => DartType::Map(Box::new(DartType::Dynamic)),
+            },
+        }
+    }
+
+    fn class(&mut self, schema: &SchemaObject, name: &str) -> DartType {
+        let mut class_name = name.to_string();
+        let mut n = 2;
+        while !self.names.insert(class_name.clone()) {
+            class_name = format!("{name}{n}");
+            n += 1;
+        }
+
+        let object = schema.object.as_ref().expect("classes are objects");
+        let fields = object
+            .properties
+            .iter()
+            .map(|(key, property)| {
+                let field = ident(&camel_case(key), KEYWORDS);
+                let ty = self
+                    .schema_type(property, &format!("{class_name}{}", pascal_case(key)));
+                let required = object.required.contains(key);
+                let ty = match required {
+                    true => ty,
+                    false => ty.nullable(),
+                };
+                (key, field, ty, required, property)
+            })
+            .collect::<Vec<_>>();
+
+        let mut class = format!("class {class_name} {{\n");
+        for (_, field, ty, _, property) in &fields {
+            if let Some(description) = description(property) {
+                for line in description.lines() {
+                    class.push_str(&format!("  /// {line}\n"));
+                }
+            }
+            class.push_str(&format!("  final {ty} {field};\n"));
+        }
+
+        class.push_str(&format!(
+            "\n  const {class_name}({{{}}});\n",
+            fields
+                .iter()
+                .map(|(_, field, ty, ..)| match ty {
+                    DartType::Nullable(_) | DartType::Dynamic => format!("this.{field}"),
+                    _ => format!("required this.{field}"),
+                })
+                .collect::<Vec<String>>()
+                .join(", ")
+        ));
+
+        class.push_str(&format!(
+            "\n  factory {class_name}.fromJson(Map<String, dynamic> json) => \
+             {class_name}(\n"
+        ));
+        for (key, field, ty, ..) in &fields {
+            class.push_str(&format!(
+                "        {field}: {},\n",
+                decode(ty, &format!("json[{}]", string(key)))
+            ));
+        }
+        class.push_str("      );\n");
+
+        class.push_str("\n  Map<String, dynamic> toJson() => {\n");
+        for (key, field, ty, required, _) in &fields {
+            let value = encode(ty, field);
+            match (required, ty) {
+                (false, DartType::Nullable(inner)) => class.push_str(&format!(
+                    "        if ({field} != null) {}: {},\n",
+                    string(key),
+                    encode(inner, &format!("{field}!"))
+                )),
+                _ => class.push_str(&format!("        {}: {value},\n", string(key))),
+            }
+        }
+        class.push_str("      };\n}\n");
+
+        self.classes.push(class);
+        DartType::Class(class_name)
+    }
+}
+
+fn description(schema: &Schema) -> Option<&str> {
+    let Schema::Object(schema) = schema else {
+        return None;
+    };
+
+    schema.metadata.as_ref()?.description.as_deref()
+}
+
+/// A Dart string literal.
+fn string(s: &str) -> String { format!("'{}'", escape(s)) }
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\'', "\\'")
+        .replace('$', "\\$")
+        .replace('\n', "\\n")
+}
+
+fn camel_case(s: &str) -> String {
+    let pascal = pascal_case(s);
+    let mut chars = pascal.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_digit() => format!("v{pascal}"),
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => "value".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use schemars::JsonSchema;
+
+    use crate::{generate_dart, GenError, Method, RequestInfo, Requests, Slot};
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct UserPath {
+        id: u32,
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Address {
+        street_name: String,
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct User {
+        name: String,
+        age: Option<u8>,
+        addresses: Vec<Address>,
+    }
+
+    fn gen(requests: Vec<RequestInfo>) -> String {
+        generate_dart(Requests {
+            requests,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn classes() {
+        let out = gen(vec![RequestInfo::new(
+            "/api/users/{id}",
+            Method::Put,
+            "users",
+        )
+        .with_path_params::<UserPath>()
+        .with_req_body::<User>()
+        .with_res_schema::<User>()]);
+
+        assert!(out.contains("UsersApi get users => UsersApi(this);"));
+        assert!(out.contains(
+            "Future<PutByIdRes> putById(PutByIdPathParams pathParams, PutByIdReq req) \
+             async {"
+        ));
+        assert!(out.contains("'/api/users/${Uri.encodeComponent('${path['id']}')}'"));
+        assert!(out.contains("body: req.toJson()"));
+        assert!(out.contains(
+            "return PutByIdRes.fromJson(jsonDecode(res.body) as Map<String, dynamic>);"
+        ));
+
+        assert!(out.contains("class PutByIdReqAddressesItem {"));
+        assert!(out.contains("  final String streetName;"));
+        assert!(out.contains("  final int? age;"));
+        assert!(out.contains(
+            "        age: json['age'] == null ? null : (json['age'] as num).toInt(),"
+        ));
+        assert!(out.contains("        if (age != null) 'age': age!,"));
+        assert!(out
+            .contains("        'addresses': addresses.map((e) => e.toJson()).toList(),"));
+    }
+
+    #[test]
+    fn streams_are_unimplemented() {
+        let out = gen(vec![
+            RequestInfo::new("/api/events", Method::Get, "events").with_sse::<User>()
+        ]);
+
+        assert!(out.contains("Never getEvents() => throw UnimplementedError("));
+    }
+
+    #[test]
+    fn unexpected_kind_error() {
+        let mut info = RequestInfo::new("/api/events", Method::Get, "events");
+        info.req_params = RequestInfo::new("/api/events", Method::Get, "events")
+            .with_sse::<User>()
+            .res_body;
+
+        let err = generate_dart(Requests {
+            requests: vec![info],
+            ..Default::default()
+        })
+        .unwrap_err();
+        let err = err.downcast_ref::<GenError>().unwrap();
+
+        assert_eq!(err.path, "/api/events");
+        assert_eq!(err.slot, Slot::Params);
+    }
+}
@@ -1,21 +1,47 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::Method;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, JsonSchema, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Deprecated {
     Simple(bool),
     WithInfo(String, Method, String),
+    /// A free-text reason, optionally with the version the route is deprecated
+    /// since, e.g. `{"reason": "use the v2 endpoint", "since": "2.1.0"}`.
+    WithReason {
+        reason: String,
+        #[serde(default)]
+        since: Option<String>,
+    },
 }
 
 impl Deprecated {
     pub fn is(&self) -> bool {
         matches!(
             self,
-            Deprecated::Simple(true) | Deprecated::WithInfo(_, _, _)
+            Deprecated::Simple(true)
+                | Deprecated::WithInfo(_, _, _)
+                | Deprecated::WithReason { .. }
         )
     }
+
+    /// The reason prefixed with its version, e.g. `since 2.1.0: use the v2
+    /// endpoint`.
+    pub(crate) fn reason(&self) -> Option<String> {
+        match self {
+            Deprecated::WithReason {
+                reason,
+                since: Some(since),
+            } => Some(format!("since {since}: {reason}")),
+            Deprecated::WithReason {
+                reason,
+                since: None,
+            } => Some(reason.clone()),
+            _ => None,
+        }
+    }
 }
 
 impl Default for Deprecated {
@@ -0,0 +1,54 @@
+use std::fmt::{Display, Formatter};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// How a schema request body is encoded.
+#[derive(
+    Debug, Copy, Clone, Default, Eq, PartialEq, JsonSchema, Serialize, Deserialize,
+)]
+pub enum BodyEncoding {
+    /// `application/json`
+    #[default]
+    Json,
+    /// `application/x-www-form-urlencoded`, for flat bodies, e.g. login forms.
+    UrlEncoded,
+    /// `multipart/form-data`, for uploads, see [`crate::file`].
+    Multipart,
+}
+
+impl BodyEncoding {
+    /// The `Content-Type` header sent with the body, none for multipart bodies
+    /// as the browser adds the boundary itself.
+    pub fn content_type(&self) -> Option<&'static str> {
+        match self {
+            BodyEncoding::Json => Some("application/json"),
+            BodyEncoding::UrlEncoded => Some("application/x-www-form-urlencoded"),
+            BodyEncoding::Multipart => None,
+        }
+    }
+}
+
+/// How arrays and objects of query params are serialized.
+#[derive(
+    Debug, Copy, Clone, Default, Eq, PartialEq, JsonSchema, Serialize, Deserialize,
+)]
+pub enum QueryStyle {
+    /// `?tags=a,b`, objects as JSON.
+    #[default]
+    Comma,
+    /// `?tags=a&tags=b`, objects as JSON.
+    Repeat,
+    /// `?tags[]=a&tags[]=b`, objects as `?filter[name]=a`.
+    Brackets,
+}
+
+impl Display for QueryStyle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            QueryStyle::Comma => "comma",
+            QueryStyle::Repeat => "repeat",
+            QueryStyle::Brackets => "brackets",
+        })
+    }
+}
@@ -0,0 +1,64 @@
+use std::{
+    error::Error as StdError,
+    fmt::{Display, Formatter},
+};
+
+use crate::types::{Kind, RequestInfo};
+
+/// The part of a route a [`GenError`] is about.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Slot {
+    PathParams,
+    Params,
+    Headers,
+    Body,
+    Res,
+    ResHeaders,
+}
+
+impl Display for Slot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Slot::PathParams => "path params",
+            Slot::Params => "request params",
+            Slot::Headers => "request headers",
+            Slot::Body => "request body",
+            Slot::Res => "response",
+            Slot::ResHeaders => "response headers",
+        })
+    }
+}
+
+/// A route that can not be generated, e.g. because of a websocket in its
+/// request params.
+#[derive(Debug, Clone)]
+pub struct GenError {
+    pub path: String,
+    pub tag: String,
+    pub slot: Slot,
+    /// The kind found in the slot, as displayed by [`Kind`].
+    pub kind: String,
+}
+
+impl GenError {
+    pub(crate) fn unexpected_kind(info: &RequestInfo, slot: Slot, kind: &Kind) -> Self {
+        GenError {
+            path: info.path.clone(),
+            tag: info.tag.clone(),
+            slot,
+            kind: kind.to_string(),
+        }
+    }
+}
+
+impl Display for GenError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Unexpected {} kind in the {} of {} (tag {})",
+            self.kind, self.slot, self.path, self.tag
+        )
+    }
+}
+
+impl StdError for GenError {}
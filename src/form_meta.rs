@@ -0,0 +1,115 @@
+use schemars::schema::{InstanceType, Schema, SchemaObject, SingleOrVec};
+use serde_json::Value;
+
+use crate::{
+    nullable::unwrap_nullable,
+    walk::{walk, Step},
+};
+
+/// Describes the fields of an object schema as a `FormField[]` literal, nested
+/// objects listing their own fields. Returns `None` for non object schemas.
+pub(crate) fn form_meta(schema: &SchemaObject) -> Option<String> {
+    let schema = unwrap_nullable(schema);
+    schema.object.as_ref()?;
+    Some(fields(schema))
+}
+
+fn fields(schema: &SchemaObject) -> String {
+    let mut fields = Vec::new();
+    walk(schema, &mut |path, property| {
+        if let ([Step::Property(name)], Schema::Object(property), Some(object)) =
+            (path, property, &schema.object)
+        {
+            fields.push(field(name, property, object.required.contains(*name)));
+        }
+        // nested objects are described by their own fields
+        false
+    });
+
+    format!("[{}]", fields.join(", "))
+}
+
+fn field(name: &str, schema: &SchemaObject, required: bool) -> String {
+    let description = schema
+        .metadata
+        .as_ref()
+        .and_then(|m| m.description.as_ref());
+    let schema = unwrap_nullable(schema);
+
+    let mut out = vec![
+        format!("name: {}", quote(name)),
+        format!("type: '{}'", type_name(schema)),
+        format!("required: {required}"),
+    ];
+
+    if let Some(description) = description {
+        out.push(format!("description: {}", quote(description)));
+    }
+
+    if let Some(values) = &schema.enum_values {
+        let options = values
+            .iter()
+            .filter(|v| !v.is_null())
+            .map(Value::to_string)
+            .collect::<Vec<String>>();
+        out.push(format!("options: [{}]", options.join(", ")));
+    }
+
+    let (min, max) = bounds(schema);
+    if let Some(min) = min {
+        out.push(format!("min: {min}"));
+    }
+    if let Some(max) = max {
+        out.push(format!("max: {max}"));
+    }
+
+    if schema.object.is_some() {
+        out.push(format!("fields: {}", fields(schema)));
+    }
+
+    format!("{{ {} }}", out.join(", "))
+}
+
+fn type_name(schema: &SchemaObject) -> &'static str {
+    let instance_type = match &schema.instance_type {
+        Some(SingleOrVec::Single(t)) => Some(**t),
+        Some(SingleOrVec::Vec(types)) =>
+            types.iter().copied().find(|t| *t != InstanceType::Null),
+        None => None,
+    };
+
+    match instance_type {
+        Some(InstanceType::String) => "string",
+        Some(InstanceType::Number) | Some(InstanceType::Integer) => "number",
+        Some(InstanceType::Boolean) => "boolean",
+        Some(InstanceType::Array) => "array",
+        Some(InstanceType::Object) => "object",
+        Some(InstanceType::Null) | None if schema.enum_values.is_some() => "enum",
+        Some(InstanceType::Null) | None if schema.object.is_some() => "object",
+        Some(InstanceType::Null) | None => "unknown",
+    }
+}
+
+fn bounds(schema: &SchemaObject) -> (Option<f64>, Option<f64>) {
+    if let Some(number) = &schema.number {
+        return (
+            number.minimum.or(number.exclusive_minimum),
+            number.maximum.or(number.exclusive_maximum),
+        );
+    }
+    if let Some(string) = &schema.string {
+        return (
+            string.min_length.map(f64::from),
+            string.max_length.map(f64::from),
+        );
+    }
+    if let Some(array) = &schema.array {
+        return (
+            array.min_items.map(f64::from),
+            array.max_items.map(f64::from),
+        );
+    }
+    (None, None)
+}
+
+fn quote(s: &str) -> String { Value::String(s.to_string()).to_string() }
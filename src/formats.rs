@@ -0,0 +1,124 @@
+use schemars::schema::{InstanceType, Schema, SchemaObject, SubschemaValidation};
+use serde_json::Value;
+
+use crate::walk::{object_at_mut, walk, Step};
+
+/// The zod schemas of the string formats, every other format stays a plain
+/// string.
+const FORMATS: &[(&str, &str)] = &[
+    ("uuid", "z.string().uuid()"),
+    ("email", "z.string().email()"),
+    ("uri", "z.string().url()"),
+    ("url", "z.string().url()"),
+];
+
+/// The schema with every string of a known format replaced by a literal
+/// marker, which the zod translation keeps as `z.literal(...)`, and the zod
+/// schemas [`restore`] replaces the markers with.
+pub(crate) fn mark(schema: &SchemaObject) -> (SchemaObject, Vec<String>) {
+    let mut formatted = Vec::<Vec<Step>>::new();
+    if refinement(schema).is_some() {
+        formatted.push(Vec::new());
+    } else {
+        walk(schema, &mut |path, schema| match schema {
+            Schema::Object(schema) if refinement(schema).is_some() => {
+                formatted.push(path.to_vec());
+                false
+            },
+            _ => true,
+        });
+    }
+
+    let mut marked = schema.clone();
+    let mut refined = Vec::new();
+    for path in formatted {
+        if let Some(schema) = object_at_mut(&mut marked, &path) {
+            mark_object(schema, &mut refined);
+        }
+    }
+
+    (marked, refined)
+}
+
+/// Replaces the markers of [`mark`] in the zod translation.
+pub(crate) fn restore(mut zod: String, refined: &[String]) -> String {
+    for (i, refinement) in refined.iter().enumerate() {
+        for quote in ['"', '\''] {
+            zod = zod.replace(
+                &format!("z.literal({quote}{}{quote})", marker(i)),
+                refinement,
+            );
+        }
+    }
+
+    zod
+}
+
+fn marker(i: usize) -> String { format!("__client_gen_format_{i}__") }
+
+/// The zod schema of a string with a known format.
+fn refinement(schema: &SchemaObject) -> Option<String> {
+    let is_string = schema
+        .instance_type
+        .as_ref()
+        .is_some_and(|t| t.contains(&InstanceType::String));
+    let format = schema.format.as_deref().filter(|_| is_string)?;
+    let (_, zod) = FORMATS.iter().find(|(name, _)| *name == format)?;
+
+    let mut refinement = zod.to_string();
+    if let Some(string) = &schema.string {
+        if let Some(min) = string.min_length {
+            refinement.push_str(&format!(".min({min})"));
+        }
+        if let Some(max) = string.max_length {
+            refinement.push_str(&format!(".max({max})"));
+        }
+        if let Some(pattern) = &string.pattern {
+            refinement.push_str(&format!(
+                ".regex(new RegExp({}))",
+                serde_json::to_string(pattern).unwrap()
+            ));
+        }
+    }
+    Some(refinement)
+}
+
+/// Replaces the string by a literal marker, nullable if the string is.
+fn mark_object(schema: &mut SchemaObject, refined: &mut Vec<String>) {
+    let Some(refinement) = refinement(schema) else {
+        return;
+    };
+
+    let literal = SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        const_value: Some(Value::String(marker(refined.len()))),
+        ..Default::default()
+    };
+    refined.push(refinement);
+
+    let nullable = schema
+        .instance_type
+        .as_ref()
+        .is_some_and(|t| t.contains(&InstanceType::Null));
+    *schema = match nullable {
+        true => SchemaObject {
+            metadata: schema.metadata.take(),
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![
+                    literal.into(),
+                    SchemaObject {
+                        instance_type: Some(InstanceType::Null.into()),
+                        ..Default::default()
+                    }
+                    .into(),
+                ]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        },
+        false => SchemaObject {
+            metadata: schema.metadata.take(),
+            ..literal
+        },
+    };
+}
@@ -1,83 +1,796 @@
-use std::{collections::BTreeMap, error::Error as StdError};
+use std::{
+    borrow::{Borrow, Cow},
+    collections::BTreeMap,
+    error::Error as StdError,
+};
 
+use schemars::schema::{RootSchema, Schema, SchemaObject};
 use schemars_to_zod::{pretty::default_pretty_conf, Config, Parser};
 
 use crate::{
+    angular,
+    config::{GenerateConfig, Layout, Target},
+    error::{GenError, Slot},
+    form_meta::form_meta,
+    formats,
+    headers,
+    multipart::split_files,
+    namespace::{self, is_identifier, Namespaces},
+    naming,
+    nullable::{normalize_request, normalized, unwrap_nullable},
+    path,
+    sensitive::sensitive_fields,
+    shared,
+    status,
+    strict::{dropped, Kept},
     types::{Kind, RequestInfo, Requests},
+    typescript,
+    BodyEncoding,
     Deprecated,
+    Method,
+    QueryStyle,
+    Stability,
 };
 
-fn first_upper(s: impl AsRef<str>) -> String {
-    let mut s: Vec<char> = s.as_ref().chars().collect();
-    s[0] = s[0].to_uppercase().next().unwrap();
-    s.into_iter().collect()
+pub(crate) fn first_upper(s: impl AsRef<str>) -> String {
+    let mut chars = s.as_ref().chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Whether a route is generated, leaving out routes excluded from the client
+/// and, unless disabled, `OPTIONS`, `TRACE` and `CONNECT` routes.
+pub(crate) fn included(info: &RequestInfo, config: &GenerateConfig) -> bool {
+    info.add_to_client && !(config.exclude_meta_methods && info.method.is_meta())
+}
+
+/// Lists what is generated but likely unintended, the warnings of
+/// [`RequestInfo::validate`] and deprecation notes whose replacement is not
+/// generated, which is an error when generating with `strict`.
+pub fn warnings(requests: &Requests, config: &GenerateConfig) -> Vec<String> {
+    let generated = requests
+        .requests
+        .iter()
+        .filter(|v| included(v, config))
+        .filter(|v| !(config.exclude_internal && v.stability == Stability::Internal))
+        .collect::<Vec<&RequestInfo>>();
+
+    let mut warnings = generated
+        .iter()
+        .flat_map(|v| v.validate())
+        .collect::<Vec<String>>();
+    for v in &generated {
+        let Deprecated::WithInfo(path, method, tag) = &v.deprecated else {
+            continue;
+        };
+        if !generated
+            .iter()
+            .any(|r| &r.path == path && r.method == *method && &r.tag == tag)
+        {
+            warnings.push(format!(
+                "The replacement {method} {path} of tag {tag} deprecating {} {} is not \
+                 generated",
+                v.method, v.path
+            ));
+        }
+    }
+
+    warnings
+}
+
+pub(crate) fn schemas(info: &RequestInfo) -> Vec<(&'static str, &RootSchema)> {
+    let mut out = Vec::new();
+
+    for (slot, kind) in [
+        ("pathParams", &info.path_params),
+        ("params", &info.req_params),
+        ("headers", &info.req_headers),
+        ("req", &info.req_body),
+        ("res", &info.res_body),
+        ("resHeaders", &info.res_headers),
+    ] {
+        match kind {
+            Kind::None | Kind::Any | Kind::Text | Kind::Binary => {},
+            Kind::Schema(schema)
+            | Kind::TextSchema(schema)
+            | Kind::SSE(schema)
+            | Kind::Ndjson(schema) => out.push((slot, schema)),
+            Kind::SSEEvents(events) =>
+                out.extend(events.values().map(|schema| (slot, schema))),
+            Kind::Websocket {
+                client_msg,
+                server_msg,
+            } => {
+                out.push(("clientMsg", client_msg));
+                out.push(("serverMsg", server_msg));
+            },
+            Kind::StatusUnion(bodies) =>
+                out.extend(bodies.iter().map(|(_, schema)| (slot, schema))),
+        }
+    }
+    out.extend(
+        info.error_schemas
+            .iter()
+            .map(|(_, schema)| ("error", schema)),
+    );
+
+    out
+}
+
+/// The url expression of a websocket with the token param of the route
+/// appended.
+pub(crate) fn ws_url(info: &RequestInfo, url: String) -> String {
+    match &info.ws_token_param {
+        Some(param) => format!("withWsToken({url}, '{}', '{param}')", info.tag),
+        None => url,
+    }
+}
+
+/// The subprotocols of a websocket as trailing argument, empty without any.
+pub(crate) fn ws_protocols(info: &RequestInfo) -> String {
+    match info.ws_protocols.is_empty() {
+        true => String::new(),
+        false => format!(
+            ", [{}]",
+            info.ws_protocols
+                .iter()
+                .map(|v| format!("'{v}'"))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// The reconnect policy of an event source or websocket as trailing argument,
+/// empty without one.
+fn reconnect_policy(info: &RequestInfo) -> String {
+    match &info.reconnect {
+        Some(policy) => format!(
+            ",\n            {{ maxRetries: {}, baseDelayMs: {}, maxDelayMs: {} }}",
+            policy.max_retries, policy.base_delay_ms, policy.max_delay_ms
+        ),
+        None => String::new(),
+    }
+}
+
+/// The `Accept` header of a route, listing the content type of the response
+/// body followed by the additionally declared ones with decreasing quality.
+/// Without any, JSON responses accept `application/json` if `json` is set.
+pub(crate) fn accept_header(info: &RequestInfo, json: bool) -> Option<String> {
+    if let Some(accept) = &info.accept {
+        return Some(accept.clone());
+    }
+
+    let primary = match info.res_body {
+        Kind::Schema(_) | Kind::StatusUnion(_) => Some("application/json"),
+        Kind::Ndjson(_) => Some("application/x-ndjson"),
+        Kind::Text | Kind::TextSchema(_) => Some("text/plain"),
+        _ => None,
+    };
+
+    if info.res_content_types.is_empty() {
+        return primary
+            .filter(|primary| json && *primary == "application/json")
+            .map(str::to_string);
+    }
+
+    Some(
+        primary
+            .into_iter()
+            .chain(info.res_content_types.iter().map(String::as_str))
+            .enumerate()
+            .map(|(i, content_type)| match i {
+                0 => content_type.to_string(),
+                i => format!("{content_type};q=0.{}", 10usize.saturating_sub(i).max(1)),
+            })
+            .collect::<Vec<String>>()
+            .join(", "),
+    )
 }
 
-fn make_name(info: &RequestInfo) -> String {
-    make_name_raw(info.method.to_string(), info.path.clone(), info.tag.clone())
+/// The values of a string enum, `null` apart.
+fn string_enum(schema: &SchemaObject) -> Option<Vec<&str>> {
+    unwrap_nullable(schema)
+        .enum_values
+        .as_ref()?
+        .iter()
+        .filter(|v| !v.is_null())
+        .map(|v| v.as_str())
+        .collect()
 }
 
-fn make_name_raw(method: String, path: String, tag: String) -> String {
-    let start = method.to_string().to_lowercase();
+/// The string enum fields at the top level of the schema.
+fn enum_fields(schema: &SchemaObject) -> Vec<(&str, Vec<&str>)> {
+    let Some(object) = &schema.object else {
+        return Vec::new();
+    };
 
-    let path = path.strip_prefix('/').unwrap_or(&path);
-    let path = path.strip_prefix("api/").unwrap_or(path);
-    let path = path.strip_prefix(&format!("{}/", tag)).unwrap_or(path);
+    object
+        .properties
+        .iter()
+        .filter_map(|(field, property)| match property {
+            Schema::Object(property) => Some((field.as_str(), string_enum(property)?)),
+            Schema::Bool(_) => None,
+        })
+        .collect()
+}
 
-    let path = path
-        .split(&['-', '/', '_'][..])
-        .map(str::to_lowercase)
+fn pascal_case(s: &str) -> String {
+    s.split(|c: char| !c.is_alphanumeric())
+        .filter(|segment| !segment.is_empty())
         .map(first_upper)
-        .collect::<Vec<String>>()
-        .join("");
-    format!("{start}{path}")
+        .collect()
+}
+
+/// Exports the allowed values of the top level string enum fields of
+/// `type_name`, e.g. for building dropdowns.
+fn enum_constants(schema: &SchemaObject, type_name: &str) -> String {
+    let mut out = String::new();
+    for (field, values) in enum_fields(schema) {
+        let values = values
+            .iter()
+            .map(|v| format!("{v:?}"))
+            .collect::<Vec<String>>();
+
+        out.push_str(&format!(
+            "    export const {type_name}{}Values = [{}] as const satisfies readonly \
+             NonNullable<{type_name}['{field}']>[];\n",
+            pascal_case(field),
+            values.join(", ")
+        ));
+    }
+
+    out
 }
 
-fn format_js(js: &str) -> Result<String, Box<dyn StdError>> {
-    let mut config = default_pretty_conf();
-    config.line_width = 90;
-    config.indent_width = 4;
+/// Exports const objects naming the values of the schema if it is a string
+/// enum, otherwise of its top level string enum fields, e.g.
+/// `{ InProgress: 'in_progress' }`.
+fn enum_objects(schema: &SchemaObject, type_name: &str) -> String {
+    let object = |name: &str, values: Vec<&str>| {
+        let entries = values
+            .iter()
+            .map(|v| match pascal_case(v) {
+                key if is_identifier(&key) => format!("{key}: {v:?}"),
+                _ => format!("{v:?}: {v:?}"),
+            })
+            .collect::<Vec<String>>();
+
+        format!(
+            "    export const {name} = {{ {} }} as const;\n",
+            entries.join(", ")
+        )
+    };
+
+    if let Some(values) = string_enum(schema) {
+        return object(type_name, values);
+    }
 
-    schemars_to_zod::pretty::format_js(js, "client.ts", &config)
+    enum_fields(schema)
+        .into_iter()
+        .map(|(field, values)| {
+            object(&format!("{type_name}{}", pascal_case(field)), values)
+        })
+        .collect()
 }
 
-pub fn generate(Requests { requests }: Requests) -> Result<String, Box<dyn StdError>> {
-    let mut namespaces = BTreeMap::<&'static str, Vec<String>>::new();
-    let mut classes = String::from(include_str!("base/client.ts"));
+/// The `credentials` of the requests of the route.
+fn credentials(info: &RequestInfo) -> &'static str {
+    match info.credentials {
+        true => "include",
+        false => "omit",
+    }
+}
+
+fn call_signature(info: &RequestInfo, name: &str) -> String {
+    let struct_name = first_upper(name);
+    let streaming = info.res_body.is_sse() || info.res_body.is_websocket();
+
+    let mut args = Vec::new();
+    if info.path_params.is_some() {
+        args.push(format!("pathParams: {struct_name}PathParams"));
+    }
+    if info.req_body.is_some() && !streaming {
+        args.push(format!("req: {struct_name}Req"));
+    }
+    if info.req_params.is_some() {
+        args.push(format!("params: {struct_name}Params"));
+    }
+    if info.req_headers.is_some() {
+        args.push(format!("headers: {struct_name}Headers"));
+    }
+    if !streaming {
+        args.push("init?: RequestInit".to_string());
+    }
+    if info.timeout_ms.is_some() && !streaming {
+        args.push("timeoutMs?: number".to_string());
+    }
+    if info.retries.is_some() && !streaming {
+        args.push("retries?: number".to_string());
+    }
+
+    format!("{name}({})", args.join(", "))
+}
+
+pub(crate) fn format_js(
+    js: &str,
+    config: &GenerateConfig,
+) -> Result<String, Box<dyn StdError>> {
+    let mut pretty = default_pretty_conf();
+    pretty.line_width = config.line_width;
+    pretty.indent_width = config.indent_width;
+
+    schemars_to_zod::pretty::format_js(js, "client.ts", &pretty)
+}
+
+pub(crate) fn finish(out: String, config: &GenerateConfig) -> String {
+    let mut out = out.trim_end().replace('\n', config.eol.as_str());
+
+    if config.final_newline {
+        out.push_str(config.eol.as_str());
+    }
+
+    out
+}
+
+/// Assembles the imports and the base classes the generated functions depend
+/// on.
+fn base(
+    requests: &[impl Borrow<RequestInfo>],
+    config: &GenerateConfig,
+) -> (String, String) {
+    let requests = requests
+        .iter()
+        .map(Borrow::borrow)
+        .collect::<Vec<&RequestInfo>>();
+    let mut imports = String::new();
+    let mut classes = String::new();
+
+    let has_ws = requests.iter().any(|r| r.res_body.is_websocket());
+    let has_sse = requests.iter().any(|r| r.res_body.is_sse());
+    let has_timeout = requests.iter().any(|r| r.timeout_ms.is_some());
+    let has_retries = requests.iter().any(|r| r.retries.is_some());
+    let has_ndjson = requests.iter().any(|r| r.res_body.is_ndjson());
+
+    if config.target == Target::Angular {
+        imports.push_str(angular::IMPORTS);
+    }
+
+    if !config.include_base {
+        if let Some(module) = &config.base_import {
+            imports.push_str(&format!(
+                "import {{ {} }} from '{module}';\n",
+                base_symbols(&requests, config).join(", ")
+            ));
+        }
+
+        return (imports, classes);
+    }
+
+    match config.target {
+        Target::Fetch => {
+            classes.push_str(include_str!("base/client.ts"));
+
+            if has_ws || has_sse {
+                classes.push_str(include_str!("base/connection.ts"));
+            }
+
+            if has_ws {
+                classes.push_str(include_str!("base/websocket.ts"));
+            }
+
+            if has_sse {
+                classes.push_str(include_str!("base/sse.ts"));
+            }
+
+            #[cfg(feature = "react")]
+            if has_ws || has_sse {
+                imports.push_str(
+                    "import { useCallback, useEffect, useRef, useState } from 'react';\n",
+                );
+                classes.push_str(include_str!("base/react.ts"));
+            }
+
+            if config.abort_all {
+                classes.push_str(include_str!("base/abort.ts"));
+            }
+
+            if has_timeout {
+                classes.push_str(include_str!("base/timeout.ts"));
+            }
+
+            if has_retries {
+                classes.push_str(include_str!("base/retry.ts"));
+            }
+
+            if has_ndjson {
+                classes.push_str(include_str!("base/ndjson.ts"));
+            }
+
+            if config.validate_options {
+                classes.push_str(include_str!("base/options.ts"));
+            }
+        },
+        Target::Angular => classes.push_str(angular::BASE),
+    }
+
+    if config.emit_form_meta {
+        classes.push_str(include_str!("base/form.ts"));
+    }
+
+    (imports, classes)
+}
+
+fn zod_import(config: &GenerateConfig) -> String {
+    match (config.runtime_validation, config.zod_symbol.as_str()) {
+        (false, _) => String::new(),
+        (true, "z") => format!("import {{ z }} from '{}';\n", config.zod_module),
+        (true, symbol) =>
+            format!("import {{ {symbol} as z }} from '{}';\n", config.zod_module),
+    }
+}
+
+fn assign_base_urls(tag_base_urls: &BTreeMap<String, String>) -> String {
+    if tag_base_urls.is_empty() {
+        return String::new();
+    }
+
+    format!(
+        "Object.assign(options.baseUrls, {{ {} }});\n\n",
+        tag_base_urls
+            .iter()
+            .map(|(tag, url)| format!("{tag:?}: {url:?}"))
+            .collect::<Vec<String>>()
+            .join(", ")
+    )
+}
+
+/// Whether the base exports the symbol itself, e.g. `options`.
+fn exported(classes: &str, symbol: &str) -> bool {
+    classes
+        .lines()
+        .filter_map(|line| line.strip_prefix("export "))
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .any(|name| {
+            name.split(|c: char| !c.is_alphanumeric() && c != '_')
+                .next()
+                == Some(symbol)
+        })
+}
+
+/// The symbols of the base the generated functions reference, which have to be
+/// provided when `include_base` is disabled.
+pub fn base_symbols(
+    requests: &[impl Borrow<RequestInfo>],
+    config: &GenerateConfig,
+) -> Vec<&'static str> {
+    let requests = requests
+        .iter()
+        .map(Borrow::borrow)
+        .collect::<Vec<&RequestInfo>>();
+    let has_ws = requests.iter().any(|r| r.res_body.is_websocket());
+    let has_sse = requests.iter().any(|r| r.res_body.is_sse());
+    let has_timeout = requests.iter().any(|r| r.timeout_ms.is_some());
+    let has_retries = requests.iter().any(|r| r.retries.is_some());
+    let has_ndjson = requests.iter().any(|r| r.res_body.is_ndjson());
+
+    let mut symbols = match config.target {
+        Target::Fetch => vec![
+            "options",
+            "urlOf",
+            "PromiseWrapper",
+            "ok",
+            "okWith",
+            "err",
+            "errWith",
+            "urlEncoded",
+            "formData",
+            "makeQuery",
+            "readJson",
+            "readHeaders",
+            "readStatus",
+            "deduped",
+            "authorized",
+            "mergeHeaders",
+            "RepresentsHeader",
+        ],
+        Target::Angular => vec![
+            "options",
+            "baseUrlOf",
+            "wsBaseUrlOf",
+            "makeParams",
+            "makeQuery",
+            "eventSource",
+            "socket",
+            "SocketConnection",
+            "withWsToken",
+        ],
+    };
+
+    if config.target == Target::Fetch {
+        if has_ws {
+            symbols.push("WebsocketWrapper");
+            symbols.push("withWsToken");
+        }
+        if has_sse {
+            symbols.push("SSE");
+        }
+        if has_ws || has_sse {
+            symbols.push("ConnectionState");
+        }
+        #[cfg(feature = "react")]
+        {
+            if has_ws {
+                symbols.push("useWebsocket");
+            }
+            if has_sse {
+                symbols.push("useSSE");
+            }
+        }
+        if config.abort_all {
+            symbols.push("tracked");
+        }
+        if has_timeout {
+            symbols.push("timed");
+        }
+        if has_retries {
+            symbols.push("retried");
+        }
+        if has_ndjson {
+            symbols.push("readNdjson");
+        }
+    }
+
+    if config.emit_form_meta {
+        symbols.push("FormField");
+    }
+
+    symbols
+}
+
+/// The code of a single route, the method of its angular service apart.
+struct Route {
+    code: String,
+    service: Option<String>,
+}
+
+/// An error of a route generated on another thread, [`GenError`]s being kept
+/// for downcasting.
+#[cfg(feature = "parallel")]
+enum RouteError {
+    Gen(GenError),
+    Other(String),
+}
+
+#[cfg(feature = "parallel")]
+impl From<Box<dyn StdError>> for RouteError {
+    fn from(e: Box<dyn StdError>) -> Self {
+        match e.downcast::<GenError>() {
+            Ok(e) => RouteError::Gen(*e),
+            Err(e) => RouteError::Other(e.to_string()),
+        }
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl From<RouteError> for Box<dyn StdError> {
+    fn from(e: RouteError) -> Self {
+        match e {
+            RouteError::Gen(e) => e.into(),
+            RouteError::Other(e) => e.into(),
+        }
+    }
+}
+
+pub fn generate(requests: Requests) -> Result<String, Box<dyn StdError>> {
+    generate_with(requests, &GenerateConfig::default())
+}
+
+pub fn generate_with(
+    Requests {
+        mut requests,
+        tag_base_urls,
+    }: Requests,
+    config: &GenerateConfig,
+) -> Result<String, Box<dyn StdError>> {
+    requests.iter_mut().for_each(normalize_request);
+    generate_normalized(requests.iter().collect(), &tag_base_urls, config)
+}
+
+/// Like [`generate`], borrowing the requests. Only requests whose schemas have
+/// to be normalized are cloned.
+pub fn generate_ref(requests: &Requests) -> Result<String, Box<dyn StdError>> {
+    generate_ref_with(requests, &GenerateConfig::default())
+}
+
+/// Like [`generate_with`], borrowing the requests. Only requests whose schemas
+/// have to be normalized are cloned.
+pub fn generate_ref_with(
+    requests: &Requests,
+    config: &GenerateConfig,
+) -> Result<String, Box<dyn StdError>> {
+    let normalized = requests
+        .requests
+        .iter()
+        .map(normalized)
+        .collect::<Vec<Cow<RequestInfo>>>();
+
+    generate_normalized(
+        normalized.iter().map(Cow::as_ref).collect(),
+        &requests.tag_base_urls,
+        config,
+    )
+}
+
+/// Like [`generate`], one file per tag, see [`generate_multi_with`].
+pub fn generate_multi(
+    requests: Requests,
+) -> Result<BTreeMap<String, String>, Box<dyn StdError>> {
+    generate_multi_with(requests, &GenerateConfig::default())
+}
+
+/// Generates one file per tag keyed by its name, e.g. `admin.users.ts`, the
+/// routes being exported as with [`Layout::Flat`]. The base is emitted once
+/// into `base.ts`, imported by the tag files, and `index.ts` re-exports all
+/// of them, the tags `base` and `index` being reserved.
+pub fn generate_multi_with(
+    Requests {
+        mut requests,
+        tag_base_urls,
+    }: Requests,
+    config: &GenerateConfig,
+) -> Result<BTreeMap<String, String>, Box<dyn StdError>> {
+    if config.target == Target::Angular {
+        return Err("Multiple files are not supported by the angular target".into());
+    }
+
+    requests.retain(|v| included(v, config));
+    if config.exclude_internal {
+        requests.retain(|v| v.stability != Stability::Internal);
+    }
+    requests.iter_mut().for_each(normalize_request);
+
+    // the index re-exports all tag files, their exports may not collide
+    naming::flatten(&requests, &naming::resolve(&requests, config)?)?;
+
+    let mut tags = BTreeMap::<&str, Vec<&RequestInfo>>::new();
+    for v in &requests {
+        // the files of the base and the index
+        if v.tag == "base" || v.tag == "index" {
+            return Err(format!(
+                "The tag {} of {} {} is reserved for a shared file",
+                v.tag, v.method, v.path
+            )
+            .into());
+        }
+        tags.entry(v.tag.as_str()).or_default().push(v);
+    }
+
+    let tag_config = GenerateConfig {
+        layout: Layout::Flat,
+        include_base: false,
+        base_import: Some("./base".to_string()),
+        ..config.clone()
+    };
+
+    let mut files = BTreeMap::new();
+    let mut index = "export * from './base';\n".to_string();
+    for (tag, requests) in tags {
+        files.insert(
+            format!("{tag}.ts"),
+            generate_normalized(requests, &BTreeMap::new(), &tag_config)?,
+        );
+        index.push_str(&format!("export * from './{tag}';\n"));
+    }
+
+    let base_config = GenerateConfig {
+        include_base: true,
+        ..config.clone()
+    };
+    let (imports, classes) = base(&requests, &base_config);
+    let symbols = base_symbols(&requests, &base_config)
+        .into_iter()
+        .filter(|symbol| !exported(&classes, symbol))
+        .collect::<Vec<&str>>()
+        .join(", ");
+    let base = format!(
+        "{imports}{}\n{classes}\n{}export {{ {symbols} }};\n",
+        zod_import(config),
+        assign_base_urls(&tag_base_urls),
+    );
+
+    files.insert(
+        "base.ts".to_string(),
+        finish(format_js(&base, config)?, config),
+    );
+    files.insert("index.ts".to_string(), finish(index, config));
 
-    let ws = include_str!("base/websocket.ts");
-    let sse = include_str!("base/sse.ts");
+    Ok(files)
+}
+
+fn generate_normalized(
+    mut requests: Vec<&RequestInfo>,
+    tag_base_urls: &BTreeMap<String, String>,
+    config: &GenerateConfig,
+) -> Result<String, Box<dyn StdError>> {
+    requests.retain(|v| included(v, config));
+    if config.exclude_internal {
+        requests.retain(|v| v.stability != Stability::Internal);
+    }
+
+    if config.validate_options && config.target == Target::Angular {
+        return Err("Validating options is not supported by the angular target".into());
+    }
 
-    if requests.iter().any(|r| r.res_body.is_websocket()) {
-        classes.push_str(ws);
+    if !config.runtime_validation {
+        if config.target == Target::Angular {
+            return Err(
+                "Skipping runtime validation is not supported by the angular target"
+                    .into(),
+            );
+        }
+        if config.validate_options || config.enforce_formats || config.apply_body_defaults
+        {
+            return Err("Validating options, enforcing formats and applying body \
+                        defaults require runtime validation"
+                .into());
+        }
     }
 
-    if requests.iter().any(|r| r.res_body.is_sse()) {
-        classes.push_str(sse);
+    let mut names = naming::resolve(&requests, config)?;
+    if config.layout != Layout::Namespace {
+        if config.target == Target::Angular {
+            return Err(format!(
+                "The {} layout is not supported by the angular target",
+                if config.layout == Layout::Flat {
+                    "flat"
+                } else {
+                    "prefixed"
+                }
+            )
+            .into());
+        }
+        if config.emit_latest && config.layout == Layout::Flat {
+            return Err("The latest namespace is not supported by the flat layout".into());
+        }
+        names = naming::flatten(&requests, &names)?;
     }
 
-    let mut out = format!(
-        r#"import {{ z }} from 'zod';
+    let mut namespaces = BTreeMap::<&'static str, Vec<String>>::new();
+    let mut services = BTreeMap::<&'static str, Vec<String>>::new();
+    let (imports, classes) = base(&requests, config);
+
+    let zod_import = zod_import(config);
 
+    let mut out = match config.layout {
+        Layout::Namespace | Layout::Prefixed => format!(
+            r#"{imports}{zod_import}
 export namespace client {{
 
 {classes}
 "#
-    );
+        ),
+        Layout::Flat => format!("{imports}{zod_import}\n{classes}\n"),
+    };
 
-    let config = Config {
-        use_coerce_date: Default::default(),
-        array_wrapper: false,
-        explicit_min_max: false,
-        add_descriptions: true,
-        union_first: true,
-        add_default: false,
-        ignore_undefined: false,
+    out.push_str(&assign_base_urls(tag_base_urls));
+
+    let zod_config = &config.zod;
+    let (input, output) = match config.io_types {
+        true => ("z.input", "z.output"),
+        false => ("z.infer", "z.infer"),
     };
 
     let i_parser = Parser::new(Config {
         use_coerce_date: false,
-        ..config
+        ..*zod_config
+    });
+    let b_parser = Parser::new(Config {
+        use_coerce_date: false,
+        add_default: zod_config.add_default || config.apply_body_defaults,
+        ..*zod_config
     });
     let o_parser = Parser::new(Config {
         use_coerce_date: true,
@@ -85,205 +798,971 @@ export namespace client {{
         ignore_undefined: false,
         #[cfg(not(feature = "add-undefined"))]
         ignore_undefined: true,
-        ..config
+        ..*zod_config
     });
 
-    for v in &requests {
+    // translates the schema, with the native zod refinements of known string
+    // formats if enforced
+    let translate = |parser: &Parser, schema: &SchemaObject| {
+        if !config.enforce_formats {
+            return parser.parse_schema_object(schema);
+        }
+
+        let (marked, refined) = formats::mark(schema);
+        parser
+            .parse_schema_object(&marked)
+            .map(|zod| formats::restore(zod, &refined))
+    };
+
+    let kept = Kept {
+        descriptions: zod_config.add_descriptions,
+        defaults: zod_config.add_default,
+        bounds: zod_config.explicit_min_max,
+    };
+
+    let route = |v: &RequestInfo, name: &String| -> Result<Route, Box<dyn StdError>> {
+        // the zod translation and the schema walkers inline every schema
+        if let Some((slot, _)) = schemas(v)
+            .into_iter()
+            .find(|(_, schema)| !schema.definitions.is_empty())
+        {
+            return Err(format!(
+                "The {slot} schema of {name} references definitions, which can not be \
+                 translated, generate it with `inline_subschemas`"
+            )
+            .into());
+        }
+
         let mut s = String::new();
-        let name = make_name(v);
-        let struct_name = first_upper(&name);
+        let struct_name = first_upper(name);
+        let returns = |ty: &str| match config.explicit_return_types {
+            true => format!(": {ty}"),
+            false => String::new(),
+        };
+        let (path_arg, path_call) = match v.path_params.is_some() {
+            true => (
+                format!("pathParams: {struct_name}PathParams, "),
+                "pathParams, ",
+            ),
+            false => (String::new(), ""),
+        };
+        let (headers_arg, headers_call) = match v.req_headers.is_some() {
+            true => (format!("headers: {struct_name}Headers, "), "headers, "),
+            false => (String::new(), ""),
+        };
+        let route_headers = match v.req_headers.is_schema() && config.runtime_validation {
+            true =>
+                format!("options.unsafe ? headers : {name}HeadersSchema.parse(headers)"),
+            false => "headers".to_string(),
+        };
+        let query = match config.runtime_validation {
+            true => format!(
+                "options.unsafe ? params as {struct_name}Params : \
+                 {name}ParamsSchema.parse(params)"
+            ),
+            false => "params".to_string(),
+        };
+        let query_style = v.query_style.unwrap_or(config.query_style);
+        let query = match query_style {
+            QueryStyle::Comma => query,
+            style => format!("{query}, '{style}'"),
+        };
+        // parses a message of the route with the schema of `slot`
+        let parse = |slot: &str, schema: &str| match config.runtime_validation {
+            true => format!(
+                "(data) => options.unsafe ? data as {struct_name}{slot} : \
+                 {name}{schema}.parse(data)"
+            ),
+            false => format!("(data) => data as {struct_name}{slot}"),
+        };
 
-        match &v.req_params {
-            Kind::None => {},
+        if config.strict {
+            let lost = schemas(v)
+                .into_iter()
+                .flat_map(|(slot, schema)| {
+                    let kept = match slot {
+                        "req" => Kept {
+                            defaults: kept.defaults || config.apply_body_defaults,
+                            ..kept
+                        },
+                        _ => kept,
+                    };
+                    dropped(&schema.schema, kept, slot)
+                })
+                .collect::<Vec<String>>();
+
+            if !lost.is_empty() {
+                return Err(format!(
+                    "Schema information of {name} would be dropped:\n  {}",
+                    lost.join("\n  ")
+                )
+                .into());
+            }
+        }
+
+        match &v.path_params {
+            Kind::None =>
+                if !path::placeholders(&v.path).is_empty() {
+                    return Err(format!(
+                        "Path placeholders of {name} need path params, see \
+                         `with_path_params`"
+                    )
+                    .into());
+                },
 
             Kind::Any => {
                 s.push_str(&format!(
-                    "    export type {struct_name}Params = Record<string, string>;\n\n"
+                    "    export type {struct_name}PathParams = Record<string, \
+                     string>;\n\n"
                 ));
+                s.push_str(&path::function(v, name, &struct_name, false));
             },
 
             Kind::Schema(schema) => {
-                let zod =
-                    i_parser
-                        .parse_schema_object(&schema.schema)
-                        .inspect_err(|_| {
-                            #[cfg(feature = "binary")]
-                            eprintln!("Error in client schema generation of: {name}")
-                        })?;
+                let covered = schema.schema.object.as_ref().map(|o| &o.properties);
+                let missing = path::placeholders(&v.path)
+                    .into_iter()
+                    .filter(|p| !covered.is_some_and(|c| c.contains_key(*p)))
+                    .collect::<Vec<&str>>();
+                if !missing.is_empty() {
+                    return Err(format!(
+                        "Path placeholders of {name} are not covered by its path \
+                         params: {}",
+                        missing.join(", ")
+                    )
+                    .into());
+                }
 
-                s.push_str(&format!("    const {name}ParamsSchema = {};\n", zod));
-                s.push_str(&format!(
-                    "    export type {struct_name}Params = z.input<typeof \
-                     {name}ParamsSchema>;\n\n"
+                if config.runtime_validation {
+                    let zod = translate(&i_parser, &schema.schema).inspect_err(|_| {
+                        #[cfg(feature = "binary")]
+                        eprintln!("Error in path params schema generation of: {name}")
+                    })?;
+
+                    s.push_str(&format!("    const {name}PathParamsSchema = {};\n", zod));
+                    s.push_str(&format!(
+                        "    export type {struct_name}PathParams = {input}<typeof \
+                         {name}PathParamsSchema>;\n\n"
+                    ));
+                } else {
+                    s.push_str(&typescript::declaration(
+                        &format!("{struct_name}PathParams"),
+                        &schema.schema,
+                    ));
+                }
+                s.push_str(&path::function(
+                    v,
+                    name,
+                    &struct_name,
+                    config.runtime_validation,
                 ));
             },
 
-            kind => panic!("Unexpected kind: {kind}"),
+            kind =>
+                return Err(GenError::unexpected_kind(v, Slot::PathParams, kind).into()),
         }
 
-        match &v.req_body {
+        match &v.req_params {
             Kind::None => {},
 
             Kind::Any => {
                 s.push_str(&format!(
-                    "    type {struct_name}Req = Blob | FormData | string;\n\n"
+                    "    export type {struct_name}Params = Record<string, string>;\n\n"
                 ));
             },
 
             Kind::Schema(schema) => {
-                let zod =
-                    i_parser
-                        .parse_schema_object(&schema.schema)
-                        .inspect_err(|_| {
-                            #[cfg(feature = "binary")]
-                            eprintln!("Error in client schema generation of: {name}")
-                        })?;
-                s.push_str(&format!("    const {name}ReqSchema = {};\n", zod));
-                s.push_str(&format!(
-                    "    export type {struct_name}Req = z.input<typeof \
-                     {name}ReqSchema>;\n\n"
-                ));
+                if config.runtime_validation {
+                    let zod = translate(&i_parser, &schema.schema).inspect_err(|_| {
+                        #[cfg(feature = "binary")]
+                        eprintln!("Error in client schema generation of: {name}")
+                    })?;
+
+                    s.push_str(&format!("    const {name}ParamsSchema = {};\n", zod));
+                    s.push_str(&format!(
+                        "    export type {struct_name}Params = {input}<typeof \
+                         {name}ParamsSchema>;\n\n"
+                    ));
+                } else {
+                    s.push_str(&typescript::declaration(
+                        &format!("{struct_name}Params"),
+                        &schema.schema,
+                    ));
+                }
+
+                if config.emit_enum_values {
+                    s.push_str(&enum_constants(
+                        &schema.schema,
+                        &format!("{struct_name}Params"),
+                    ));
+                }
+
+                if config.emit_enum_objects {
+                    s.push_str(&enum_objects(
+                        &schema.schema,
+                        &format!("{struct_name}Params"),
+                    ));
+                }
             },
 
-            kind => panic!("Unexpected kind: {kind}"),
+            kind => return Err(GenError::unexpected_kind(v, Slot::Params, kind).into()),
         }
 
-        match &v.res_body {
+        match &v.req_headers {
             Kind::None => {},
+
             Kind::Any => {
-                s.push_str(&format!("    export type {struct_name}Res = unknown;\n\n"));
+                s.push_str(&format!(
+                    "    export type {struct_name}Headers = Record<string, string>;\n\n"
+                ));
             },
+
             Kind::Schema(schema) => {
-                let zod =
-                    o_parser
-                        .parse_schema_object(&schema.schema)
-                        .inspect_err(|_| {
-                            #[cfg(feature = "binary")]
-                            eprintln!("Error in server schema generation of: {name}")
-                        })?;
+                if let Some(header) = headers::invalid(&schema.schema) {
+                    return Err(format!(
+                        "Header {header} of {name} must be a string, number or boolean"
+                    )
+                    .into());
+                }
+
+                if config.runtime_validation {
+                    let zod = translate(&i_parser, &schema.schema).inspect_err(|_| {
+                        #[cfg(feature = "binary")]
+                        eprintln!("Error in headers schema generation of: {name}")
+                    })?;
+
+                    s.push_str(&format!("    const {name}HeadersSchema = {};\n", zod));
+                    s.push_str(&format!(
+                        "    export type {struct_name}Headers = {input}<typeof \
+                         {name}HeadersSchema>;\n\n"
+                    ));
+                } else {
+                    s.push_str(&typescript::declaration(
+                        &format!("{struct_name}Headers"),
+                        &schema.schema,
+                    ));
+                }
+            },
 
-                s.push_str(&format!("    const {name}ResSchema = {};\n", zod));
+            kind => return Err(GenError::unexpected_kind(v, Slot::Headers, kind).into()),
+        }
+
+        match &v.req_body {
+            Kind::None => {},
+
+            Kind::Any => {
                 s.push_str(&format!(
-                    "    export type {struct_name}Res = z.output<typeof \
-                     {name}ResSchema>;\n\n"
+                    "    type {struct_name}Req = Blob | FormData | string;\n\n"
                 ));
             },
+
+            Kind::Text => {
+                s.push_str(&format!("    export type {struct_name}Req = string;\n\n"));
+            },
+
+            Kind::Schema(schema) => {
+                if v.body_encoding == BodyEncoding::UrlEncoded {
+                    if let Some(field) = headers::invalid(&schema.schema) {
+                        return Err(format!(
+                            "Field {field} of the url encoded body of {name} must be a \
+                             string, number or boolean"
+                        )
+                        .into());
+                    }
+                }
+
+                if v.body_encoding == BodyEncoding::Multipart
+                    && schema.schema.object.is_none()
+                {
+                    return Err(format!(
+                        "The multipart body of {name} must be an object"
+                    )
+                    .into());
+                }
+
+                if config.runtime_validation {
+                    // files are not validated, only the other fields
+                    let (fields, files) = match v.body_encoding {
+                        BodyEncoding::Multipart => split_files(&schema.schema),
+                        _ => (schema.schema.clone(), None),
+                    };
+
+                    let zod = translate(&b_parser, &fields).inspect_err(|_| {
+                        #[cfg(feature = "binary")]
+                        eprintln!("Error in client schema generation of: {name}")
+                    })?;
+                    s.push_str(&format!("    const {name}ReqSchema = {};\n", zod));
+                    s.push_str(&format!(
+                        "    export type {struct_name}Req = {input}<typeof \
+                         {name}ReqSchema>{};\n\n",
+                        files.map(|files| format!(" & {files}")).unwrap_or_default()
+                    ));
+                } else {
+                    s.push_str(&typescript::declaration(
+                        &format!("{struct_name}Req"),
+                        &schema.schema,
+                    ));
+                }
+
+                if config.emit_enum_values {
+                    s.push_str(&enum_constants(
+                        &schema.schema,
+                        &format!("{struct_name}Req"),
+                    ));
+                }
+
+                if config.emit_enum_objects {
+                    s.push_str(&enum_objects(
+                        &schema.schema,
+                        &format!("{struct_name}Req"),
+                    ));
+                }
+
+                if let Some(meta) = config
+                    .emit_form_meta
+                    .then(|| form_meta(&schema.schema))
+                    .flatten()
+                {
+                    s.push_str(&format!(
+                        "    export const {name}FormMeta: FormField[] = {meta};\n\n"
+                    ));
+                }
+            },
+
+            kind => return Err(GenError::unexpected_kind(v, Slot::Body, kind).into()),
+        }
+
+        match &v.res_body {
+            Kind::None => {},
+            Kind::Any => {
+                s.push_str(&format!("    export type {struct_name}Res = unknown;\n\n"));
+            },
+            Kind::Text => {
+                s.push_str(&format!("    export type {struct_name}Res = string;\n\n"));
+            },
+            Kind::Binary => {
+                s.push_str(&format!("    export type {struct_name}Res = Blob;\n\n"));
+            },
+            Kind::Schema(schema) | Kind::TextSchema(schema) | Kind::Ndjson(schema) => {
+                if config.runtime_validation {
+                    let zod = translate(&o_parser, &schema.schema).inspect_err(|_| {
+                        #[cfg(feature = "binary")]
+                        eprintln!("Error in server schema generation of: {name}")
+                    })?;
+
+                    s.push_str(&format!("    const {name}ResSchema = {};\n", zod));
+                    s.push_str(&format!(
+                        "    export type {struct_name}Res = {output}<typeof \
+                         {name}ResSchema>;\n\n"
+                    ));
+                } else {
+                    s.push_str(&typescript::declaration(
+                        &format!("{struct_name}Res"),
+                        &schema.schema,
+                    ));
+                }
+
+                if config.emit_enum_objects {
+                    s.push_str(&enum_objects(
+                        &schema.schema,
+                        &format!("{struct_name}Res"),
+                    ));
+                }
+            },
             Kind::Websocket {
                 client_msg,
                 server_msg,
             } => {
-                let client_msg = i_parser
-                    .parse_schema_object(&client_msg.schema)
-                    .inspect_err(|_| {
+                if config.runtime_validation {
+                    let client_msg = translate(&i_parser, &client_msg.schema)
+                        .inspect_err(|_| {
+                            #[cfg(feature = "binary")]
+                            eprintln!(
+                                "Error in websocket client schema generation of: {name}"
+                            )
+                        })?;
+                    let server_msg = translate(&o_parser, &server_msg.schema)
+                        .inspect_err(|_| {
+                            #[cfg(feature = "binary")]
+                            eprintln!("Error in websocket server generation of: {name}")
+                        })?;
+
+                    s.push_str(&format!(
+                        "    const {name}ClientMsgSchema = {};\n",
+                        client_msg
+                    ));
+                    s.push_str(&format!(
+                        "    export type {struct_name}ClientMsg = {output}<typeof \
+                         {name}ClientMsgSchema>;\n"
+                    ));
+
+                    s.push_str(&format!(
+                        "    const {name}ServerMsgSchema = {};\n",
+                        server_msg
+                    ));
+                    s.push_str(&format!(
+                        "    export type {struct_name}ServerMsg = {output}<typeof \
+                         {name}ServerMsgSchema>;\n"
+                    ));
+                } else {
+                    s.push_str(&typescript::declaration(
+                        &format!("{struct_name}ClientMsg"),
+                        &client_msg.schema,
+                    ));
+                    s.push_str(&typescript::declaration(
+                        &format!("{struct_name}ServerMsg"),
+                        &server_msg.schema,
+                    ));
+                }
+
+                s.push_str(&format!(
+                    "    export type {struct_name}Websocket = \
+                     {wrapper}<{struct_name}ClientMsg, {struct_name}ServerMsg>;\n\n",
+                    wrapper = match config.target {
+                        Target::Fetch => "WebsocketWrapper",
+                        Target::Angular => "SocketConnection",
+                    },
+                ));
+            },
+            Kind::SSE(schema) => {
+                if config.runtime_validation {
+                    let zod = translate(&o_parser, &schema.schema).inspect_err(|_| {
                         #[cfg(feature = "binary")]
-                        eprintln!(
-                            "Error in websocket client schema generation of: {name}"
-                        )
+                        eprintln!("Error in server schema generation of: {name}")
                     })?;
-                let server_msg = o_parser
-                    .parse_schema_object(&server_msg.schema)
-                    .inspect_err(|_| {
+
+                    s.push_str(&format!("    const {name}Msg = {};\n", zod));
+                    s.push_str(&format!(
+                        "    export type {struct_name}Msg = {output}<typeof \
+                         {name}Msg>;\n\n"
+                    ));
+                } else {
+                    s.push_str(&typescript::declaration(
+                        &format!("{struct_name}Msg"),
+                        &schema.schema,
+                    ));
+                }
+                if config.target == Target::Fetch {
+                    s.push_str(&format!(
+                        "    export type {struct_name}SSE = SSE<{struct_name}Msg>;\n\n"
+                    ));
+                }
+            },
+            Kind::SSEEvents(events) => {
+                let mut schemas = Vec::new();
+                let mut messages = Vec::new();
+                for (event, schema) in events {
+                    if !config.runtime_validation {
+                        messages.push(format!(
+                            "{{ event: {event:?}; data: {} }}",
+                            typescript::ts_type(&schema.schema)
+                        ));
+                        continue;
+                    }
+
+                    let zod = translate(&o_parser, &schema.schema).inspect_err(|_| {
                         #[cfg(feature = "binary")]
-                        eprintln!("Error in websocket server generation of: {name}")
+                        eprintln!("Error in server schema generation of: {name}")
                     })?;
 
+                    schemas.push(format!("{event:?}: {zod}"));
+                    messages.push(format!(
+                        "{{ event: {event:?}; data: {output}<typeof \
+                         {name}Events[{event:?}]> }}"
+                    ));
+                }
+
+                if config.runtime_validation {
+                    s.push_str(&format!(
+                        "    const {name}Events = {{ {} }};\n",
+                        schemas.join(", ")
+                    ));
+                }
                 s.push_str(&format!(
-                    "    const {name}ClientMsgSchema = {};\n",
-                    client_msg
+                    "    export type {struct_name}Msg = {};\n\n",
+                    messages.join(" | ")
                 ));
                 s.push_str(&format!(
-                    "    export type {struct_name}ClientMsg = z.output<typeof \
-                     {name}ClientMsgSchema>;\n"
+                    "    export type {struct_name}SSE = SSE<{struct_name}Msg>;\n\n"
                 ));
+            },
+            Kind::StatusUnion(bodies) => {
+                let mut schemas = Vec::new();
+                let mut variants = Vec::new();
+                for (code, schema) in bodies {
+                    if !config.runtime_validation {
+                        schemas.push(format!("{code}: null"));
+                        variants.push(format!(
+                            "{{ status: {code}; body: {} }}",
+                            typescript::ts_type(&schema.schema)
+                        ));
+                        continue;
+                    }
+
+                    let zod = translate(&o_parser, &schema.schema).inspect_err(|_| {
+                        #[cfg(feature = "binary")]
+                        eprintln!("Error in server schema generation of: {name}")
+                    })?;
+
+                    schemas.push(format!("{code}: {zod}"));
+                    variants.push(format!(
+                        "{{ status: {code}; body: {output}<typeof \
+                         {name}ResSchemas[{code}]> }}"
+                    ));
+                }
 
                 s.push_str(&format!(
-                    "    const {name}ServerMsgSchema = {};\n",
-                    server_msg
+                    "    const {name}ResSchemas = {{ {} }};\n",
+                    schemas.join(", ")
                 ));
                 s.push_str(&format!(
-                    "    export type {struct_name}ServerMsg = z.output<typeof \
-                     {name}ServerMsgSchema>;\n"
+                    "    export type {struct_name}Res = {};\n\n",
+                    variants.join(" | ")
                 ));
+            },
+        }
 
+        match &v.res_headers {
+            Kind::None => {},
+
+            Kind::Schema(schema) => {
+                if let Some(header) = headers::invalid(&schema.schema) {
+                    return Err(format!(
+                        "Response header {header} of {name} must be a string, number or \
+                         boolean"
+                    )
+                    .into());
+                }
+
+                if config.runtime_validation {
+                    let zod = translate(&o_parser, &schema.schema).inspect_err(|_| {
+                        #[cfg(feature = "binary")]
+                        eprintln!(
+                            "Error in response headers schema generation of: {name}"
+                        )
+                    })?;
+
+                    s.push_str(&format!("    const {name}ResHeadersSchema = {};\n", zod));
+                    s.push_str(&format!(
+                        "    export type {struct_name}ResHeaders = {output}<typeof \
+                         {name}ResHeadersSchema>;\n"
+                    ));
+                } else {
+                    s.push_str(&typescript::declaration(
+                        &format!("{struct_name}ResHeaders"),
+                        &schema.schema,
+                    ));
+                }
+
+                let kinds = headers::kinds(&schema.schema)
+                    .into_iter()
+                    .map(|(header, kind)| {
+                        format!("{}: '{kind}'", typescript::key(header))
+                    })
+                    .collect::<Vec<String>>();
                 s.push_str(&format!(
-                    "    export type {struct_name}Websocket = \
-                     WebsocketWrapper<{struct_name}ClientMsg, \
-                     {struct_name}ServerMsg>;\n\n"
+                    "    const {name}ResHeaders = (headers: Headers) => \
+                     ({})(readHeaders(headers, {{ {} }}));\n\n",
+                    parse("ResHeaders", "ResHeadersSchema"),
+                    kinds.join(", ")
                 ));
             },
-            Kind::SSE(schema) => {
-                let zod =
-                    o_parser
-                        .parse_schema_object(&schema.schema)
-                        .inspect_err(|_| {
-                            #[cfg(feature = "binary")]
-                            eprintln!("Error in server schema generation of: {name}")
-                        })?;
 
-                s.push_str(&format!("    const {name}Msg = {};\n", zod));
-                s.push_str(&format!(
-                    "    export type {struct_name}Msg = z.output<typeof {name}Msg>;\n\n"
-                ));
-                s.push_str(&format!(
-                    "    export type {struct_name}SSE = SSE<{struct_name}Msg>;\n\n"
+            kind =>
+                return Err(GenError::unexpected_kind(v, Slot::ResHeaders, kind).into()),
+        }
+
+        if !v.error_schemas.is_empty() {
+            let mut schemas = Vec::new();
+            let mut errors = Vec::new();
+            for (code, schema) in &v.error_schemas {
+                if !config.runtime_validation {
+                    schemas.push(format!("{code}: null"));
+                    errors.push(format!(
+                        "{{ status: {code}; body: {} }}",
+                        typescript::ts_type(&schema.schema)
+                    ));
+                    continue;
+                }
+
+                let zod = translate(&o_parser, &schema.schema).inspect_err(|_| {
+                    #[cfg(feature = "binary")]
+                    eprintln!("Error in error schema generation of: {name}")
+                })?;
+
+                schemas.push(format!("{code}: {zod}"));
+                errors.push(format!(
+                    "{{ status: {code}; body: {output}<typeof {name}Errors[{code}]> }}"
                 ));
-            },
+            }
+
+            s.push_str(&format!(
+                "    const {name}Errors = {{ {} }};\n",
+                schemas.join(", ")
+            ));
+            s.push_str(&format!(
+                "    export type {struct_name}Error = {};\n\n",
+                errors.join(" | ")
+            ));
         }
 
-        if let Deprecated::WithInfo(path, method, tag) = &v.deprecated {
-            let new =
-                make_name_raw(method.to_string(), path.to_string(), tag.to_string());
+        let sensitive = [
+            ("pathParams", &v.path_params),
+            ("params", &v.req_params),
+            ("headers", &v.req_headers),
+            ("req", &v.req_body),
+            ("res", &v.res_body),
+        ]
+        .into_iter()
+        .filter_map(|(slot, kind)| match kind {
+            Kind::Schema(schema) => Some((slot, sensitive_fields(&schema.schema))),
+            _ => None,
+        })
+        .filter(|(_, fields)| !fields.is_empty())
+        .map(|(slot, fields)| {
+            format!(
+                "{slot}: [{}]",
+                fields
+                    .iter()
+                    .map(|field| format!("'{field}'"))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )
+        })
+        .collect::<Vec<String>>();
 
+        if !sensitive.is_empty() {
             s.push_str(&format!(
-                "    /** @deprecated Please use {{@link {new}}} instead */\n",
+                "    export const {name}SensitiveFields = {{ {} }};\n\n",
+                sensitive.join(", ")
             ));
-        } else if matches!(&v.deprecated, &Deprecated::Simple(true)) {
-            s.push_str("    /** @deprecated */\n");
         }
 
+        let deprecation = if let Deprecated::WithInfo(path, method, tag) = &v.deprecated {
+            let target = requests
+                .iter()
+                .zip(&names)
+                .find(|(r, _)| &r.path == path && r.method == *method && &r.tag == tag);
+
+            match target {
+                Some((target, new)) => {
+                    let signature = match config.verbose_deprecation {
+                        true => format!(", called as `{}`", call_signature(target, new)),
+                        false => String::new(),
+                    };
+
+                    format!(
+                        "    /** @deprecated Please use {{@link {new}}} \
+                         instead{signature} */\n"
+                    )
+                },
+                // a link would dangle
+                None => {
+                    let missing = format!(
+                        "The replacement {method} {path} of tag {tag} deprecating \
+                         {name} is not generated"
+                    );
+                    if config.strict {
+                        return Err(missing.into());
+                    }
+
+                    format!(
+                        "    /** @deprecated Please use `{method} {path}` instead */\n"
+                    )
+                },
+            }
+        } else if let Some(reason) = v.deprecated.reason() {
+            format!("    /** @deprecated {} */\n", reason.replace("*/", "*\\/"))
+        } else if matches!(&v.deprecated, &Deprecated::Simple(true)) {
+            "    /** @deprecated */\n".to_string()
+        } else {
+            String::new()
+        };
+
+        let deprecation = match v.stability.tag() {
+            Some(tag) => format!("{deprecation}    /** {tag} */\n"),
+            None => deprecation,
+        };
+
         const TABS: &str = "    ";
 
         let comment = if v.error_codes.is_empty() {
-            String::new()
+            deprecation
         } else {
             format!(
-                "{TABS}/**\n{TABS} * Error responses:\n{TABS} *\n{TABS} * {}\n{TABS} \
-                 */\n",
-                v.error_codes
+                "{deprecation}{TABS}/**\n{TABS} * Error responses:\n{TABS} *\n{TABS} * \
+                 {}\n{TABS} */\n",
+                v.error_ranges()
                     .iter()
-                    .map(|(code, info)| { format!("{code}: {info}") })
+                    .map(|(codes, info)| format!("{}: {info}", status::label(codes)))
                     .collect::<Vec<String>>()
                     .join(&format!("\n{TABS} *\n{TABS} * ")),
             )
         };
 
+        // the descriptions of the schemas, from the doc comments of their types
+        let tags = [
+            ("pathParams", &v.docs.path_params),
+            ("req", &v.docs.req_body),
+            ("params", &v.docs.req_params),
+            ("headers", &v.docs.req_headers),
+        ]
+        .into_iter()
+        .filter_map(|(param, doc)| Some(format!("@param {param} {}", doc.as_ref()?)))
+        .chain(v.docs.res_body.iter().map(|doc| format!("@returns {doc}")))
+        .collect::<Vec<String>>();
+
+        // the summary and description of the route come first
+        let paragraphs = [v.summary.clone(), v.description.clone()]
+            .into_iter()
+            .flatten()
+            .chain((!tags.is_empty()).then(|| tags.join("\n")))
+            .collect::<Vec<String>>();
+
+        let comment = if paragraphs.is_empty() {
+            comment
+        } else {
+            format!(
+                "{comment}{TABS}/**\n{}{TABS} */\n",
+                paragraphs
+                    .join("\n\n")
+                    .lines()
+                    .map(|line| match line.is_empty() {
+                        true => format!("{TABS} *\n"),
+                        false => format!("{TABS} * {}\n", line.replace("*/", "*\\/")),
+                    })
+                    .collect::<String>()
+            )
+        };
+
+        if v.req_headers.is_some() && (v.res_body.is_sse() || v.res_body.is_websocket()) {
+            return Err(format!(
+                "Request headers of {name} can not be sent by event sources and \
+                 websockets"
+            )
+            .into());
+        }
+
+        if v.res_headers.is_some() && (v.res_body.is_sse() || v.res_body.is_websocket()) {
+            return Err(format!(
+                "Response headers of {name} can not be read from event sources and \
+                 websockets"
+            )
+            .into());
+        }
+
+        if !v.error_schemas.is_empty()
+            && (v.res_body.is_sse() || v.res_body.is_websocket())
+        {
+            return Err(format!(
+                "Error schemas of {name} do not apply to event sources and websockets"
+            )
+            .into());
+        }
+
+        if v.reconnect.is_some() && !v.res_body.is_sse() && !v.res_body.is_websocket() {
+            return Err(format!(
+                "Reconnect policies of {name} only apply to event sources and websockets"
+            )
+            .into());
+        }
+
+        if (!v.ws_protocols.is_empty() || v.ws_token_param.is_some())
+            && !v.res_body.is_websocket()
+        {
+            return Err(format!(
+                "Websocket protocols and token params of {name} only apply to websockets"
+            )
+            .into());
+        }
+
+        if v.timeout_ms.is_some() && (v.res_body.is_sse() || v.res_body.is_websocket()) {
+            return Err(format!(
+                "Timeouts of {name} do not apply to event sources and websockets, see \
+                 `connectTimeoutMs`"
+            )
+            .into());
+        }
+
+        if v.method == Method::Head
+            && (v.res_body.is_some() || !v.error_schemas.is_empty())
+        {
+            return Err(format!(
+                "HEAD responses of {name} have no body, see `with_res_headers`"
+            )
+            .into());
+        }
+
+        if v.res_body.is_ndjson()
+            && (!v.error_schemas.is_empty() || v.res_headers.is_some() || v.long_poll)
+        {
+            return Err(format!(
+                "Error schemas, response headers and long polling of {name} do not \
+                 apply to NDJSON streams"
+            )
+            .into());
+        }
+
+        if v.retries.is_some() && (v.res_body.is_sse() || v.res_body.is_websocket()) {
+            return Err(format!(
+                "Retries of {name} do not apply to event sources and websockets"
+            )
+            .into());
+        }
+
+        if config.target == Target::Angular {
+            if v.req_headers.is_some() {
+                return Err(format!(
+                    "Request headers of {name} are not supported by the angular target"
+                )
+                .into());
+            }
+
+            if matches!(v.res_body, Kind::SSEEvents(_)) {
+                return Err(format!(
+                    "Named events of {name} are not supported by the angular target"
+                )
+                .into());
+            }
+
+            if v.body_encoding != BodyEncoding::Json {
+                return Err(format!(
+                    "Url encoded and multipart bodies of {name} are not supported by \
+                     the angular target"
+                )
+                .into());
+            }
+
+            if v.timeout_ms.is_some() {
+                return Err(format!(
+                    "Timeouts of {name} are not supported by the angular target"
+                )
+                .into());
+            }
+
+            if v.retries.is_some() {
+                return Err(format!(
+                    "Retries of {name} are not supported by the angular target"
+                )
+                .into());
+            }
+
+            if matches!(v.res_body, Kind::TextSchema(_)) {
+                return Err(format!(
+                    "Validated text responses of {name} are not supported by the \
+                     angular target"
+                )
+                .into());
+            }
+
+            if query_style != QueryStyle::Comma {
+                return Err(format!(
+                    "Query styles of {name} are not supported by the angular target"
+                )
+                .into());
+            }
+
+            if !v.error_schemas.is_empty() {
+                return Err(format!(
+                    "Error schemas of {name} are not supported by the angular target"
+                )
+                .into());
+            }
+
+            if v.res_headers.is_some() {
+                return Err(format!(
+                    "Response headers of {name} are not supported by the angular target"
+                )
+                .into());
+            }
+
+            if v.res_body.is_ndjson() {
+                return Err(format!(
+                    "NDJSON streams of {name} are not supported by the angular target"
+                )
+                .into());
+            }
+
+            if matches!(v.res_body, Kind::StatusUnion(_)) {
+                return Err(format!(
+                    "Status unions of {name} are not supported by the angular target"
+                )
+                .into());
+            }
+
+            if v.pagination.is_some() || v.existence_check || v.long_poll {
+                return Err(format!(
+                    "Pagination, existence checks and long polling of {name} are not \
+                     supported by the angular target"
+                )
+                .into());
+            }
+
+            if v.reconnect.is_some() {
+                return Err(format!(
+                    "Reconnect policies of {name} are not supported by the angular \
+                     target"
+                )
+                .into());
+            }
+
+            return Ok(Route {
+                code: s,
+                service: Some(angular::method(v, name, &struct_name, &comment)),
+            });
+        }
+
         if v.res_body.is_sse() {
-            // todo!() make https dynamic
             s.push_str(&format!(
-                "{comment}    export function {name}({req_params}): {struct_name}SSE {{
-        \
-                 const url = (!options.baseUrl || options.baseUrl.startsWith('/'))
-            \
-                 && 'location' in global
-            ? `https://${{(global.location as any).host}}${{options.baseUrl}}`
-            : options.baseUrl;
+                "{comment}    export function {name}({path_arg}{req_params}){returns} {{
+        const url = urlOf('{tag}', `{path}`);
+        const absoluteUrl = url.startsWith('/') && 'location' in globalThis
+            ? `${{location.protocol}}//${{location.host}}${{url}}`
+            : url;
 
         return new SSE(
             () => new EventSource(
-                `${{url}}{path}{params_suffix}`,
-                {{ ...options.globalInit, withCredentials: true }}
+                `${{absoluteUrl}}{params_suffix}`,
+                {{ ...options.globalInit, withCredentials: {credentials} }}
             ),
-            (data) => options.unsafe ? data as {struct_name}Msg : {name}Msg.parse(data),
+            {parse}{reconnect}
         )
     }}\n",
+                // the annotated return type
+                returns = returns(&format!("{struct_name}SSE")),
+                // the events default to `message` before the policy
+                reconnect = match (&v.res_body, reconnect_policy(v)) {
+                    (_, policy) if policy.is_empty() => policy,
+                    (Kind::SSEEvents(_), policy) => policy[1..].to_string(),
+                    (_, policy) => format!("\n            ['message']{policy}"),
+                },
+                credentials = v.credentials,
+                // parse the messages, by their event name for named events
+                parse = match v.res_body {
+                    Kind::SSEEvents(ref events) if !config.runtime_validation => format!(
+                        "(data, event) => ({{ event, data }}) as {struct_name}Msg,
+            [{}],",
+                        events
+                            .keys()
+                            .map(|event| format!("{event:?}"))
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ),
+                    Kind::SSEEvents(_) => format!(
+                        "(data, event) => ({{ event, data: options.unsafe ? data : \
+                         {name}Events[event as keyof typeof {name}Events].parse(data) \
+                         }}) as {struct_name}Msg,
+            Object.keys({name}Events),"
+                    ),
+                    _ => format!("{},", parse("Msg", "Msg")),
+                },
                 // where to fetch
-                path = v.path,
+                path = path::template(v, name),
+                // the namespace the base url is resolved for
+                tag = v.tag,
                 // make the query string
                 params_suffix = if v.req_params.is_some() {
-                    format!(
-                        "${{makeQuery(options.unsafe ? params as {struct_name}Params : \
-                         {name}ParamsSchema.parse(params))}}"
-                    )
+                    format!("${{makeQuery({query})}}")
                 } else {
                     String::new()
                 },
@@ -295,28 +1774,39 @@ export namespace client {{
                 },
             ));
         } else if v.res_body.is_websocket() {
+            // make the query string
+            let params_suffix = if v.req_params.is_some() {
+                format!("${{makeQuery({query})}}")
+            } else {
+                String::new()
+            };
+
             s.push_str(&format!(
-                "{comment}    export function {name}({req_params}): \
-                 {struct_name}Websocket {{
+                "{comment}    export function {name}({path_arg}{req_params}){returns} {{
         const protocol = location.protocol === 'https:' ? 'wss://' : 'ws://'
 
-        const wsBaseUrl = (!options.baseUrl || options.baseUrl.startsWith('/'))
-            ? `${{protocol}}${{location.host}}${{options.baseUrl}}`
-            : (protocol + options.baseUrl.replace(/^https:\\/\\//, \
-                 '').replace(/^http:\\/\\//, ''))
+        const url = urlOf('{tag}', `{path}`);
+        const wsUrl = url.startsWith('/')
+            ? `${{protocol}}${{location.host}}${{url}}`
+            : (protocol + url.replace(/^https:\\/\\//, '').replace(/^http:\\/\\//, ''))
 
         return new WebsocketWrapper(
             () => new WebSocket(
-                `${{wsBaseUrl}}{path}{params_suffix}`
+                {url}{protocols}
             ),
-            (data) => options.unsafe ? data as {struct_name}ClientMsg : \
-                 {name}ClientMsgSchema.parse(data),
-            (data) => options.unsafe ? data as {struct_name}ServerMsg : \
-                 {name}ServerMsgSchema.parse(data)
+            {client_msg},
+            {server_msg}{reconnect}
         )
     }}\n",
+                reconnect = reconnect_policy(v),
+                client_msg = parse("ClientMsg", "ClientMsgSchema"),
+                server_msg = parse("ServerMsg", "ServerMsgSchema"),
+                url = ws_url(v, format!("`${{wsUrl}}{params_suffix}`")),
+                protocols = ws_protocols(v),
                 // the function name
                 name = name,
+                // the annotated return type
+                returns = returns(&format!("{struct_name}Websocket")),
                 // the request query parameter
                 req_params = if v.req_params.is_some() {
                     format!("params: {struct_name}Params, ")
@@ -324,121 +1814,3430 @@ export namespace client {{
                     String::new()
                 },
                 // where to fetch
-                path = v.path,
-                // make the query string
-                params_suffix = if v.req_params.is_some() {
-                    format!(
-                        "${{makeQuery(options.unsafe ? params as {struct_name}Params : \
-                         {name}ParamsSchema.parse(params))}}"
-                    )
-                } else {
-                    String::new()
-                },
+                path = path::template(v, name),
+                // the namespace the base url is resolved for
+                tag = v.tag,
             ));
         } else {
-            s.push_str(&format!(
-                "{comment}    export function {name}({req_json}{req_params}init: \
-                 RequestInit = {{}}): PromiseWrapper<{res_name}> {{
-        return new PromiseWrapper(
-            options.fetch(
-                new Request(
-                    options.baseUrl + '{path}'{params_suffix},
-                    {{
-                        method: '{method}',
-                        body: {req},
-                        credentials: 'include',
-                        ...options.globalInit,
-                        ...init,{headers_addition}
-                    }}
-                )
-            ){res}
-        )
-    }}\n",
-                // the function name
-                name = name,
-                // the request body parameter
-                req_json = if v.req_body.is_some() {
-                    format!("req: {struct_name}Req, ")
-                } else {
-                    String::new()
-                },
-                // the request query parameter
-                req_params = if v.req_params.is_some() {
-                    format!("params: {struct_name}Params, ")
-                } else {
-                    String::new()
-                },
-                // the response type
-                res_name = if v.res_body.is_some() {
-                    format!("{struct_name}Res")
-                } else {
-                    "Response".to_string()
-                },
+            let req_json = if v.req_body.is_some() {
+                format!("req: {struct_name}Req, ")
+            } else {
+                String::new()
+            };
+            let req_params = if v.req_params.is_some() {
+                format!("params: {struct_name}Params, ")
+            } else {
+                String::new()
+            };
+
+            let new_request = format!(
+                "new Request(
+            urlOf('{tag}', {path}){params_suffix},
+            {{
+                method: '{method}',
+                body: {req},
+                credentials: '{credentials}',
+                ...options.globalInit,
+                ...init,{headers_addition}
+            }}
+        )",
                 // where to fetch
-                path = v.path,
+                path = path::expr(v, name),
+                // the namespace the base url is resolved for
+                tag = v.tag,
                 // make the query string
                 params_suffix = if v.req_params.is_some() {
-                    format!(
-                        " + makeQuery(options.unsafe ? params as {struct_name}Params : \
-                         {name}ParamsSchema.parse(params))"
-                    )
+                    format!(" + makeQuery({query})")
                 } else {
                     String::new()
                 },
+                credentials = credentials(v),
                 // the method for fetching
                 method = v.method,
                 // make the request body
-                req = if v.req_body.is_none() {
-                    String::from("null")
-                } else {
-                    match &v.req_body {
-                        Kind::None => "null".to_string(),
-                        Kind::Any => "req".to_string(),
-                        Kind::Schema(_) =>
-                            format!("JSON.stringify({name}ReqSchema.parse(req))"),
-                        Kind::Websocket { .. } => unreachable!(),
-                        Kind::SSE { .. } => unreachable!(),
-                    }
-                },
-                headers_addition = if v.req_body.is_schema() {
-                    "\nheaders: jsonContentTypeHeader(init.headers as RepresentsHeader, \
-                     options.globalInit.headers as RepresentsHeader),"
-                } else {
-                    ""
-                },
-                // make the response
-                res = match &v.res_body {
-                    Kind::None => ".then(res => res.ok ? ok(res) : err(res))".to_string(),
-                    Kind::Any => ".then(res => res.ok ? res.text().then(ok) : err(res))"
-                        .to_string(),
-                    Kind::Schema(_) => format!(
-                        ".then(res => res.ok ? res.json().then(options.unsafe ? (data) \
-                         => (data as {struct_name}Res) : \
-                         {name}ResSchema.parse).then(ok) : err(res))"
-                    ),
+                req = match &v.req_body {
+                    Kind::None => "null".to_string(),
+                    Kind::Any | Kind::Text => "req".to_string(),
+                    Kind::Schema(_) => {
+                        let req = match config.runtime_validation {
+                            true => format!("{name}ReqSchema.parse(req)"),
+                            false => "req".to_string(),
+                        };
+                        match v.body_encoding {
+                            BodyEncoding::Json => format!("JSON.stringify({req})"),
+                            BodyEncoding::UrlEncoded => format!("urlEncoded({req})"),
+                            // the parsed fields lack the files, which are taken from
+                            // `req`
+                            BodyEncoding::Multipart if config.runtime_validation =>
+                                format!("formData({{ ...req, ...{req} }})"),
+                            BodyEncoding::Multipart => "formData(req)".to_string(),
+                        }
+                    },
+                    Kind::Binary | Kind::TextSchema(_) => unreachable!(),
+                    Kind::StatusUnion(_) | Kind::Ndjson(_) => unreachable!(),
                     Kind::Websocket { .. } => unreachable!(),
-                    Kind::SSE { .. } => unreachable!(),
+                    Kind::SSE { .. } | Kind::SSEEvents { .. } => unreachable!(),
                 },
-            ));
-        }
+                headers_addition = {
+                    let mut defaults = Vec::new();
+                    match v.req_body {
+                        Kind::Schema(_) => {
+                            if let Some(content_type) = v.body_encoding.content_type() {
+                                defaults.push(format!("contentType: '{content_type}'"));
+                            }
+                        },
+                        Kind::Text =>
+                            defaults.push("contentType: 'text/plain'".to_string()),
+                        _ => {},
+                    }
+                    if let Some(accept) = accept_header(v, config.accept_json) {
+                        defaults.push(format!("accept: '{accept}'"));
+                    }
+                    if v.req_headers.is_some() {
+                        defaults.push(format!("route: {route_headers}"));
+                    }
 
-        namespaces.entry(&v.tag).or_default().push(s);
-    }
+                    if defaults.is_empty() {
+                        String::new()
+                    } else {
+                        format!(
+                            "\nheaders: mergeHeaders(init.headers as RepresentsHeader, \
+                             options.globalInit.headers as RepresentsHeader, {{ {} }}),",
+                            defaults.join(", ")
+                        )
+                    }
+                },
+            );
 
-    out.push_str(
-        &namespaces
-            .iter()
-            .map(|(tag, res)| {
-                let mut s = format!("export namespace {tag} {{\n");
-                s.push_str(&res.join("\n"));
-                s.push_str("\n}");
-                s
-            })
-            .collect::<Vec<String>>()
-            .join("\n\n"),
-    );
+            // build the request, through the exposed builder if there is one
+            let request = if config.emit_request_builders {
+                format!(
+                    "{name}.request({path_call}{}{}{headers_call}init)",
+                    if v.req_body.is_some() { "req, " } else { "" },
+                    if v.req_params.is_some() {
+                        "params, "
+                    } else {
+                        ""
+                    },
+                )
+            } else {
+                new_request.clone()
+            };
+            // the timeout, overridable per call
+            let timeout_arg = match v.timeout_ms {
+                Some(timeout_ms) => format!(", timeoutMs: number = {timeout_ms}"),
+                None => String::new(),
+            };
+            // the retries, overridable per call
+            let retries_arg = match v.retries {
+                Some(retries) => format!(", retries: number = {retries}"),
+                None => String::new(),
+            };
+            // register the request so it can be aborted with `abortAll`
+            let fetch = {
+                let timed = |request: &str| match v.timeout_ms {
+                    Some(_) => format!("timed({request}, timeoutMs, authorized)"),
+                    None => format!("authorized({request})"),
+                };
+                let send = |request: &str| match v.retries {
+                    Some(_) => format!(
+                        "retried({request}, retries, (request) => {})",
+                        timed("request")
+                    ),
+                    None => timed(request),
+                };
+                match config.abort_all {
+                    true => format!(
+                        "tracked(init, (signal) => {})",
+                        send("new Request(request, { signal })")
+                    ),
+                    false => send("request"),
+                }
+            };
+
+            if let Kind::Ndjson(_) = v.res_body {
+                s.push_str(&format!(
+                    "{comment}    export async function* \
+                     {name}({path_arg}{req_json}{req_params}{headers_arg}init: \
+                     RequestInit = {{}}{timeout_arg}{retries_arg}){returns} {{
+        const request = {request};
+        const res = await {fetch};
+        if (!res.ok) throw res;
+
+        yield* readNdjson(res, {parse});
+    }}\n",
+                    returns = returns(&format!("AsyncGenerator<{struct_name}Res>")),
+                    parse = match config.runtime_validation {
+                        true => format!(
+                            "options.unsafe ? (data) => data as {struct_name}Res : \
+                             {name}ResSchema.parse"
+                        ),
+                        false => format!("(data) => data as {struct_name}Res"),
+                    },
+                ));
+            } else {
+                s.push_str(&format!(
+                    "{comment}    export function \
+                     {name}({path_arg}{req_json}{req_params}{headers_arg}init: \
+                     RequestInit = {{}}{timeout_arg}{retries_arg}){returns} {{
+        const request = {request};
+
+        return new PromiseWrapper(
+            deduped(request, () => {fetch}{res})
+        )
+    }}\n",
+                    // the function name
+                    name = name,
+                    // the request body parameter
+                    req_json = req_json,
+                    // the request query parameter
+                    req_params = req_params,
+                    // the annotated return type
+                    returns = returns(&format!(
+                        "PromiseWrapper<{}{}>",
+                        if v.res_body.is_some() {
+                            format!("{struct_name}Res")
+                        } else if v.no_content {
+                            "void".to_string()
+                        } else {
+                            "Response".to_string()
+                        },
+                        match (v.error_schemas.is_empty(), v.res_headers.is_some()) {
+                            (true, false) => String::new(),
+                            (false, false) => format!(", {struct_name}Error"),
+                            (true, true) => format!(", never, {struct_name}ResHeaders"),
+                            (false, true) =>
+                                format!(", {struct_name}Error, {struct_name}ResHeaders"),
+                        }
+                    )),
+                    // make the response, parsing typed headers and error bodies
+                    res = {
+                        let ok = match v.res_headers.is_some() {
+                            true => format!("res, {name}ResHeaders(res.headers)"),
+                            false => "res".to_string(),
+                        };
+                        let fail = match v.error_schemas.is_empty() {
+                            true => "err(res)".to_string(),
+                            false =>
+                                format!("errWith<{struct_name}Error>(res, {name}Errors)"),
+                        };
+                        match &v.res_body {
+                            Kind::None if v.no_content => format!(
+                                ".then(res => res.ok ? ok(undefined, {ok}) : {fail})"
+                            ),
+                            Kind::None =>
+                                format!(".then(res => res.ok ? ok(res, {ok}) : {fail})"),
+                            Kind::Any | Kind::Text => format!(
+                                ".then(res => res.ok ? res.text().then(okWith({ok})) : \
+                                 {fail})"
+                            ),
+                            Kind::TextSchema(_) if !config.runtime_validation => format!(
+                                ".then(res => res.ok ? res.text().then(okWith({ok})) : \
+                                 {fail})"
+                            ),
+                            Kind::TextSchema(_) => format!(
+                                ".then(res => res.ok ? res.text().then(options.unsafe ? \
+                                 (data) => (data as {struct_name}Res) : \
+                                 {name}ResSchema.parse).then(okWith({ok})) : {fail})"
+                            ),
+                            Kind::Binary => format!(
+                                ".then(res => res.ok ? res.blob().then(okWith({ok})) : \
+                                 {fail})"
+                            ),
+                            Kind::Schema(_) if !config.runtime_validation => format!(
+                                ".then(res => res.ok ? readJson(res).then((data) => \
+                                 data as {struct_name}Res).then(okWith({ok})) : {fail})"
+                            ),
+                            Kind::Schema(_) => format!(
+                                ".then(res => res.ok ? \
+                                 readJson(res).then(options.unsafe ? (data) => (data as \
+                                 {struct_name}Res) : \
+                                 {name}ResSchema.parse).then(okWith({ok})) : {fail})"
+                            ),
+                            // statuses outside of the union fail, even 2xx ones
+                            Kind::StatusUnion(_) => format!(
+                                ".then(res => res.status in {name}ResSchemas ? \
+                                 readStatus<{struct_name}Res>(res, \
+                                 {name}ResSchemas).then(okWith({ok})) : {fail})"
+                            ),
+                            Kind::Ndjson(_) => unreachable!(),
+                            Kind::Websocket { .. } => unreachable!(),
+                            Kind::SSE { .. } | Kind::SSEEvents { .. } => unreachable!(),
+                        }
+                    },
+                ));
+            }
+
+            if config.emit_request_builders {
+                s.push_str(&format!(
+                    "    /** Builds the request of `{name}` without sending it */
+    {name}.request = ({path_arg}{req_json}{req_params}{headers_arg}init: RequestInit = \
+                     {{}}){returns} =>
+        {new_request};\n",
+                    returns = returns("Request"),
+                ));
+            }
+        }
+
+        if v.existence_check {
+            if v.res_body.is_sse() || v.res_body.is_websocket() || v.req_body.is_some() {
+                return Err(format!(
+                    "Existence check of {name} can not have a request body or stream"
+                )
+                .into());
+            }
+
+            s.push_str(&format!(
+                "    /** Resolves to `true` for 2xx, to `false` for 404 and rejects \
+                 with the response otherwise */
+    export function exists{path_name}({path_arg}{req_params}{headers_arg}init: \
+                 RequestInit = {{}}){returns} {{
+        return authorized(
+            new Request(
+                urlOf('{tag}', {path}){params_suffix},
+                {{
+                    method: '{method}',
+                    credentials: '{credentials}',
+                    ...options.globalInit,
+                    ...init,{exists_headers}
+                }}
+            )
+        ).then(res => res.ok ? true : res.status === 404 ? false : Promise.reject(res));
+    }}\n",
+                returns = returns("Promise<boolean>"),
+                credentials = credentials(v),
+                exists_headers = match v.req_headers.is_some() {
+                    true => format!(
+                        "\nheaders: mergeHeaders(init.headers as RepresentsHeader, \
+                         options.globalInit.headers as RepresentsHeader, {{ route: \
+                         {route_headers} }}),"
+                    ),
+                    false => String::new(),
+                },
+                // `HeadProfile` becomes `existsProfile`, names without the method
+                // prefix are kept whole
+                path_name = struct_name
+                    .strip_prefix(&first_upper(v.method.as_str().to_lowercase()))
+                    .filter(|rest| rest.starts_with(|c: char| c.is_ascii_uppercase()))
+                    .unwrap_or(&struct_name),
+                req_params = if v.req_params.is_some() {
+                    format!("params: {struct_name}Params, ")
+                } else {
+                    String::new()
+                },
+                tag = v.tag,
+                path = path::expr(v, name),
+                params_suffix = if v.req_params.is_some() {
+                    format!(" + makeQuery({query})")
+                } else {
+                    String::new()
+                },
+                method = v.method,
+            ));
+        }
+
+        if v.long_poll {
+            if v.res_body.is_sse() || v.res_body.is_websocket() {
+                return Err(format!(
+                    "Long polling of {name} can not be used with a stream"
+                )
+                .into());
+            }
+
+            s.push_str(&format!(
+                "    export async function* \
+                 longPoll{struct_name}({path_arg}{req_json}{req_params}{headers_arg}{{ \
+                 signal }}: {{ signal?: AbortSignal }} = {{}}){returns} {{
+        while (!signal?.aborted) {{
+            try {{
+                yield await {name}({path_call}{req_arg}{params_arg}{headers_call}{{ \
+                 signal }});
+            }} catch (e) {{
+                if (signal?.aborted) return;
+                throw e;
+            }}
+        }}
+    }}\n",
+                req_json = if v.req_body.is_some() {
+                    format!("req: {struct_name}Req, ")
+                } else {
+                    String::new()
+                },
+                req_params = if v.req_params.is_some() {
+                    format!("params: {struct_name}Params, ")
+                } else {
+                    String::new()
+                },
+                returns = returns(&format!(
+                    "AsyncGenerator<{}>",
+                    if v.res_body.is_some() {
+                        format!("{struct_name}Res")
+                    } else if v.no_content {
+                        "void".to_string()
+                    } else {
+                        "Response".to_string()
+                    }
+                )),
+                req_arg = if v.req_body.is_some() { "req, " } else { "" },
+                params_arg = if v.req_params.is_some() {
+                    "params, "
+                } else {
+                    ""
+                },
+            ));
+        }
+
+        #[cfg(feature = "react")]
+        if v.res_body.is_sse() || v.res_body.is_websocket() {
+            let (hook, wrapper) = match v.res_body.is_sse() {
+                true => ("useSSE", "SSE"),
+                false => ("useWebsocket", "Websocket"),
+            };
+
+            s.push_str(&format!(
+                "    export function use{struct_name}{wrapper}({path_arg}{req_params}) \
+                 {{
+        return {hook}(() => {name}({path_call}{args}), [{deps}]);
+    }}\n",
+                req_params = if v.req_params.is_some() {
+                    format!("params: {struct_name}Params")
+                } else {
+                    String::new()
+                },
+                args = if v.req_params.is_some() { "params" } else { "" },
+                deps = [
+                    v.path_params
+                        .is_some()
+                        .then_some("JSON.stringify(pathParams)"),
+                    v.req_params.is_some().then_some("JSON.stringify(params)"),
+                ]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<&str>>()
+                .join(", "),
+            ));
+        }
+
+        if let Some(page) = &v.pagination {
+            if !v.res_body.is_schema() || !v.req_params.is_some() {
+                return Err(format!(
+                    "Pagination of {name} requires request params and a response schema"
+                )
+                .into());
+            }
+
+            s.push_str(&format!(
+                "    export async function* \
+                 iterate{struct_name}({path_arg}{req_json}params: {struct_name}Params, \
+                 {headers_arg}init: RequestInit = {{}}){returns} {{
+        let cursor = params['{cursor}'];
+
+        while (true) {{
+            const page: {struct_name}Res = await {name}({path_call}{req_arg}{{ \
+                 ...params, '{cursor}': cursor }}, {headers_call}init);
+
+            yield* page['{items}'];
+
+            const next = page['{next}'];
+            if (next === undefined || next === null) return;
+            cursor = next;
+        }}
+    }}\n",
+                req_json = if v.req_body.is_some() {
+                    format!("req: {struct_name}Req, ")
+                } else {
+                    String::new()
+                },
+                returns = returns(&format!(
+                    "AsyncGenerator<{struct_name}Res['{}'][number]>",
+                    page.items_field
+                )),
+                req_arg = if v.req_body.is_some() { "req, " } else { "" },
+                cursor = page.cursor_param,
+                next = page.next_cursor_field,
+                items = page.items_field,
+            ));
+        }
+
+        Ok(Route {
+            code: s,
+            service: None,
+        })
+    };
+
+    // the routes are independent, generated in parallel with the `parallel`
+    // feature and collected in order
+    #[cfg(not(feature = "parallel"))]
+    let routes = requests.iter().zip(&names).map(|(v, name)| route(v, name));
+    #[cfg(feature = "parallel")]
+    let routes = {
+        use rayon::prelude::*;
+
+        requests
+            .par_iter()
+            .zip(&names)
+            .map(|(v, name)| route(v, name).map_err(RouteError::from))
+            .collect::<Vec<Result<Route, RouteError>>>()
+            .into_iter()
+            .map(|route| route.map_err(Box::<dyn StdError>::from))
+    };
+
+    let mut generated = Vec::new();
+    for (v, route) in requests.iter().zip(routes) {
+        generated.push((&v.tag, route?));
+    }
+
+    if config.share_schemas {
+        out.push_str(&shared::share(
+            &mut generated
+                .iter_mut()
+                .map(|(_, route)| &mut route.code)
+                .collect::<Vec<&mut String>>(),
+        ));
+    }
+
+    for (tag, Route { code, service }) in generated {
+        if let Some(service) = service {
+            services.entry(tag).or_default().push(service);
+        }
+        namespaces.entry(tag).or_default().push(code);
+    }
+
+    if config.layout == Layout::Namespace {
+        let mut nested = Namespaces::default();
+        if config.emit_tag_docs {
+            let mut listings = BTreeMap::<&str, String>::new();
+            for (v, name) in requests.iter().zip(&names) {
+                listings.entry(&v.tag).or_default().push_str(&format!(
+                    " * - `{} {}` {{@link {name}}}\n",
+                    v.method,
+                    v.path.replace("*/", "*\\/")
+                ));
+            }
+            for (tag, listing) in listings {
+                nested.document(
+                    tag,
+                    format!("/**\n * Routes of `{tag}`:\n *\n{listing} */\n"),
+                )?;
+            }
+        }
+        for (tag, res) in &namespaces {
+            let mut s = res.join("\n");
+            if let Some(methods) = services.get(tag) {
+                s.push('\n');
+                s.push_str(&angular::service(
+                    tag.rsplit('.').next().unwrap_or(tag),
+                    methods,
+                ));
+            }
+            nested.insert(tag, s)?;
+        }
+        out.push_str(&nested.render());
+    } else {
+        out.push_str(
+            &namespaces
+                .into_values()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
+
+        if config.layout == Layout::Flat {
+            return Ok(finish(format_js(&out, config)?, config));
+        }
+    }
 
     out.push('}');
 
-    format_js(&out)
+    // the newest routes, exported next to `client`
+    if config.emit_latest {
+        if config.target == Target::Angular {
+            return Err(
+                "The latest namespace is not supported by the angular target".into(),
+            );
+        }
+
+        let mut latest = Namespaces::default();
+        let mut prefixed = Vec::new();
+        for (v, name) in requests
+            .iter()
+            .zip(&names)
+            .filter(|(v, _)| !v.deprecated.is())
+        {
+            match config.layout {
+                Layout::Prefixed =>
+                    prefixed.push(format!("export const {name} = client.{name};")),
+                _ => latest.insert(
+                    &v.tag,
+                    format!(
+                        "export const {name} = client.{}.{name};",
+                        namespace::path(&v.tag)
+                    ),
+                )?,
+            }
+        }
+
+        out.push_str("\n\nexport namespace latest {\n");
+        out.push_str(&latest.render());
+        out.push_str(&prefixed.join("\n"));
+        out.push_str("\n}");
+    }
+
+    Ok(finish(format_js(&out, config)?, config))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        borrow::Cow,
+        fs,
+        path::PathBuf,
+        process::Command,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use schemars::{schema::Schema, JsonSchema};
+    use serde_json::Value;
+
+    use super::{generate, generate_multi, generate_ref, generate_with, warnings};
+    use crate::{
+        DefaultNames,
+        Eol,
+        GenError,
+        GenerateConfig,
+        Kind,
+        Layout,
+        Method,
+        NameStrategy,
+        PageConfig,
+        QueryStyle,
+        ReconnectPolicy,
+        RequestInfo,
+        Requests,
+        Slot,
+        Stability,
+        Target,
+    };
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Node {
+        value: u32,
+        next: Option<Box<Node>>,
+    }
+
+    #[test]
+    fn zod_import() {
+        let info = || {
+            RequestInfo::new("/api/users/list", Method::Get, "users")
+                .with_res_schema::<ListRes>()
+        };
+
+        assert!(gen(info()).contains("import { z } from 'zod';"));
+
+        let out = gen_with(vec![info()], GenerateConfig {
+            zod_module: "zod/v4".to_string(),
+            ..Default::default()
+        });
+        assert!(out.contains("import { z } from 'zod/v4';"));
+
+        let out = gen_with(vec![info()], GenerateConfig {
+            zod_module: "@acme/schema".to_string(),
+            zod_symbol: "zod".to_string(),
+            ..Default::default()
+        });
+        assert!(out.contains("import { zod as z } from '@acme/schema';"));
+    }
+
+    #[test]
+    fn multiple_files() {
+        let files = generate_multi(Requests {
+            requests: vec![
+                RequestInfo::new("/api/users/list", Method::Get, "users")
+                    .with_res_schema::<ListRes>(),
+                RequestInfo::new("/api/list", Method::Get, "admin.users")
+                    .with_res_schema::<ListRes>(),
+            ],
+            tag_base_urls: [("users".to_string(), "/users".to_string())].into(),
+        })
+        .unwrap();
+
+        assert_eq!(files.keys().collect::<Vec<_>>(), [
+            "admin.users.ts",
+            "base.ts",
+            "index.ts",
+            "users.ts"
+        ]);
+
+        let base = &files["base.ts"];
+        assert!(base.contains("class PromiseWrapper"));
+        assert!(base.contains("Object.assign(options.baseUrls"));
+        assert!(base.contains("urlOf,"));
+        assert!(!base.contains("export { options"));
+
+        let users = &files["users.ts"];
+        assert!(users.contains("from './base';"));
+        assert!(users.contains("export function usersGetList("));
+        assert!(!users.contains("class PromiseWrapper"));
+        assert!(files["admin.users.ts"].contains("export function adminUsersGetList("));
+
+        assert!(files["index.ts"].contains("export * from './admin.users';"));
+        assert!(files["index.ts"].contains("export * from './users';"));
+
+        for tag in ["base", "index"] {
+            let reserved = generate_multi(Requests {
+                requests: vec![RequestInfo::new("/api/list", Method::Get, tag)],
+                ..Default::default()
+            });
+            assert!(reserved.is_err());
+        }
+    }
+
+    #[test]
+    fn flat_layout() {
+        let requests = || {
+            vec![
+                RequestInfo::new("/api/users/list", Method::Get, "users")
+                    .with_res_schema::<ListRes>(),
+                RequestInfo::new("/api/list", Method::Get, "admin.users")
+                    .with_res_schema::<ListRes>(),
+            ]
+        };
+        let flat = || GenerateConfig {
+            layout: Layout::Flat,
+            ..Default::default()
+        };
+
+        let out = gen_with(requests(), flat());
+        assert!(!out.contains("export namespace"));
+        assert!(out.contains("export function usersGetList("));
+        assert!(out.contains("export type UsersGetListRes"));
+        assert!(out.contains("export function adminUsersGetList("));
+
+        let taken = generate_with(
+            Requests {
+                requests: vec![
+                    RequestInfo::new("/api/adminUsers/list", Method::Get, "adminUsers"),
+                    RequestInfo::new("/api/list", Method::Get, "admin.users"),
+                ],
+                ..Default::default()
+            },
+            &flat(),
+        );
+        assert!(taken.is_err());
+
+        let latest = generate_with(
+            Requests {
+                requests: requests(),
+                ..Default::default()
+            },
+            &GenerateConfig {
+                emit_latest: true,
+                ..flat()
+            },
+        );
+        assert!(latest.is_err());
+    }
+
+    #[test]
+    fn prefixed_layout() {
+        let requests = || {
+            vec![
+                RequestInfo::new("/api/users/list", Method::Get, "users")
+                    .with_res_schema::<ListRes>(),
+                RequestInfo::new("/api/posts/list", Method::Get, "posts")
+                    .with_res_schema::<ListRes>(),
+                RequestInfo::new("/api/list", Method::Get, "default"),
+            ]
+        };
+        let prefixed = || GenerateConfig {
+            layout: Layout::Prefixed,
+            ..Default::default()
+        };
+
+        let out = gen_with(requests(), prefixed());
+        assert!(out.contains("export namespace client {"));
+        assert!(!out.contains("export namespace users"));
+        assert!(!out.contains("nDefault"));
+        assert!(out.contains("export function usersGetList("));
+        assert!(out.contains("export function postsGetList("));
+        assert!(out.contains("export type PostsGetListRes"));
+        assert!(out.contains("export function defaultGetList("));
+
+        let out = gen_with(requests(), GenerateConfig {
+            emit_latest: true,
+            ..prefixed()
+        });
+        assert!(out.contains("export const usersGetList = client.usersGetList;"));
+    }
+
+    #[test]
+    fn share_schemas() {
+        let requests = || {
+            vec![
+                RequestInfo::new("/api/users/list", Method::Get, "users")
+                    .with_res_schema::<ListRes>(),
+                RequestInfo::new("/api/orders/list", Method::Get, "orders")
+                    .with_res_schema::<ListRes>(),
+                RequestInfo::new("/api/orders/search", Method::Get, "orders")
+                    .with_req_params::<Search>(),
+            ]
+        };
+
+        let out = gen_with(requests(), GenerateConfig {
+            share_schemas: true,
+            ..Default::default()
+        });
+        let compact = out.split_whitespace().collect::<String>();
+        assert_eq!(compact.matches("constsharedSchema0=z.object(").count(), 1);
+        assert_eq!(
+            out.matches("const getListResSchema = sharedSchema0;")
+                .count(),
+            2
+        );
+        assert!(!out.contains("sharedSchema1"));
+        assert!(out.contains("const getSearchParamsSchema = z."));
+
+        let out = gen_with(requests(), GenerateConfig::default());
+        assert!(!out.contains("sharedSchema"));
+    }
+
+    #[test]
+    fn routes_keep_their_order() {
+        let out = gen_with(
+            vec![
+                RequestInfo::new("/api/alpha/b", Method::Get, "alpha"),
+                RequestInfo::new("/api/alpha/a", Method::Get, "alpha"),
+            ],
+            GenerateConfig::default(),
+        );
+        let position = |s: &str| out.find(s).unwrap();
+        assert!(position("export function getB(") < position("export function getA("));
+
+        let err = generate(Requests {
+            requests: vec![
+                RequestInfo::new("/api/users/first", Method::Head, "users")
+                    .with_res_schema::<ListRes>(),
+                RequestInfo::new("/api/users/second", Method::Head, "users")
+                    .with_res_schema::<ListRes>(),
+            ],
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert!(err.to_string().contains("headFirst"));
+    }
+
+    #[test]
+    fn schema_settings() {
+        let with_inline = |inline: bool| {
+            move || {
+                let mut settings = crate::settings(true);
+                settings.inline_subschemas = inline;
+                settings
+            }
+        };
+
+        let out = gen(RequestInfo::new("/api/users", Method::Get, "users")
+            .with_schema_settings(with_inline(true))
+            .with_res_schema::<ListRes>());
+        assert!(out.contains("const getUsersResSchema = "));
+
+        let err = generate(Requests {
+            requests: vec![RequestInfo::new("/api/nodes", Method::Get, "nodes")
+                .with_schema_settings(with_inline(false))
+                .with_res_schema::<Node>()],
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("The res schema of getNodes references"));
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct ListParams {
+        cursor: Option<String>,
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct ListRes {
+        items: Vec<String>,
+        next: Option<String>,
+    }
+
+    /// Runs `script` after the generated client, the `client` namespace being
+    /// in scope, with `tsx` and `zod` from the `node_modules` directory in
+    /// `CLIENT_GEN_NODE_MODULES`. Returns what the script logs.
+    fn run(requests: Vec<RequestInfo>, config: GenerateConfig, script: &str) -> String {
+        static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+        let modules = PathBuf::from(
+            std::env::var("CLIENT_GEN_NODE_MODULES")
+                .expect("CLIENT_GEN_NODE_MODULES is not set"),
+        );
+        let dir = std::env::temp_dir().join(format!(
+            "client-gen-{}-{}",
+            std::process::id(),
+            RUNS.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        std::os::unix::fs::symlink(&modules, dir.join("node_modules")).unwrap();
+
+        let client = generate_with(
+            Requests {
+                requests,
+                ..Default::default()
+            },
+            &config,
+        )
+        .unwrap();
+        // `.mts` for top-level await
+        fs::write(dir.join("main.mts"), format!("{client}\n\n{script}")).unwrap();
+
+        let out = Command::new(modules.join(".bin/tsx"))
+            .arg("main.mts")
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(
+            out.status.success(),
+            "{}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        String::from_utf8(out.stdout).unwrap()
+    }
+
+    /// Generates the client, with quotes normalized to single quotes so the
+    /// assertions do not depend on the quote style of the formatter.
+    fn gen_with(requests: Vec<RequestInfo>, config: GenerateConfig) -> String {
+        generate_with(
+            Requests {
+                requests,
+                ..Default::default()
+            },
+            &config,
+        )
+        .unwrap()
+        .replace('"', "'")
+    }
+
+    fn gen(info: RequestInfo) -> String {
+        generate(Requests {
+            requests: vec![info],
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn error_ranges() {
+        let out = gen(RequestInfo::new("/api/users/list", Method::Get, "users")
+            .with_error(500..=599, "Server error")
+            .with_error(422..=429, "Rejected")
+            .with_error("not found", "No such user")
+            .with_error(409, "Taken"));
+        let compact = out.split_whitespace().collect::<String>();
+
+        assert!(compact
+            .contains("*404:Nosuchuser**409:Taken**422-429:Rejected**5xx:Servererror"));
+    }
+
+    #[test]
+    fn meta_methods() {
+        let requests = || {
+            vec![
+                RequestInfo::new("/api/users/list", Method::Get, "users"),
+                RequestInfo::new("/api/users/list", Method::Options, "users"),
+                RequestInfo::new("/api/users/list", Method::Trace, "users"),
+            ]
+        };
+
+        let out = gen_with(requests(), GenerateConfig::default());
+        assert!(out.contains("export function getList("));
+        assert!(!out.contains("optionsList"));
+        assert!(!out.contains("traceList"));
+
+        let out = gen_with(requests(), GenerateConfig {
+            exclude_meta_methods: false,
+            ..Default::default()
+        });
+        assert!(out.contains("export function optionsList("));
+        assert!(out.contains("export function traceList("));
+    }
+
+    #[test]
+    fn empty_requests() {
+        let out = generate(Requests::default()).unwrap();
+        assert!(out.contains("export namespace client {"));
+        assert!(out.trim_end().ends_with('}'));
+        assert_eq!(out, generate(Requests::default()).unwrap());
+
+        let excluded = generate(Requests {
+            requests: vec![RequestInfo::new("/api/users/list", Method::Get, "users")
+                .with_excluded_from_client()],
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(out, excluded);
+    }
+
+    #[test]
+    fn first_upper() {
+        assert_eq!(super::first_upper(""), "");
+        assert_eq!(super::first_upper("ß"), "SS");
+        assert_eq!(super::first_upper("ßtraße"), "SStraße");
+        assert_eq!(super::first_upper("users"), "Users");
+        assert_eq!(super::first_upper("Users"), "Users");
+    }
+
+    #[test]
+    fn root_path() {
+        let out =
+            gen(RequestInfo::new("/", Method::Get, "root").with_res_schema::<ListRes>());
+        assert!(out.contains("export function get("));
+        assert!(out.contains("export type GetRes"));
+
+        let out = gen(RequestInfo::new("/api/users/", Method::Get, "users"));
+        assert!(out.contains("export function get("));
+    }
+
+    #[test]
+    fn pagination_iterator() {
+        let out = gen(RequestInfo::new("/api/users/list", Method::Get, "users")
+            .with_req_params::<ListParams>()
+            .with_res_schema::<ListRes>()
+            .with_pagination(PageConfig::new("cursor", "next", "items")));
+
+        assert!(out.contains("async function* iterateGetList("));
+        assert!(out.contains("await getList("));
+        assert!(out.contains("yield* page['items']"));
+    }
+
+    #[test]
+    #[ignore = "needs CLIENT_GEN_NODE_MODULES with tsx and zod"]
+    fn pagination_iterator_run() {
+        let out = run(
+            vec![RequestInfo::new("/api/users/list", Method::Get, "users")
+                .with_req_params::<ListParams>()
+                .with_res_schema::<ListRes>()
+                .with_pagination(PageConfig::new("cursor", "next", "items"))],
+            GenerateConfig::default(),
+            r#"
+const cursors: (string | null)[] = [];
+client.options.baseUrl = 'http://localhost';
+client.options.fetch = async (req: Request) => {
+    const cursor = new URL(req.url).searchParams.get('cursor');
+    cursors.push(cursor);
+    const page = cursor === null ? { items: ['a', 'b'], next: '2' } : { items: ['c'], next: null };
+    return new Response(JSON.stringify(page));
+};
+
+const items: string[] = [];
+for await (const item of client.users.iterateGetList({})) items.push(item);
+console.log(JSON.stringify({ cursors, items }));
+"#,
+        );
+
+        assert_eq!(
+            serde_json::from_str::<Value>(&out).unwrap(),
+            serde_json::json!({ "cursors": [null, "2"], "items": ["a", "b", "c"] })
+        );
+    }
+
+    #[test]
+    fn pagination_requires_response_schema() {
+        let res = generate(Requests {
+            requests: vec![RequestInfo::new("/api/users/list", Method::Get, "users")
+                .with_req_params::<ListParams>()
+                .with_pagination(PageConfig::new("cursor", "next", "items"))],
+            ..Default::default()
+        });
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn crlf_line_endings() {
+        let config = GenerateConfig {
+            eol: Eol::Crlf,
+            final_newline: false,
+            ..Default::default()
+        };
+        let out = generate_with(
+            Requests {
+                requests: vec![RequestInfo::new("/api/users", Method::Get, "users")],
+                ..Default::default()
+            },
+            &config,
+        )
+        .unwrap();
+
+        assert!(out.contains("\r\n"));
+        assert!(!out.replace("\r\n", "").contains('\n'));
+        assert!(!out.ends_with("\r\n"));
+    }
+
+    #[test]
+    fn verbose_deprecation_signature() {
+        let new = RequestInfo::new("/api/users/list/v2", Method::Get, "users")
+            .with_req_params::<ListParams>()
+            .with_res_schema::<ListRes>();
+        let old = RequestInfo::new("/api/users/list", Method::Get, "users")
+            .with_deprecation_note(&new);
+
+        let out = generate_with(
+            Requests {
+                requests: vec![old, new],
+                ..Default::default()
+            },
+            &GenerateConfig {
+                verbose_deprecation: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert!(out.contains("getListV2(params: GetListV2Params, init?: RequestInit)"));
+    }
+
+    #[test]
+    fn missing_deprecation_target() {
+        let new = RequestInfo::new("/api/users/list/v2", Method::Get, "users");
+        let old = || {
+            RequestInfo::new("/api/users/list", Method::Get, "users")
+                .with_deprecation_note(&new)
+        };
+
+        let out = gen(old());
+        assert!(out.contains("@deprecated Please use `GET /api/users/list/v2` instead"));
+        assert!(!out.contains("@link"));
+
+        let requests = Requests {
+            requests: vec![old()],
+            ..Default::default()
+        };
+        assert_eq!(warnings(&requests, &GenerateConfig::default()), [
+            "The replacement GET /api/users/list/v2 of tag users deprecating GET \
+             /api/users/list is not generated"
+        ]);
+        let requests = requests.with(|| new.clone());
+        assert!(warnings(&requests, &GenerateConfig::default()).is_empty());
+
+        let strict = generate_with(
+            Requests {
+                requests: vec![old()],
+                ..Default::default()
+            },
+            &GenerateConfig {
+                strict: true,
+                ..Default::default()
+            },
+        );
+        assert!(strict.is_err());
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Bounded {
+        #[schemars(range(min = 1, max = 10))]
+        count: u8,
+    }
+
+    #[test]
+    fn strict_rejects_dropped_bounds() {
+        let requests = Requests {
+            requests: vec![RequestInfo::new("/api/items", Method::Post, "items")
+                .with_req_body::<Bounded>()],
+            ..Default::default()
+        };
+
+        assert!(generate(requests.clone()).is_ok());
+
+        let err = generate_with(requests, &GenerateConfig {
+            strict: true,
+            ..Default::default()
+        })
+        .unwrap_err();
+
+        assert!(err.to_string().contains("req.count: minimum"));
+    }
+
+    #[test]
+    fn tag_base_urls() {
+        let out = generate(
+            Requests::default()
+                .with(|| RequestInfo::new("/api/auth/login", Method::Post, "auth"))
+                .with(|| RequestInfo::new("/api/orders/list", Method::Get, "orders"))
+                .with_tag_base_url("auth", "https://auth.example.com")
+                .with_tag_base_url("orders", "https://orders.example.com"),
+        )
+        .unwrap();
+
+        assert!(out.contains("https://auth.example.com"));
+        assert!(out.contains("https://orders.example.com"));
+        assert!(out.contains("urlOf('auth', '/api/auth/login')"));
+        assert!(out.contains("urlOf('orders', '/api/orders/list')"));
+    }
+
+    #[test]
+    fn angular_service() {
+        let out = generate_with(
+            Requests {
+                requests: vec![RequestInfo::new("/api/users/list", Method::Get, "users")
+                    .with_req_params::<ListParams>()
+                    .with_res_schema::<ListRes>()],
+                ..Default::default()
+            },
+            &GenerateConfig {
+                target: Target::Angular,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert!(out.contains("export class UsersService"));
+        assert!(out.contains("constructor(private http: HttpClient)"));
+        assert!(out.contains("Observable<GetListRes>"));
+        assert!(!out.contains("PromiseWrapper"));
+    }
+
+    #[test]
+    #[cfg(feature = "react")]
+    fn react_sse_hook() {
+        let out = gen(RequestInfo::new("/api/events", Method::Get, "events")
+            .with_req_params::<ListParams>()
+            .with_sse::<ListRes>());
+
+        assert!(out.contains("export function useGetEventsSSE(params: GetEventsParams)"));
+        assert!(out.contains("useSSE(() => getEvents(params), [JSON.stringify(params)])"));
+        assert!(out.contains("sse.subscribe(setState)"));
+        assert!(out.contains("sse.close();"));
+        assert!(!out.contains("sse.sse."));
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Contact {
+        #[schemars(email)]
+        email: String,
+        #[schemars(url)]
+        website: String,
+        #[schemars(regex(pattern = "^[a-z]+$"))]
+        nickname: String,
+        ids: Vec<uuid::Uuid>,
+    }
+
+    #[test]
+    fn string_formats() {
+        let request = || {
+            RequestInfo::new("/api/contacts", Method::Post, "contacts")
+                .with_req_body::<Contact>()
+        };
+
+        assert!(!gen(request()).contains("z.string().email()"));
+
+        let out = gen_with(vec![request()], GenerateConfig {
+            enforce_formats: true,
+            ..Default::default()
+        });
+        let compact = out.split_whitespace().collect::<String>();
+
+        assert!(compact.contains("email:z.string().email()"));
+        assert!(compact.contains("website:z.string().url()"));
+        assert!(compact.contains("ids:z.array(z.string().uuid())"));
+        assert!(compact.contains("nickname:z.string().regex("));
+        assert!(!out.contains("__client_gen_format_"));
+    }
+
+    #[test]
+    #[ignore = "needs CLIENT_GEN_NODE_MODULES with tsx and zod"]
+    fn string_formats_run() {
+        let out = run(
+            vec![RequestInfo::new("/api/contacts", Method::Post, "contacts")
+                .with_req_body::<Contact>()],
+            GenerateConfig {
+                enforce_formats: true,
+                ..Default::default()
+            },
+            r#"
+client.options.baseUrl = 'http://localhost';
+client.options.fetch = async () => new Response(null, { status: 204 });
+
+const valid = {
+    email: 'a@example.com',
+    website: 'https://example.com',
+    nickname: 'abc',
+    ids: ['6f1c2a4e-8d3b-4c5a-9e7f-0a1b2c3d4e5f'],
+};
+const accepts = (contact: Record<string, unknown>) => {
+    try {
+        client.contacts.postContacts(contact as any);
+        return true;
+    } catch {
+        return false;
+    }
+};
+console.log(JSON.stringify([
+    accepts(valid),
+    accepts({ ...valid, email: 'a' }),
+    accepts({ ...valid, website: 'example' }),
+    accepts({ ...valid, ids: ['1'] }),
+]));
+"#,
+        );
+
+        assert_eq!(
+            serde_json::from_str::<Value>(&out).unwrap(),
+            serde_json::json!([true, false, false, false])
+        );
+    }
+
+    #[test]
+    fn abort_all() {
+        let request = || RequestInfo::new("/api/users", Method::Get, "users");
+
+        assert!(!gen(request()).contains("abortAll"));
+
+        let out = gen_with(vec![request()], GenerateConfig {
+            abort_all: true,
+            ..Default::default()
+        });
+
+        assert!(out.contains("export const abortAll"));
+        assert!(out.contains("tracked(init, (signal) =>"));
+        assert!(out.contains("new Request(request, { signal })"));
+    }
+
+    #[test]
+    #[ignore = "needs CLIENT_GEN_NODE_MODULES with tsx and zod"]
+    fn abort_all_aborted_signal_run() {
+        let out = run(
+            vec![RequestInfo::new("/api/users", Method::Get, "users")],
+            GenerateConfig {
+                abort_all: true,
+                ..Default::default()
+            },
+            r#"
+let sent = 0;
+client.options.baseUrl = 'http://localhost';
+client.options.fetch = async (req: Request) => {
+    if (req.signal.aborted) throw req.signal.reason;
+    sent++;
+    return new Response(null, { status: 204 });
+};
+
+const result = await client.users.getUsers({ signal: AbortSignal.abort('gone') })
+    .silent()
+    .raw()
+    .then(() => 'resolved', () => 'rejected');
+console.log(JSON.stringify({ sent, result }));
+"#,
+        );
+
+        assert_eq!(
+            serde_json::from_str::<Value>(&out).unwrap(),
+            serde_json::json!({ "sent": 0, "result": "rejected" })
+        );
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    #[serde(untagged)]
+    enum UntaggedShape {
+        Circle { radius: f64 },
+        Rect { width: f64, height: f64 },
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    #[serde(tag = "type")]
+    enum TaggedShape {
+        Circle { radius: f64 },
+        Rect { width: f64, height: f64 },
+    }
+
+    fn assert_union_body(out: &str) {
+        assert!(out.contains("z.union(") || out.contains("z.discriminatedUnion("));
+        assert!(out
+            .contains("export type PostShapesReq = z.input<typeof postShapesReqSchema>"));
+        assert!(out.contains("JSON.stringify(postShapesReqSchema.parse(req))"));
+    }
+
+    #[test]
+    fn untagged_union_body() {
+        assert_union_body(&gen(RequestInfo::new(
+            "/api/shapes",
+            Method::Post,
+            "shapes",
+        )
+        .with_req_body::<UntaggedShape>()));
+    }
+
+    #[test]
+    fn tagged_union_body() {
+        assert_union_body(&gen(RequestInfo::new(
+            "/api/shapes",
+            Method::Post,
+            "shapes",
+        )
+        .with_req_body::<TaggedShape>()));
+    }
+
+    #[test]
+    fn accept_header_from_content_types() {
+        let out = gen(RequestInfo::new("/api/report", Method::Get, "report")
+            .with_res_schema::<ListRes>()
+            .with_res_content_type("text/plain"));
+
+        assert!(out.contains("accept: 'application/json, text/plain;q=0.9'"));
+
+        let out = gen(RequestInfo::new("/api/report", Method::Get, "report")
+            .with_res_schema::<ListRes>()
+            .with_accept("application/vnd.report+json"));
+
+        assert!(out.contains("accept: 'application/vnd.report+json'"));
+    }
+
+    #[test]
+    fn tag_docs() {
+        let requests = || {
+            vec![
+                RequestInfo::new("/api/users/list", Method::Get, "users")
+                    .with_res_schema::<ListRes>(),
+                RequestInfo::new("/api/users/{id}", Method::Delete, "users")
+                    .with_path_params::<PostPath>(),
+            ]
+        };
+
+        let out = gen_with(requests(), GenerateConfig::default());
+        let compact = out.split_whitespace().collect::<String>();
+        assert!(compact.contains(
+            "/***Routesof`users`:**-`GET/api/users/list`{@linkgetList}*-`DELETE/api/\
+             users/{id}`{@linkdeleteById}*/exportnamespaceusers{"
+        ));
+
+        let out = gen_with(requests(), GenerateConfig {
+            emit_tag_docs: false,
+            ..Default::default()
+        });
+        assert!(!out.contains("Routes of"));
+    }
+
+    #[test]
+    fn accept_json() {
+        let info = || {
+            RequestInfo::new("/api/users/list", Method::Get, "users")
+                .with_res_schema::<ListRes>()
+        };
+
+        let out = gen(info());
+        assert!(out.contains("accept: 'application/json'"));
+        assert!(out.contains("mergeHeaders(init.headers as RepresentsHeader"));
+
+        let out = gen_with(vec![info()], GenerateConfig {
+            accept_json: false,
+            ..Default::default()
+        });
+        assert!(!out.contains("accept: 'application/json'"));
+
+        let out = gen(RequestInfo::new(
+            "/api/users/delete",
+            Method::Delete,
+            "users",
+        ));
+        assert!(!out.contains("accept:"));
+    }
+
+    #[test]
+    fn raw_response() {
+        let out = gen(RequestInfo::new("/api/users", Method::Get, "users")
+            .with_res_schema::<ListRes>());
+
+        assert!(out.contains("raw(): Promise<{ response: Response; data: T }>"));
+        assert!(out.contains("getUsersResSchema.parse).then(okWith(res))"));
+    }
+
+    #[test]
+    #[ignore = "needs CLIENT_GEN_NODE_MODULES with tsx and zod"]
+    fn raw_response_run() {
+        let out = run(
+            vec![RequestInfo::new("/api/users", Method::Get, "users")
+                .with_res_schema::<ListRes>()],
+            GenerateConfig::default(),
+            r#"
+let status = 200;
+client.options.baseUrl = 'http://localhost';
+client.options.fetch = async () => status === 200
+    ? new Response(JSON.stringify({ items: ['a'], next: null }), { headers: { 'x-total': '1' } })
+    : new Response('failed', { status });
+
+const results = [];
+for (status of [200, 404, 500]) {
+    results.push(await client.users.getUsers().silent().raw().then(
+        ({ response, data }) => ({ status: response.status, total: response.headers.get('x-total'), data }),
+        (response: Response) => ({ status: response.status, rejected: true }),
+    ));
+}
+const data = await client.users.getUsers();
+console.log(JSON.stringify({ results, data }));
+"#,
+        );
+
+        assert_eq!(
+            serde_json::from_str::<Value>(&out).unwrap(),
+            serde_json::json!({
+                "results": [
+                    { "status": 200, "total": "1", "data": { "items": ["a"], "next": null } },
+                    { "status": 404, "rejected": true },
+                    { "status": 500, "rejected": true },
+                ],
+                "data": { "items": ["a"], "next": null },
+            })
+        );
+    }
+
+    #[test]
+    fn without_base() {
+        let out = gen_with(
+            vec![RequestInfo::new("/api/users", Method::Get, "users")
+                .with_res_schema::<ListRes>()],
+            GenerateConfig {
+                include_base: false,
+                base_import: Some("./base".to_string()),
+                ..Default::default()
+            },
+        );
+
+        assert!(!out.contains("class PromiseWrapper"));
+        assert!(!out.contains("const makeQuery"));
+        assert!(out.contains("from './base'"));
+        assert!(out.contains("export function getUsers("));
+    }
+
+    #[test]
+    fn existence_check() {
+        let out = gen(
+            RequestInfo::new("/api/users/profile", Method::Head, "users")
+                .with_req_params::<ListParams>()
+                .as_existence_check(),
+        );
+
+        assert!(out.contains("export function existsProfile(params: HeadProfileParams, "));
+        assert!(out.contains(
+            "res.ok ? true : res.status === 404 ? false : Promise.reject(res)"
+        ));
+        assert!(out.contains("export function headProfile("));
+    }
+
+    #[test]
+    #[ignore = "needs CLIENT_GEN_NODE_MODULES with tsx and zod"]
+    fn existence_check_run() {
+        let out = run(
+            vec![
+                RequestInfo::new("/api/users/profile", Method::Head, "users")
+                    .as_existence_check(),
+            ],
+            GenerateConfig::default(),
+            r#"
+const statuses = [200, 404, 500];
+client.options.baseUrl = 'http://localhost';
+client.options.fetch = async (req: Request) =>
+    new Response(null, { status: statuses.shift(), headers: { 'x-method': req.method } });
+
+const results = [];
+for (let i = 0; i < 3; i++) {
+    results.push(await client.users.existsProfile().then(
+        exists => exists,
+        (res: Response) => ({ status: res.status, method: res.headers.get('x-method') }),
+    ));
+}
+console.log(JSON.stringify(results));
+"#,
+        );
+
+        assert_eq!(
+            serde_json::from_str::<Value>(&out).unwrap(),
+            serde_json::json!([true, false, { "status": 500, "method": "HEAD" }])
+        );
+    }
+
+    #[test]
+    fn head_reads_no_body() {
+        let info = || {
+            RequestInfo::new("/api/users/profile", Method::Head, "users")
+                .with_res_headers::<RateLimit>()
+        };
+
+        let out = gen_with(vec![info()], GenerateConfig {
+            include_base: false,
+            ..Default::default()
+        });
+        assert!(!out.contains("readJson"));
+        assert!(!out.contains(".json()"));
+        assert!(!out.contains(".text()"));
+        assert!(out.contains("PromiseWrapper<Response, never, HeadProfileResHeaders>"));
+        let compact = out.split_whitespace().collect::<String>();
+        assert!(compact.contains("ok(res,res,headProfileResHeaders(res.headers))"));
+
+        let err = generate_with(
+            Requests {
+                requests: vec![info().with_res_schema::<ListRes>()],
+                ..Default::default()
+            },
+            &GenerateConfig::default(),
+        );
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn reserved_tags() {
+        let out = gen_with(
+            vec![
+                RequestInfo::new("/api/default/list", Method::Get, "default")
+                    .with_res_schema::<ListRes>(),
+                RequestInfo::new(
+                    "/api/admin/function/list",
+                    Method::Get,
+                    "admin.function",
+                )
+                .with_res_schema::<ListRes>(),
+            ],
+            GenerateConfig {
+                emit_latest: true,
+                ..Default::default()
+            },
+        );
+
+        assert!(out.contains("export namespace nDefault {"));
+        assert!(out.contains("export namespace nFunction {"));
+        assert!(!out.contains("namespace default"));
+        assert!(!out.contains("namespace function"));
+        assert!(out.contains("client.nDefault.getList;"));
+        assert!(out.contains("client.admin.nFunction."));
+        assert!(out.contains("urlOf('default', "));
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Search {
+        query: String,
+        #[serde(default)]
+        page_size: u32,
+    }
+
+    #[test]
+    fn body_defaults() {
+        let request = || {
+            RequestInfo::new("/api/search", Method::Post, "search")
+                .with_req_body::<Search>()
+        };
+
+        assert!(!gen(request()).contains(".default("));
+
+        let out = gen_with(vec![request()], GenerateConfig {
+            apply_body_defaults: true,
+            ..Default::default()
+        });
+
+        assert!(out.contains(".default("));
+        assert!(out
+            .contains("export type PostSearchReq = z.input<typeof postSearchReqSchema>"));
+    }
+
+    #[test]
+    fn long_poll() {
+        let out = gen(RequestInfo::new("/api/events", Method::Get, "events")
+            .with_req_params::<ListParams>()
+            .with_res_schema::<ListRes>()
+            .long_poll());
+
+        assert!(out.contains(
+            "export async function* longPollGetEvents(params: GetEventsParams, "
+        ));
+        assert!(out.contains("AsyncGenerator<GetEventsRes>"));
+        assert!(out.contains("yield await getEvents(params, { signal })"));
+    }
+
+    #[test]
+    #[ignore = "needs CLIENT_GEN_NODE_MODULES with tsx and zod"]
+    fn long_poll_run() {
+        let out = run(
+            vec![RequestInfo::new("/api/events", Method::Get, "events")
+                .with_req_params::<ListParams>()
+                .with_res_schema::<ListRes>()
+                .long_poll()],
+            GenerateConfig::default(),
+            r#"
+const responses = [
+    new Response('expired', { status: 401 }),
+    new Response(JSON.stringify({ items: ['a'], next: '1' })),
+    new Response(JSON.stringify({ items: ['b'], next: null })),
+];
+let calls = 0;
+let refreshes = 0;
+client.options.baseUrl = 'http://localhost';
+client.options.fetch = async () => {
+    calls++;
+    return responses.shift()!;
+};
+client.options.onUnauthorized = async () => {
+    refreshes++;
+    return true;
+};
+
+const controller = new AbortController();
+const pages = [];
+for await (const page of client.events.longPollGetEvents({}, { signal: controller.signal })) {
+    pages.push(page);
+    if (pages.length === 2) controller.abort();
+}
+console.log(JSON.stringify({ calls, refreshes, pages }));
+"#,
+        );
+
+        assert_eq!(
+            serde_json::from_str::<Value>(&out).unwrap(),
+            serde_json::json!({
+                "calls": 3,
+                "refreshes": 1,
+                "pages": [{ "items": ["a"], "next": "1" }, { "items": ["b"], "next": null }],
+            })
+        );
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Login {
+        user: String,
+        #[schemars(schema_with = "crate::sensitive::<String>")]
+        password: String,
+    }
+
+    #[test]
+    fn sensitive_fields() {
+        let out =
+            gen(RequestInfo::new("/api/login", Method::Post, "auth")
+                .with_req_body::<Login>());
+
+        assert!(
+            out.contains("export const postLoginSensitiveFields = { req: ['password'] }")
+        );
+    }
+
+    #[test]
+    fn latest_namespace() {
+        let v2 = RequestInfo::new("/api/users/list/v2", Method::Get, "users");
+        let v1 = RequestInfo::new("/api/users/list/v1", Method::Get, "users")
+            .with_deprecation_note(&v2);
+
+        for layout in [Layout::Namespace, Layout::Prefixed] {
+            let out = gen_with(vec![v1.clone(), v2.clone()], GenerateConfig {
+                emit_latest: true,
+                layout,
+                ..Default::default()
+            });
+
+            // a top-level export after `client` closes
+            let (client, latest) =
+                out.split_at(out.find("export namespace latest").unwrap());
+            assert!(client.contains("export namespace client {"));
+            assert_eq!(client.matches('{').count(), client.matches('}').count());
+            assert!(!latest.contains("ListV1"));
+
+            assert!(latest.contains(match layout {
+                Layout::Prefixed =>
+                    "export const usersGetListV2 = client.usersGetListV2;",
+                _ => "export const getListV2 = client.users.getListV2;",
+            }));
+        }
+    }
+
+    #[test]
+    fn deprecation_reason() {
+        let out = gen(RequestInfo::new("/api/users/list", Method::Get, "users")
+            .with_deprecation_reason("use the v2 endpoint", Some("2.1.0")));
+        assert!(out.contains("/** @deprecated since 2.1.0: use the v2 endpoint */"));
+
+        let out = gen(RequestInfo::new("/api/users/list", Method::Get, "users")
+            .with_deprecation_reason("use the v2 endpoint", None));
+        assert!(out.contains("/** @deprecated use the v2 endpoint */"));
+
+        let info: RequestInfo = serde_json::from_value(serde_json::json!({
+            "path": "/api/users/list",
+            "method": "GET",
+            "tag": "users",
+            "req_body": "None",
+            "req_params": "None",
+            "res_body": "None",
+            "deprecated": { "reason": "use the v2 endpoint" },
+        }))
+        .unwrap();
+        assert!(info.deprecated.is());
+    }
+
+    #[test]
+    fn excluded_from_client() {
+        let info: RequestInfo = serde_json::from_value(serde_json::json!({
+            "path": "/api/users/list",
+            "method": "GET",
+            "tag": "users",
+            "req_body": "None",
+            "req_params": "None",
+            "res_body": "None",
+            "deprecated": false,
+        }))
+        .unwrap();
+        assert!(info.add_to_client);
+
+        let excluded = info.with_excluded_from_client();
+        let round_trip: RequestInfo =
+            serde_json::from_value(serde_json::to_value(&excluded).unwrap()).unwrap();
+        assert!(!round_trip.add_to_client);
+
+        let out = gen_with(
+            vec![
+                round_trip,
+                RequestInfo::new("/api/users/create", Method::Post, "users"),
+            ],
+            GenerateConfig::default(),
+        );
+        assert!(!out.contains("getList"));
+        assert!(out.contains("postCreate"));
+    }
+
+    #[test]
+    fn query_style() {
+        let info = || {
+            RequestInfo::new("/api/users/list", Method::Get, "users")
+                .with_req_params::<ListParams>()
+        };
+
+        let out = gen(info());
+        assert!(out.contains("style: QueryStyle = 'comma'"));
+        assert!(!out
+            .split_whitespace()
+            .collect::<String>()
+            .contains("parse(params),'"));
+
+        let out = gen_with(vec![info()], GenerateConfig {
+            query_style: QueryStyle::Repeat,
+            ..Default::default()
+        });
+        let out = out.split_whitespace().collect::<String>();
+        assert!(out.contains("ListParamsSchema.parse(params),'repeat'"));
+
+        let out = gen_with(
+            vec![info().with_query_style(QueryStyle::Brackets)],
+            GenerateConfig {
+                query_style: QueryStyle::Repeat,
+                ..Default::default()
+            },
+        );
+        let out = out.split_whitespace().collect::<String>();
+        assert!(out.contains("ListParamsSchema.parse(params),'brackets'"));
+
+        let angular = generate_with(
+            Requests {
+                requests: vec![info().with_query_style(QueryStyle::Repeat)],
+                ..Default::default()
+            },
+            &GenerateConfig {
+                target: Target::Angular,
+                ..Default::default()
+            },
+        );
+        assert!(angular.is_err());
+    }
+
+    #[test]
+    fn without_credentials() {
+        let out = gen_with(
+            vec![
+                RequestInfo::new("/api/users/list", Method::Get, "users")
+                    .with_res_schema::<ListRes>(),
+                RequestInfo::new("/api/rates", Method::Get, "rates")
+                    .with_res_schema::<ListRes>()
+                    .with_credentials(false),
+                RequestInfo::new("/api/rates/events", Method::Get, "rates")
+                    .with_sse::<ListRes>()
+                    .with_credentials(false),
+            ],
+            GenerateConfig::default(),
+        );
+
+        assert!(out.contains("credentials: 'include'"));
+        assert!(out.contains("credentials: 'omit'"));
+        assert!(out.contains("withCredentials: false"));
+    }
+
+    #[test]
+    fn delete_placement() {
+        let by_method = RequestInfo::new("/api/users/remove", Method::Delete, "users")
+            .with_req_schema::<ListParams>();
+        assert!(by_method.req_params.is_some());
+        assert!(by_method.req_body.is_none());
+
+        let explicit = RequestInfo::new("/api/users/remove", Method::Delete, "users")
+            .with_req_params::<ListParams>()
+            .with_req_body::<ListRes>();
+        assert!(explicit.req_params.is_some());
+        assert!(explicit.req_body.is_some());
+        assert!(explicit.validate().is_empty());
+
+        let out = gen(explicit);
+        assert!(out.contains("makeQuery("));
+        assert!(out.contains("body: JSON.stringify("));
+        assert!(out.contains("method: 'DELETE'"));
+    }
+
+    #[test]
+    fn text_schema() {
+        let out = gen(RequestInfo::new("/api/auth/token", Method::Get, "auth")
+            .with_text_res_schema::<String>());
+        let compact = out.split_whitespace().collect::<String>();
+
+        assert!(out.contains("const getTokenResSchema = z.string()"));
+        assert!(compact.contains(
+            "res.text().then(options.unsafe?(data)=>(dataasGetTokenRes):\
+             getTokenResSchema.parse)"
+        ));
+        assert!(!compact.contains("readJson(res).then"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Text response schemas must be strings")]
+    fn text_schema_of_object() {
+        RequestInfo::new("/api/auth/token", Method::Get, "auth")
+            .with_text_res_schema::<ListRes>();
+    }
+
+    #[test]
+    fn interceptors() {
+        let out = gen(RequestInfo::new("/api/users", Method::Get, "users")
+            .with_res_schema::<ListRes>()
+            .as_existence_check());
+
+        assert!(out.contains("onRequest?(req: Request): MaybePromise<Request | void>;"));
+        assert!(
+            out.contains("onResponse?(res: Response): MaybePromise<Response | void>;")
+        );
+        assert!(out.contains("const res = await sent(request);"));
+        assert!(!out
+            .split_whitespace()
+            .collect::<String>()
+            .contains("returnoptions.fetch(newRequest"));
+    }
+
+    #[test]
+    fn deduped_requests() {
+        let out = gen(RequestInfo::new("/api/users", Method::Get, "users")
+            .with_res_schema::<ListRes>());
+
+        assert!(out.contains("dedupe: false"));
+        assert!(out.contains("deduped(request, () => authorized(request)"));
+    }
+
+    #[test]
+    #[ignore = "needs CLIENT_GEN_NODE_MODULES with tsx and zod"]
+    fn deduped_requests_run() {
+        let out = run(
+            vec![
+                RequestInfo::new("/api/users/list", Method::Get, "users")
+                    .with_res_schema::<ListRes>(),
+                RequestInfo::new("/api/users/ping", Method::Get, "users"),
+                RequestInfo::new("/api/users/missing", Method::Get, "users")
+                    .with_res_schema::<ListRes>(),
+            ],
+            GenerateConfig::default(),
+            r#"
+let calls = 0;
+client.options.baseUrl = 'http://localhost';
+client.options.dedupe = true;
+client.options.fetch = async (req: Request) => {
+    calls++;
+    await new Promise(resolve => setTimeout(resolve, 10));
+    if (req.url.endsWith('/list')) return new Response(JSON.stringify({ items: ['a'], next: null }));
+    if (req.url.endsWith('/ping')) return new Response('pong');
+    return new Response('gone', { status: 410 });
+};
+
+const lists = await Promise.all([client.users.getList(), client.users.getList()]);
+const pings = await Promise.all([client.users.getPing(), client.users.getPing()]);
+const texts = await Promise.all(pings.map(res => res.text()));
+const failures: string[] = [];
+await Promise.all([0, 1].map(() =>
+    client.users.getMissing().httpFailure((_, text) => { failures.push(text); }).failsafe()));
+
+console.log(JSON.stringify({ calls, lists, texts, failures }));
+"#,
+        );
+
+        assert_eq!(
+            serde_json::from_str::<Value>(&out).unwrap(),
+            serde_json::json!({
+                "calls": 3,
+                "lists": [{ "items": ["a"], "next": null }, { "items": ["a"], "next": null }],
+                "texts": ["pong", "pong"],
+                "failures": ["gone", "gone"],
+            })
+        );
+    }
+
+    #[test]
+    fn connect_timeout() {
+        let out =
+            gen(RequestInfo::new("/api/events", Method::Get, "events")
+                .with_sse::<ListRes>());
+
+        assert!(out.contains("connectTimeoutMs?: number"));
+        assert!(out.contains("this.closeHandler(new Event('timeout'))"));
+        assert!(!out.contains("class WebsocketWrapper"));
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    enum Role {
+        Admin,
+        Member,
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Address {
+        city: String,
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Profile {
+        role: Role,
+        nickname: Option<String>,
+        address: Address,
+    }
+
+    #[test]
+    fn form_meta() {
+        let out = gen_with(
+            vec![
+                RequestInfo::new("/api/profile/update", Method::Post, "profile")
+                    .with_req_body::<Profile>(),
+            ],
+            GenerateConfig {
+                emit_form_meta: true,
+                ..Default::default()
+            },
+        );
+
+        assert!(out.contains("export interface FormField"));
+        assert!(out.contains("export const postUpdateFormMeta: FormField[] = ["));
+        assert!(out.contains("options: ['Admin', 'Member']"));
+        assert!(out.contains("name: 'nickname'"));
+        assert!(out.contains("name: 'city', type: 'string', required: true"));
+
+        let nickname = &out[out.find("name: 'nickname'").unwrap()..];
+        assert!(nickname.starts_with("name: 'nickname', type: 'string', required: false"));
+    }
+
+    #[test]
+    fn request_builders() {
+        let out = gen_with(
+            vec![RequestInfo::new("/api/users/list", Method::Get, "users")
+                .with_req_params::<ListParams>()
+                .with_res_schema::<ListRes>()],
+            GenerateConfig {
+                emit_request_builders: true,
+                ..Default::default()
+            },
+        );
+
+        assert!(out.contains("const request = getList.request(params, init);"));
+        assert!(out.contains(
+            "getList.request = (params: GetListParams, init: RequestInit = {}): Request \
+             =>"
+        ));
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Optionals {
+        role: Option<Role>,
+        tags: Option<Vec<String>>,
+        nested: Option<Option<String>>,
+        address: Option<Address>,
+    }
+
+    #[test]
+    fn nullable_enum_accepts_null() {
+        let info = RequestInfo::new("/api/profile/update", Method::Post, "profile")
+            .with_req_body::<Optionals>();
+        let Kind::Schema(mut schema) = info.req_body else {
+            unreachable!()
+        };
+        crate::nullable::normalize(&mut schema.schema);
+
+        let Some(Schema::Object(role)) = schema
+            .schema
+            .object
+            .as_ref()
+            .and_then(|o| o.properties.get("role"))
+        else {
+            panic!("role is missing")
+        };
+        let values = role.enum_values.as_ref().unwrap();
+        assert!(values.contains(&Value::Null));
+    }
+
+    #[test]
+    fn generate_borrowed() {
+        let requests = Requests {
+            requests: vec![
+                RequestInfo::new("/api/profile/update", Method::Post, "profile")
+                    .with_req_body::<Optionals>(),
+                RequestInfo::new("/api/users/list", Method::Get, "users")
+                    .with_req_params::<ListParams>()
+                    .with_res_schema::<ListRes>(),
+            ],
+            ..Default::default()
+        };
+
+        let normalized = |i: usize| crate::nullable::normalized(&requests.requests[i]);
+        assert!(matches!(normalized(0), Cow::Owned(_)));
+        assert!(matches!(normalized(1), Cow::Borrowed(_)));
+        assert_eq!(
+            generate_ref(&requests).unwrap(),
+            generate(requests.clone()).unwrap()
+        );
+    }
+
+    #[test]
+    fn optionals_in_params_and_body() {
+        let out = gen_with(
+            vec![
+                RequestInfo::new("/api/profile/find", Method::Get, "profile")
+                    .with_req_params::<Optionals>(),
+                RequestInfo::new("/api/profile/update", Method::Post, "profile")
+                    .with_req_body::<Optionals>(),
+            ],
+            GenerateConfig::default(),
+        );
+
+        assert!(!out.contains("z.null(), z.undefined()"));
+        assert!(!out.contains("z.undefined(), z.null()"));
+        assert!(out.contains("getFindParamsSchema"));
+        assert!(out.contains("postUpdateReqSchema"));
+    }
+
+    #[test]
+    #[ignore = "needs CLIENT_GEN_NODE_MODULES with tsx and zod"]
+    fn optionals_in_params_and_body_run() {
+        let out = run(
+            vec![
+                RequestInfo::new("/api/profile/find", Method::Get, "profile")
+                    .with_req_params::<Optionals>(),
+                RequestInfo::new("/api/profile/update", Method::Post, "profile")
+                    .with_req_body::<Optionals>(),
+            ],
+            GenerateConfig::default(),
+            r#"
+client.options.baseUrl = 'http://localhost';
+client.options.fetch = async () => new Response(null, { status: 204 });
+
+// whether the schema of the route accepts the input
+const accepts = async (call: () => unknown) => {
+    try {
+        await call();
+        return true;
+    } catch {
+        return false;
+    }
+};
+const inputs: Record<string, any> = {
+    missing: {},
+    present: { role: 'Admin', tags: ['a'], nested: 'b', address: { city: 'c' } },
+    null: { role: null, tags: null, nested: null, address: null },
+    invalid: { role: 'Other' },
+};
+
+const params: Record<string, boolean> = {};
+const body: Record<string, boolean> = {};
+for (const [name, input] of Object.entries(inputs)) {
+    params[name] = await accepts(() => client.profile.getFind(input));
+    body[name] = await accepts(() => client.profile.postUpdate(input));
+}
+console.log(JSON.stringify({ params, body }));
+"#,
+        );
+
+        // optional in params, optional and nullable in bodies
+        assert_eq!(
+            serde_json::from_str::<Value>(&out).unwrap(),
+            serde_json::json!({
+                "params": { "missing": true, "present": true, "null": false, "invalid": false },
+                "body": { "missing": true, "present": true, "null": true, "invalid": false },
+            })
+        );
+    }
+
+    #[test]
+    fn text_body_and_response() {
+        let out = gen(RequestInfo::new("/api/notes/render", Method::Post, "notes")
+            .with_text_body()
+            .with_text_res());
+
+        assert!(out.contains("export type PostRenderReq = string;"));
+        assert!(out.contains("export type PostRenderRes = string;"));
+        assert!(out.contains("contentType: 'text/plain'"));
+        assert!(out.contains("res.text().then(okWith(res))"));
+
+        let route = &out[out.find("export function postRender").unwrap()..];
+        assert!(!route.contains("JSON.stringify"));
+        assert!(!route.contains("readJson"));
+        assert!(!route.contains("res.json()"));
+    }
+
+    #[test]
+    fn binary_response() {
+        let info = || {
+            RequestInfo::new("/api/files/download", Method::Get, "files")
+                .with_binary_res()
+        };
+
+        let out = gen(info());
+        assert!(out.contains("export type GetDownloadRes = Blob;"));
+        assert!(out.contains("res.blob().then(okWith(res))"));
+        assert!(!out.contains("res.text()"));
+
+        let out = gen_with(vec![info()], GenerateConfig {
+            target: Target::Angular,
+            ..Default::default()
+        });
+        assert!(out.contains("Observable<Blob>"));
+        assert!(out.contains("responseType: 'blob'"));
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    #[serde(rename_all = "lowercase")]
+    enum SortOrder {
+        Asc,
+        Desc,
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct SortParams {
+        sort_order: Option<SortOrder>,
+    }
+
+    #[test]
+    fn enum_values() {
+        let out = gen_with(
+            vec![RequestInfo::new("/api/users/list", Method::Get, "users")
+                .with_req_params::<SortParams>()],
+            GenerateConfig {
+                emit_enum_values: true,
+                ..Default::default()
+            },
+        );
+
+        assert!(out.contains(
+            "export const GetListParamsSortOrderValues = ['asc', 'desc'] as const"
+        ));
+        assert!(out.contains("readonly NonNullable<GetListParams['sort_order']>[]"));
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    #[serde(rename_all = "snake_case")]
+    enum JobStatus {
+        InProgress,
+        Done,
+    }
+
+    #[test]
+    fn enum_objects() {
+        let out = gen_with(
+            vec![
+                RequestInfo::new("/api/users/list", Method::Get, "users")
+                    .with_req_params::<SortParams>(),
+                RequestInfo::new("/api/jobs/status", Method::Get, "jobs")
+                    .with_res_schema::<JobStatus>(),
+            ],
+            GenerateConfig {
+                emit_enum_objects: true,
+                ..Default::default()
+            },
+        );
+        let out = out.split_whitespace().collect::<String>();
+
+        assert!(out.contains(
+            "exportconstGetListParamsSortOrder={Asc:'asc',Desc:'desc'}asconst;"
+        ));
+        assert!(
+            out.contains("exportconstGetStatusRes={InProgress:'in_progress',Done:'done'")
+        );
+        assert!(out.contains("exporttypeGetStatusRes="));
+    }
+
+    #[test]
+    fn unauthorized_retry() {
+        let out = gen(RequestInfo::new("/api/users", Method::Post, "users")
+            .with_req_body::<ListParams>()
+            .with_res_schema::<ListRes>());
+
+        assert!(out.contains("onUnauthorized?(res: Response): Promise<boolean>;"));
+        assert!(out.contains("const res = await options.fetch(request.clone());"));
+        assert!(out.contains("deduped(request, () => authorized(request)"));
+    }
+
+    #[test]
+    #[ignore = "needs CLIENT_GEN_NODE_MODULES with tsx and zod"]
+    fn unauthorized_retry_run() {
+        let out = run(
+            vec![RequestInfo::new("/api/users", Method::Post, "users")
+                .with_req_body::<ListParams>()
+                .with_res_schema::<ListRes>()],
+            GenerateConfig::default(),
+            r#"
+let expired = 1;
+const bodies: string[] = [];
+let refreshes = 0;
+client.options.baseUrl = 'http://localhost';
+client.options.fetch = async (req: Request) => {
+    bodies.push(await req.text());
+    if (expired-- > 0) return new Response('expired', { status: 401 });
+    return new Response(JSON.stringify({ items: ['a'], next: null }));
+};
+client.options.onUnauthorized = async () => {
+    refreshes++;
+    return true;
+};
+
+const retried = await client.users.postUsers({ cursor: 'x' });
+const first = { bodies: bodies.splice(0), refreshes };
+
+// a second 401 is not retried again
+expired = 2;
+refreshes = 0;
+const status = await client.users.postUsers({ cursor: 'x' }).silent().then(
+    () => 200,
+    (res: Response) => res.status,
+);
+console.log(JSON.stringify({ retried, first, second: { status, calls: bodies.length, refreshes } }));
+"#,
+        );
+
+        assert_eq!(
+            serde_json::from_str::<Value>(&out).unwrap(),
+            serde_json::json!({
+                "retried": { "items": ["a"], "next": null },
+                "first": { "bodies": [r#"{"cursor":"x"}"#, r#"{"cursor":"x"}"#], "refreshes": 1 },
+                "second": { "status": 401, "calls": 2, "refreshes": 1 },
+            })
+        );
+    }
+
+    #[test]
+    fn reconnect_policy() {
+        let policy = ReconnectPolicy::new(10, 500, 30_000);
+
+        let out = gen(RequestInfo::new("/api/chat", Method::Get, "chat")
+            .with_websocket::<ListParams, ListRes>()
+            .with_reconnect(policy));
+        let compact = out.split_whitespace().collect::<String>();
+        assert!(compact.contains("{maxRetries:10,baseDelayMs:500,maxDelayMs:30000}"));
+        assert!(compact.contains("privatepolicy?:ReconnectPolicy"));
+        assert!(compact.contains("this.retry({kind:'parse',error})"));
+
+        let out = gen(RequestInfo::new("/api/feed", Method::Get, "feed")
+            .with_sse::<ListRes>()
+            .with_reconnect(policy));
+        let compact = out.split_whitespace().collect::<String>();
+        assert!(compact
+            .contains("['message'],{maxRetries:10,baseDelayMs:500,maxDelayMs:30000}"));
+
+        let err = generate_with(
+            Requests {
+                requests: vec![RequestInfo::new("/api/feed", Method::Get, "feed")
+                    .with_res_schema::<ListRes>()
+                    .with_reconnect(policy)],
+                ..Default::default()
+            },
+            &GenerateConfig::default(),
+        );
+        assert!(err.is_err());
+    }
+
+    #[test]
+    #[ignore = "needs CLIENT_GEN_NODE_MODULES with tsx and zod"]
+    fn sse_close_cancels_reconnect_run() {
+        let out = run(
+            vec![RequestInfo::new("/api/feed/events", Method::Get, "feed")
+                .with_sse::<ListRes>()
+                .with_reconnect(ReconnectPolicy::new(5, 20, 20))],
+            GenerateConfig::default(),
+            r#"
+let sources = 0;
+(globalThis as any).location = { protocol: 'http:', host: 'localhost' };
+(globalThis as any).EventSource = class {
+    static CLOSED = 2;
+    readyState = 0;
+    listeners = new Map<string, (ev: unknown) => void>();
+    constructor() {
+        sources++;
+        // every attempt fails
+        setTimeout(() => {
+            if (this.readyState !== 2) this.listeners.get('error')?.(new Event('error'));
+        }, 5);
+    }
+    addEventListener(event: string, listener: (ev: unknown) => void) {
+        this.listeners.set(event, listener);
+    }
+    close() {
+        this.readyState = 2;
+    }
+};
+
+const states: string[] = [];
+const sse = client.feed.getEvents();
+sse.subscribe(state => states.push(state.status));
+// closes while the reconnect is pending
+await new Promise(resolve => setTimeout(resolve, 10));
+sse.close();
+await new Promise(resolve => setTimeout(resolve, 100));
+console.log(JSON.stringify({ sources, states }));
+"#,
+        );
+
+        assert_eq!(
+            serde_json::from_str::<Value>(&out).unwrap(),
+            serde_json::json!({ "sources": 1, "states": ["reconnecting", "closed"] })
+        );
+    }
+
+    #[test]
+    #[ignore = "needs CLIENT_GEN_NODE_MODULES with tsx and zod"]
+    fn websocket_connect_after_timeout_run() {
+        let out = run(
+            vec![RequestInfo::new("/api/chat/connect", Method::Get, "chat")
+                .with_websocket::<ListParams, ListRes>()],
+            GenerateConfig::default(),
+            r#"
+let sockets = 0;
+let opens = true;
+(globalThis as any).location = { protocol: 'http:', host: 'localhost' };
+(globalThis as any).WebSocket = class {
+    onopen?: () => void;
+    constructor() {
+        // the first attempt never opens
+        if (++sockets > 1 && opens) setTimeout(() => this.onopen?.(), 5);
+    }
+    close() {}
+    send() {}
+};
+client.options.connectTimeoutMs = 20;
+client.options.reconnect = { maxRetries: 1, baseDelayMs: 0, maxDelayMs: 0 };
+
+const connect = async () => {
+    sockets = 0;
+    const events: string[] = [];
+    const ws = client.chat.getConnect()
+        .connect(() => { events.push('connect'); })
+        .failure(() => { events.push('failure'); })
+        .disconnect(() => { events.push('disconnect'); });
+    await new Promise(resolve => setTimeout(resolve, 100));
+    return { sockets, events, state: ws.state.status };
+};
+
+const retried = await connect();
+opens = false;
+const failed = await connect();
+console.log(JSON.stringify({ retried, failed }));
+"#,
+        );
+
+        assert_eq!(
+            serde_json::from_str::<Value>(&out).unwrap(),
+            serde_json::json!({
+                "retried": { "sockets": 2, "events": ["connect"], "state": "open" },
+                "failed": { "sockets": 2, "events": ["failure"], "state": "closed" },
+            })
+        );
+    }
+
+    #[test]
+    fn websocket_connection_state() {
+        let out = gen(RequestInfo::new("/api/chat", Method::Get, "chat")
+            .with_websocket::<ListParams, ListRes>());
+
+        assert!(out.contains("export type ConnectionState ="));
+        assert!(out.contains("{ status: 'reconnecting'; attempt: number }"));
+        assert!(out.contains("subscribe(listener: (state: ConnectionState) => void)"));
+
+        let out =
+            gen(RequestInfo::new("/api/feed", Method::Get, "feed").with_sse::<ListRes>());
+        assert_eq!(out.matches("export type ConnectionState =").count(), 1);
+        assert!(out.contains("subscribe(listener: (state: ConnectionState) => void)"));
+        assert!(
+            out.contains("clearTimeout(this.reconnectTimer);\n        this.sse.close();")
+        );
+    }
+
+    #[test]
+    fn websocket_protocols() {
+        let out = gen(RequestInfo::new("/api/chat", Method::Get, "chat")
+            .with_websocket::<ListParams, ListRes>()
+            .with_ws_protocols(&["chat.v1", "json"])
+            .with_ws_token_param("token"));
+        let compact = out.split_whitespace().collect::<String>().replace('"', "'");
+
+        assert!(compact.contains(
+            "newWebSocket(withWsToken(`${wsUrl}`,'chat','token'),['chat.v1','json']"
+        ));
+        assert!(out.contains("const withWsToken = "));
+
+        let err = generate_with(
+            Requests {
+                requests: vec![RequestInfo::new("/api/chat", Method::Get, "chat")
+                    .with_res_schema::<ListRes>()
+                    .with_ws_protocols(&["chat.v1"])],
+                ..Default::default()
+            },
+            &GenerateConfig::default(),
+        );
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn inferred_return_types() {
+        let info = || {
+            RequestInfo::new("/api/users/list", Method::Get, "users")
+                .with_req_params::<ListParams>()
+                .with_res_schema::<ListRes>()
+                .as_existence_check()
+        };
+
+        let out = gen(info());
+        assert!(out.contains("): PromiseWrapper<GetListRes> {"));
+        assert!(out.contains("): Promise<boolean> {"));
+
+        let out = gen_with(vec![info()], GenerateConfig {
+            explicit_return_types: false,
+            ..Default::default()
+        });
+        assert!(out.contains("export function getList("));
+        assert!(!out.contains("PromiseWrapper<GetListRes>"));
+        assert!(!out.contains("): Promise<boolean> {"));
+    }
+
+    #[test]
+    fn nested_namespaces() {
+        let out = gen_with(
+            vec![
+                RequestInfo::new("/api/users/list", Method::Get, "admin.users"),
+                RequestInfo::new("/api/orders/list", Method::Get, "admin.orders"),
+            ],
+            GenerateConfig::default(),
+        );
+
+        assert_eq!(out.matches("export namespace admin {").count(), 1);
+        assert!(out.contains("export namespace users {"));
+        assert!(out.contains("export namespace orders {"));
+        assert!(out.contains("urlOf('admin.users', "));
+
+        let escaped = gen(RequestInfo::new("/api/users", Method::Get, "admin.new"));
+        assert!(escaped.contains("export namespace nNew {"));
+
+        let err = generate_with(
+            Requests {
+                requests: vec![RequestInfo::new(
+                    "/api/users",
+                    Method::Get,
+                    "admin.user-data",
+                )],
+                ..Default::default()
+            },
+            &GenerateConfig::default(),
+        );
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn no_content() {
+        let out = gen(
+            RequestInfo::new("/api/users/remove", Method::Delete, "users")
+                .with_req_params::<ListParams>()
+                .with_empty_res(),
+        );
+
+        assert!(out.contains("PromiseWrapper<void>"));
+        assert!(out.contains("ok(undefined, res)"));
+    }
+
+    #[test]
+    fn empty_res() {
+        let info = RequestInfo::new("/api/users/ping", Method::Get, "users");
+        assert!(gen(info.clone()).contains("PromiseWrapper<Response>"));
+
+        let info = info.with_empty_res();
+        assert!(info.no_content);
+
+        let out = gen(info);
+        assert!(out.contains(
+            "export function getPing(init: RequestInit = {}): PromiseWrapper<void>"
+        ));
+        assert!(!out.contains("PromiseWrapper<Response>"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Response schema already present")]
+    fn empty_res_with_schema() {
+        RequestInfo::new("/api/users/ping", Method::Get, "users")
+            .with_res_schema::<ListRes>()
+            .with_empty_res();
+    }
+
+    #[test]
+    #[should_panic(expected = "Response already declared empty")]
+    fn schema_with_empty_res() {
+        RequestInfo::new("/api/users/ping", Method::Get, "users")
+            .with_empty_res()
+            .with_res_schema::<ListRes>();
+    }
+
+    #[test]
+    fn forced_get_body() {
+        let info = RequestInfo::new("/api/users/search", Method::Get, "users")
+            .with_req_body_forced::<ListParams>();
+        assert_eq!(info.validate().len(), 1);
+
+        let out = gen(info);
+        assert!(out.contains("req: GetSearchReq"));
+        assert!(out.contains("body: JSON.stringify(getSearchReqSchema.parse(req))"));
+    }
+
+    #[test]
+    fn options_validation() {
+        let out = gen_with(
+            vec![RequestInfo::new("/api/users", Method::Get, "users")],
+            GenerateConfig {
+                validate_options: true,
+                ..Default::default()
+            },
+        );
+
+        assert!(out.contains("export const optionsSchema = z.object({"));
+        assert!(out.contains("export function configure(next: Partial<Options>)"));
+        assert!(out.contains("options.fetch must be a function"));
+    }
+
+    #[test]
+    fn stability_tags() {
+        let requests = || {
+            vec![
+                RequestInfo::new("/api/users/list", Method::Get, "users")
+                    .with_stability(Stability::Beta),
+                RequestInfo::new("/api/users/audit", Method::Get, "users")
+                    .with_stability(Stability::Internal),
+            ]
+        };
+
+        let out = gen_with(requests(), GenerateConfig::default());
+        assert!(out.contains("/** @beta */"));
+        assert!(out.contains("/** @internal */"));
+        assert!(out.contains("export function getAudit("));
+
+        let out = gen_with(requests(), GenerateConfig {
+            exclude_internal: true,
+            ..Default::default()
+        });
+        assert!(out.contains("/** @beta */"));
+        assert!(!out.contains("@internal"));
+        assert!(!out.contains("getAudit"));
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct PostPath {
+        id: u32,
+        post_id: String,
+    }
+
+    #[test]
+    fn path_params() {
+        let out = gen(RequestInfo::new(
+            "/api/users/{id}/posts/{post_id}",
+            Method::Get,
+            "users",
+        )
+        .with_path_params::<PostPath>()
+        .with_res_schema::<ListRes>());
+        assert!(out.contains("export type GetByIdPostsByPostIdPathParams"));
+        assert!(out.contains("pathParams: GetByIdPostsByPostIdPathParams"));
+        // interpolated from the parsed params
+        let compact = out.split_whitespace().collect::<String>();
+        assert!(compact.contains(
+            "constparsed=options.unsafe?pathParams:getByIdPostsByPostIdPathParamsSchema.\
+             parse(pathParams);"
+        ));
+        assert!(out.contains("/${encodeURIComponent(String(parsed['post_id']))}`"));
+        assert!(out.contains("urlOf('users', getByIdPostsByPostIdPath(pathParams))"));
+
+        let sse = gen(
+            RequestInfo::new("/api/users/{id}/events", Method::Get, "users")
+                .with_path_params::<PostPath>()
+                .with_sse::<ListRes>(),
+        );
+        assert!(sse.contains("urlOf('users', `${getByIdEventsPath(pathParams)}`)"));
+
+        let unvalidated = gen_with(
+            vec![RequestInfo::new("/api/users/{id}", Method::Get, "users")
+                .with_path_params::<PostPath>()],
+            GenerateConfig {
+                runtime_validation: false,
+                ..Default::default()
+            },
+        );
+        let compact = unvalidated.split_whitespace().collect::<String>();
+        assert!(compact.contains(
+            "constgetByIdPath=(pathParams:GetByIdPathParams)=>`/api/users/\
+             ${encodeURIComponent(String(pathParams['id']))}`;"
+        ));
+
+        let err = generate(Requests {
+            requests: vec![RequestInfo::new("/api/users/{id}", Method::Get, "users")],
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Path placeholders of getById need path params"));
+
+        let err = generate(Requests {
+            requests: vec![RequestInfo::new("/api/users/{user}", Method::Get, "users")
+                .with_path_params::<PostPath>()],
+            ..Default::default()
+        });
+        assert!(err.is_err());
+    }
+
+    #[derive(JsonSchema)]
+    #[serde(rename_all = "camelCase")]
+    #[allow(dead_code)]
+    struct CamelPath {
+        user_id: u32,
+    }
+
+    #[derive(JsonSchema)]
+    #[serde(rename_all = "camelCase")]
+    #[allow(dead_code)]
+    struct CamelParams {
+        page_size: u32,
+        sort_order: Option<String>,
+    }
+
+    #[test]
+    fn renamed_params() {
+        let out =
+            gen(
+                RequestInfo::new("/api/users/{userId}/posts", Method::Get, "users")
+                    .with_path_params::<CamelPath>()
+                    .with_req_params::<CamelParams>()
+                    .with_res_schema::<ListRes>(),
+            );
+
+        assert!(out.contains("pageSize"));
+        assert!(out.contains("sortOrder"));
+        assert!(!out.contains("page_size"));
+        assert!(out.contains("encodeURIComponent(String(parsed['userId']))"));
+
+        // the query is serialized from the parsed params, keyed like the schema
+        let compact = out.split_whitespace().collect::<String>();
+        assert!(compact.contains("makeQuery(options.unsafe?paramsas"));
+        assert!(compact.contains("ParamsSchema.parse(params)"));
+
+        let snake_path = generate(Requests {
+            requests: vec![RequestInfo::new(
+                "/api/users/{user_id}/posts",
+                Method::Get,
+                "users",
+            )
+            .with_path_params::<CamelPath>()],
+            ..Default::default()
+        });
+        assert!(snake_path.is_err());
+    }
+
+    #[test]
+    fn unexpected_kind_error() {
+        let mut info = RequestInfo::new("/api/chat", Method::Get, "chat");
+        info.req_params = RequestInfo::new("/api/chat", Method::Get, "chat")
+            .with_sse::<ListRes>()
+            .res_body;
+
+        let err = generate(Requests {
+            requests: vec![info],
+            ..Default::default()
+        })
+        .unwrap_err();
+        let err = err.downcast_ref::<GenError>().unwrap();
+
+        assert_eq!(err.path, "/api/chat");
+        assert_eq!(err.tag, "chat");
+        assert_eq!(err.slot, Slot::Params);
+    }
+
+    #[test]
+    fn infer_types() {
+        let out = gen_with(
+            vec![RequestInfo::new("/api/users", Method::Post, "users")
+                .with_req_body::<ListParams>()
+                .with_res_schema::<ListRes>()],
+            GenerateConfig {
+                io_types: false,
+                ..Default::default()
+            },
+        );
+
+        assert!(
+            out.contains("export type PostUsersReq = z.infer<typeof postUsersReqSchema>")
+        );
+        assert!(
+            out.contains("export type PostUsersRes = z.infer<typeof postUsersResSchema>")
+        );
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct VersionHeaders {
+        #[serde(rename = "X-Api-Version")]
+        version: u32,
+    }
+
+    #[test]
+    fn request_headers() {
+        let out = gen(RequestInfo::new("/api/users", Method::Get, "users")
+            .with_req_headers::<VersionHeaders>()
+            .with_res_schema::<ListRes>());
+
+        assert!(out.contains("export type GetUsersHeaders"));
+        assert!(out.contains("headers: GetUsersHeaders"));
+        assert!(out.contains("getUsersHeadersSchema.parse(headers)"));
+
+        let err = generate(Requests {
+            requests: vec![RequestInfo::new("/api/users", Method::Get, "users")
+                .with_req_headers::<ListRes>()],
+            ..Default::default()
+        });
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn sse_respects_page_protocol() {
+        let out =
+            gen(RequestInfo::new("/api/events", Method::Get, "events")
+                .with_sse::<ListRes>());
+
+        assert!(!out.contains("`https://"));
+        assert!(out.contains("`${location.protocol}//${location.host}${url}`"));
+    }
+
+    #[test]
+    fn resolve_url() {
+        let out = gen_with(
+            vec![
+                RequestInfo::new("/api/users", Method::Get, "users")
+                    .with_res_schema::<ListRes>(),
+                RequestInfo::new("/api/events", Method::Get, "events")
+                    .with_sse::<ListRes>(),
+                RequestInfo::new("/api/chat", Method::Get, "chat")
+                    .with_websocket::<ListRes, ListRes>(),
+            ],
+            GenerateConfig::default(),
+        );
+
+        assert!(out.contains("resolveUrl?(tag: string, path: string): string;"));
+        assert!(out.contains("urlOf('users', '/api/users')"));
+        assert!(out.contains("urlOf('events', `/api/events`)"));
+        assert!(out.contains("urlOf('chat', `/api/chat`)"));
+    }
+
+    #[derive(Debug)]
+    struct OperationNames;
+
+    impl NameStrategy for OperationNames {
+        fn name(&self, info: &RequestInfo) -> String {
+            format!("{}_{}", info.method, info.tag).to_lowercase()
+        }
+    }
+
+    #[test]
+    fn custom_names() {
+        let out = gen_with(
+            vec![RequestInfo::new("/api/users/list", Method::Get, "users")
+                .with_res_schema::<ListRes>()],
+            GenerateConfig {
+                names: Arc::new(OperationNames),
+                emit_latest: true,
+                ..Default::default()
+            },
+        );
+
+        assert!(out.contains("export function get_users("));
+        assert!(out.contains("export type Get_usersRes"));
+        assert!(out.contains("export const get_users = client.users.get_users;"));
+        assert!(!out.contains("getList"));
+    }
+
+    #[derive(Debug)]
+    struct ShortNames;
+
+    impl NameStrategy for ShortNames {
+        fn name(&self, info: &RequestInfo) -> String { info.tag.clone() }
+    }
+
+    #[test]
+    fn custom_existence_check_names() {
+        let info =
+            || RequestInfo::new("/api/profile", Method::Head, "x").as_existence_check();
+
+        let out = gen_with(vec![info()], GenerateConfig {
+            names: Arc::new(ShortNames),
+            ..Default::default()
+        });
+        assert!(out.contains("export function existsX("));
+
+        let out = gen(info().with_operation_id("headers"));
+        assert!(out.contains("export function existsHeaders("));
+
+        let out = gen(info().with_operation_id("list-users"));
+        assert!(out.contains("export function existsListUsers("));
+    }
+
+    #[test]
+    fn operation_id_names() {
+        let out = gen(RequestInfo::new("/api/users/list", Method::Get, "users")
+            .with_operation_id("list-all_users")
+            .with_res_schema::<ListRes>());
+
+        assert!(out.contains("export function listAllUsers("));
+        assert!(!out.contains("getList"));
+    }
+
+    #[test]
+    fn invalid_identifiers() {
+        let err = generate(Requests {
+            requests: vec![RequestInfo::new("/api/2fa", Method::Post, "auth")
+                .with_operation_id("2fa-verify")],
+            ..Default::default()
+        })
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("`2faVerify` of POST /api/2fa"));
+
+        let err = generate(Requests {
+            requests: vec![RequestInfo::new("/api/user-data", Method::Get, "user-data")],
+            ..Default::default()
+        })
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("Segment `user-data` of tag user-data"));
+    }
+
+    #[test]
+    fn duplicate_names() {
+        let requests = || {
+            vec![
+                RequestInfo::new("/api/user-profile", Method::Get, "users")
+                    .with_res_schema::<ListRes>(),
+                RequestInfo::new("/api/user/profile", Method::Get, "users")
+                    .with_res_schema::<ListRes>(),
+            ]
+        };
+
+        let err = generate(Requests {
+            requests: requests(),
+            ..Default::default()
+        })
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("`getUserProfile`"));
+        assert!(err.contains("GET /api/user-profile, GET /api/user/profile"));
+
+        let out = gen_with(requests(), GenerateConfig {
+            disambiguate_names: true,
+            ..Default::default()
+        });
+        assert!(out.contains("export function getUserProfile("));
+        assert!(out.contains("export function getUserProfile2("));
+    }
+
+    /// The user to create
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct NewUser {
+        name: String,
+    }
+
+    /// The created user
+    ///
+    /// With its id
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct CreatedUser {
+        id: u32,
+    }
+
+    #[test]
+    fn schema_descriptions() {
+        let info = RequestInfo::new("/api/users", Method::Post, "users")
+            .with_req_body::<NewUser>()
+            .with_res_schema::<CreatedUser>();
+        assert_eq!(info.docs.req_body.as_deref(), Some("The user to create"));
+
+        let out = gen(info);
+        assert!(out.contains("     * @param req The user to create\n"));
+        assert!(out
+            .contains("     * @returns The created user\n     *\n     * With its id\n"));
+        assert!(!out.contains(".describe('The user to create')"));
+    }
+
+    #[test]
+    fn summary_and_description() {
+        let out = gen(RequestInfo::new("/api/users", Method::Post, "users")
+            .with_summary("Creates a user")
+            .with_description("Fails if the name is taken.")
+            .with_req_body::<NewUser>());
+
+        assert!(out.contains(
+            "    /**\n     * Creates a user\n     *\n     * Fails if the name is \
+             taken.\n     *\n     * @param req The user to create\n     */\n"
+        ));
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Job {
+        job_id: String,
+    }
+
+    #[test]
+    fn ndjson() {
+        let info = || {
+            RequestInfo::new("/api/logs", Method::Post, "logs")
+                .with_req_body::<NewUser>()
+                .with_ndjson_res::<Job>()
+        };
+
+        let out = gen(info());
+        assert!(out.contains("async function* readNdjson<T>("));
+        assert!(out.contains("export async function* postLogs(req: PostLogsReq, "));
+        assert!(out.contains("AsyncGenerator<PostLogsRes>"));
+        let compact = out.split_whitespace().collect::<String>();
+        assert!(
+            compact.contains("constres=awaitauthorized(request);if(!res.ok)throwres;")
+        );
+        assert!(compact.contains(
+            "yield*readNdjson(res,options.unsafe?(data)=>dataasPostLogsRes:\
+             postLogsResSchema.parse);"
+        ));
+
+        let err = generate_with(
+            Requests {
+                requests: vec![info()],
+                ..Default::default()
+            },
+            &GenerateConfig {
+                target: Target::Angular,
+                ..Default::default()
+            },
+        );
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn status_union() {
+        let info = || {
+            RequestInfo::new("/api/users", Method::Post, "users")
+                .with_req_body::<NewUser>()
+                .with_status_res::<CreatedUser>(200)
+                .with_status_res::<Job>(202)
+        };
+
+        let out = gen(info());
+        let compact = out.split_whitespace().collect::<String>();
+        assert!(compact.contains("constpostUsersResSchemas={200:z.object({"));
+        assert!(compact.contains(
+            "exporttypePostUsersRes={status:200;body:z.\
+             output<typeofpostUsersResSchemas[200]>}|{status:202;body:z.\
+             output<typeofpostUsersResSchemas[202]>};"
+        ));
+        assert!(compact.contains(
+            "res.statusinpostUsersResSchemas?readStatus<PostUsersRes>(res,\
+             postUsersResSchemas).then(okWith(res)):err(res)"
+        ));
+
+        let err = generate_with(
+            Requests {
+                requests: vec![info()],
+                ..Default::default()
+            },
+            &GenerateConfig {
+                target: Target::Angular,
+                ..Default::default()
+            },
+        );
+        assert!(err.is_err());
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct ApiError {
+        message: String,
+    }
+
+    #[test]
+    fn error_schema() {
+        let info = || {
+            RequestInfo::new("/api/users/list", Method::Get, "users")
+                .with_res_schema::<ListRes>()
+                .with_error(404, "Not found")
+                .with_error_schema::<ApiError>(422)
+        };
+
+        let out = gen(info());
+        let compact = out.split_whitespace().collect::<String>();
+        assert!(compact.contains("constgetListErrors={422:z.object({"));
+        assert!(compact.contains("status:422;body:z.output<typeofgetListErrors[422]>"));
+        assert!(out.contains("PromiseWrapper<GetListRes, GetListError>"));
+        assert!(compact.contains(":errWith<GetListError>(res,getListErrors))"));
+        assert!(out.contains("404: Not found"));
+
+        let out = gen_with(vec![info()], GenerateConfig {
+            runtime_validation: false,
+            ..Default::default()
+        });
+        let compact = out.split_whitespace().collect::<String>();
+        assert!(compact.contains("constgetListErrors={422:null};"));
+        assert!(compact.contains("status:422;body:{message:string;}"));
+
+        let err = generate_with(
+            Requests {
+                requests: vec![info()],
+                ..Default::default()
+            },
+            &GenerateConfig {
+                target: Target::Angular,
+                ..Default::default()
+            },
+        );
+        assert!(err.is_err());
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct RateLimit {
+        #[serde(rename = "X-RateLimit-Remaining")]
+        remaining: u32,
+        #[serde(rename = "X-Cursor")]
+        cursor: Option<String>,
+    }
+
+    #[test]
+    fn response_headers() {
+        let info = || {
+            RequestInfo::new("/api/users/list", Method::Get, "users")
+                .with_res_schema::<ListRes>()
+                .with_res_headers::<RateLimit>()
+        };
+
+        let out = gen(info());
+        let compact = out.split_whitespace().collect::<String>();
+        assert!(compact.contains(
+            "exporttypeGetListResHeaders=z.output<typeofgetListResHeadersSchema>"
+        ));
+        assert!(compact.contains(
+            "readHeaders(headers,{'X-Cursor':'string','X-RateLimit-Remaining':'number'})"
+        ));
+        assert!(out.contains("PromiseWrapper<GetListRes, never, GetListResHeaders>"));
+        assert!(compact.contains("okWith(res,getListResHeaders(res.headers))"));
+
+        let err = generate_with(
+            Requests {
+                requests: vec![info()],
+                ..Default::default()
+            },
+            &GenerateConfig {
+                target: Target::Angular,
+                ..Default::default()
+            },
+        );
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn retries() {
+        let info = || {
+            RequestInfo::new("/api/users", Method::Get, "users")
+                .with_res_schema::<ListRes>()
+                .with_retries(3)
+                .with_timeout_ms(5000)
+        };
+
+        let out = gen(info());
+        assert!(out.contains("const retried = ("));
+        assert!(out.contains("retries: number = 3"));
+        let compact = out.split_whitespace().collect::<String>();
+        assert!(compact.contains(
+            "retried(request,retries,(request)=>timed(request,timeoutMs,authorized))"
+        ));
+
+        let err = generate_with(
+            Requests {
+                requests: vec![info()],
+                ..Default::default()
+            },
+            &GenerateConfig {
+                target: Target::Angular,
+                ..Default::default()
+            },
+        );
+        assert!(err.is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Retries only apply to idempotent methods")]
+    fn retries_of_post() {
+        RequestInfo::new("/api/users", Method::Post, "users").with_retries(3);
+    }
+
+    #[test]
+    fn request_timeout() {
+        let info = || {
+            RequestInfo::new("/api/users", Method::Get, "users")
+                .with_res_schema::<ListRes>()
+                .with_timeout_ms(5000)
+        };
+
+        let out = gen(info());
+        assert!(out.contains("class TimeoutError"));
+        assert!(out.contains("timeoutMs: number = 5000"));
+        assert!(out.contains("timed(request, timeoutMs, authorized)"));
+
+        let out = gen_with(vec![info()], GenerateConfig {
+            abort_all: true,
+            ..Default::default()
+        });
+        let compact = out.split_whitespace().collect::<String>();
+        assert!(
+            compact.contains("tracked(init,(signal)=>timed(newRequest(request,{signal})")
+        );
+
+        assert!(out.contains(
+            "if (request.signal.aborted) controller.abort(request.signal.reason);"
+        ));
+
+        let out = gen(RequestInfo::new("/api/users", Method::Get, "users")
+            .with_res_schema::<ListRes>());
+        assert!(!out.contains("TimeoutError"));
+
+        let err = generate_with(
+            Requests {
+                requests: vec![info()],
+                ..Default::default()
+            },
+            &GenerateConfig {
+                target: Target::Angular,
+                ..Default::default()
+            },
+        );
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn named_sse_events() {
+        let info = || {
+            RequestInfo::new("/api/jobs/progress", Method::Get, "jobs")
+                .with_sse_event::<ListParams>("progress")
+                .with_sse_event::<ListRes>("done")
+        };
+
+        let out = gen(info());
+        assert!(out.contains("const getProgressEvents = {"));
+        assert!(out.contains("event: 'progress';"));
+        assert!(out.contains("z.output<typeof getProgressEvents['done']>"));
+        assert!(out.contains("Object.keys(getProgressEvents)"));
+        assert!(out.contains("export type GetProgressSSE = SSE<GetProgressMsg>"));
+        assert!(out.contains("on<Event extends"));
+
+        let err = generate_with(
+            Requests {
+                requests: vec![info()],
+                ..Default::default()
+            },
+            &GenerateConfig {
+                target: Target::Angular,
+                ..Default::default()
+            },
+        );
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn without_runtime_validation() {
+        let out = gen_with(
+            vec![
+                RequestInfo::new("/api/users", Method::Post, "users")
+                    .with_req_params::<SortParams>()
+                    .with_req_body::<Profile>()
+                    .with_res_schema::<ListRes>(),
+                RequestInfo::new("/api/events", Method::Get, "events")
+                    .with_sse::<ListRes>(),
+            ],
+            GenerateConfig {
+                runtime_validation: false,
+                ..Default::default()
+            },
+        );
+
+        assert!(!out.contains("from 'zod'"));
+        assert!(!out.contains("Schema.parse"));
+        assert!(out.contains("export interface PostUsersRes {"));
+        assert!(out.contains("items: string[];"));
+        assert!(out.contains("next?: string | null;"));
+        assert!(out.contains("'asc' | 'desc'"));
+        assert!(out.contains("JSON.stringify(req)"));
+        assert!(out.contains("(data) => data as PostUsersRes"));
+        assert!(out.contains("(data) => data as GetEventsMsg"));
+
+        let err = generate_with(
+            Requests {
+                requests: vec![],
+                ..Default::default()
+            },
+            &GenerateConfig {
+                runtime_validation: false,
+                enforce_formats: true,
+                ..Default::default()
+            },
+        );
+        assert!(err.is_err());
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct LoginForm {
+        username: String,
+        password: String,
+        remember: Option<bool>,
+    }
+
+    #[test]
+    fn urlencoded_body() {
+        let out = gen(RequestInfo::new("/api/auth/login", Method::Post, "auth")
+            .with_urlencoded_req::<LoginForm>());
+
+        assert!(out.contains("urlEncoded(postLoginReqSchema.parse(req))"));
+        assert!(out.contains("contentType: 'application/x-www-form-urlencoded'"));
+        assert!(!out.contains("JSON.stringify(postLoginReqSchema"));
+
+        let err = generate(Requests {
+            requests: vec![RequestInfo::new("/api/auth/login", Method::Post, "auth")
+                .with_urlencoded_req::<ListRes>()],
+            ..Default::default()
+        });
+        assert!(err.is_err());
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Upload {
+        title: String,
+        #[schemars(schema_with = "crate::file")]
+        avatar: Vec<u8>,
+        #[schemars(schema_with = "crate::files")]
+        documents: Vec<Vec<u8>>,
+    }
+
+    #[test]
+    fn multipart_body() {
+        let info = || {
+            RequestInfo::new("/api/profile/upload", Method::Post, "profile")
+                .with_multipart_req::<Upload>()
+        };
+
+        let out = gen(info());
+        assert!(out.contains("avatar: Blob;"));
+        assert!(out.contains("documents: Blob[];"));
+        assert!(out.contains("formData({ ...req, ...postUploadReqSchema.parse(req) })"));
+        assert!(!out.contains("contentType: 'application/json'"));
+
+        let schema = &out[out.find("const postUploadReqSchema").unwrap()..];
+        let schema = &schema[..schema.find(';').unwrap()];
+        assert!(schema.contains("title"));
+        assert!(!schema.contains("avatar"));
+
+        let out = gen_with(vec![info()], GenerateConfig {
+            runtime_validation: false,
+            ..Default::default()
+        });
+        assert!(out.contains("avatar: Blob;"));
+        assert!(out.contains("documents: Blob[];"));
+        assert!(out.contains("formData(req)"));
+    }
+
+    #[test]
+    fn stripped_prefixes() {
+        let names = |strip_prefixes: Vec<&str>| {
+            gen_with(
+                vec![RequestInfo::new("/v1/users/list", Method::Get, "users")
+                    .with_res_schema::<ListRes>()],
+                GenerateConfig {
+                    names: Arc::new(DefaultNames {
+                        strip_prefixes: strip_prefixes
+                            .into_iter()
+                            .map(String::from)
+                            .collect(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            )
+        };
+
+        assert!(names(vec!["api/"]).contains("export function getV1UsersList("));
+        assert!(names(vec!["v1/"]).contains("export function getList("));
+        assert!(names(vec![]).contains("export function getV1UsersList("));
+        assert!(names(vec!["V1/"]).contains("export function getV1UsersList("));
+    }
+
+    #[test]
+    fn preserved_case() {
+        let names = |preserve_case: bool| {
+            gen_with(
+                vec![
+                    RequestInfo::new("/api/users/byUserID", Method::Get, "users")
+                        .with_res_schema::<ListRes>(),
+                ],
+                GenerateConfig {
+                    names: Arc::new(DefaultNames {
+                        preserve_case,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            )
+        };
+
+        assert!(names(false).contains("export function getByuserid("));
+        assert!(names(true).contains("export function getByUserID("));
+        assert!(names(true).contains("export type GetByUserIDRes"));
+    }
 }
@@ -0,0 +1,79 @@
+use schemars::schema::{InstanceType, Schema, SchemaObject, SingleOrVec};
+
+use crate::{
+    nullable::unwrap_nullable,
+    walk::{walk, Step},
+};
+
+/// The first property of a headers schema that is not a string, number or
+/// boolean, headers being flat. Also checks url encoded bodies, which are flat
+/// as well.
+pub(crate) fn invalid(schema: &SchemaObject) -> Option<String> {
+    if schema.object.is_none() {
+        return Some("schema".to_string());
+    }
+
+    let mut invalid = None;
+    properties(schema, |name, property| {
+        let primitive = match property {
+            Schema::Object(property) => is_primitive(unwrap_nullable(property)),
+            Schema::Bool(_) => false,
+        };
+        if !primitive && invalid.is_none() {
+            invalid = Some(name.to_string());
+        }
+    });
+    invalid
+}
+
+/// The kind of each property of a flat headers schema, for converting the
+/// header values before validation.
+pub(crate) fn kinds(schema: &SchemaObject) -> Vec<(&str, &'static str)> {
+    let mut kinds = Vec::new();
+    properties(schema, |name, property| {
+        let types = match property {
+            Schema::Object(property) => unwrap_nullable(property).instance_type.as_ref(),
+            Schema::Bool(_) => None,
+        };
+        let has = |t: InstanceType| types.is_some_and(|types| types.contains(&t));
+
+        let kind = if has(InstanceType::Number) || has(InstanceType::Integer) {
+            "number"
+        } else if has(InstanceType::Boolean) {
+            "boolean"
+        } else {
+            "string"
+        };
+        kinds.push((name, kind));
+    });
+    kinds
+}
+
+/// Visits the properties of the flat schema, skipping nested schemas.
+fn properties<'a>(schema: &'a SchemaObject, mut visit: impl FnMut(&'a str, &'a Schema)) {
+    walk(schema, &mut |path, property| {
+        if let [Step::Property(name)] = path {
+            visit(name, property);
+        }
+        false
+    });
+}
+
+fn is_primitive(schema: &SchemaObject) -> bool {
+    let primitive = |t: &InstanceType| {
+        matches!(
+            t,
+            InstanceType::String
+                | InstanceType::Number
+                | InstanceType::Integer
+                | InstanceType::Boolean
+                | InstanceType::Null
+        )
+    };
+
+    match &schema.instance_type {
+        Some(SingleOrVec::Single(t)) => primitive(t),
+        Some(SingleOrVec::Vec(types)) => types.iter().all(primitive),
+        None => schema.enum_values.is_some(),
+    }
+}
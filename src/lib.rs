@@ -1,14 +1,96 @@
+#[cfg(feature = "client-gen")]
+mod angular;
+#[cfg(any(feature = "dart", feature = "python-gen"))]
+mod clients;
+#[cfg(feature = "client-gen")]
+mod config;
+#[cfg(feature = "dart")]
+mod dart;
 mod deprecated;
+mod encoding;
+#[cfg(feature = "client-gen")]
+mod error;
+#[cfg(feature = "client-gen")]
+mod form_meta;
+#[cfg(feature = "client-gen")]
+mod formats;
 #[cfg(feature = "client-gen")]
 mod gen;
+#[cfg(feature = "client-gen")]
+mod headers;
 mod method;
+#[cfg(feature = "client-gen")]
+mod mock;
+mod multipart;
+#[cfg(feature = "client-gen")]
+mod namespace;
+#[cfg(feature = "client-gen")]
+mod naming;
+#[cfg(feature = "client-gen")]
+mod nullable;
+#[cfg(feature = "openapi")]
+mod openapi;
+#[cfg(feature = "client-gen")]
+mod path;
+#[cfg(feature = "python-gen")]
+mod python;
+mod sensitive;
+#[cfg(feature = "client-gen")]
+mod shared;
+mod stability;
+mod status;
+#[cfg(feature = "client-gen")]
+mod strict;
 mod types;
+#[cfg(feature = "client-gen")]
+mod typescript;
+#[cfg(feature = "client-gen")]
+mod walk;
 
+#[cfg(feature = "client-gen")]
+pub use config::{Eol, GenerateConfig, Layout, Target};
+#[cfg(feature = "dart")]
+pub use dart::{generate_dart, generate_dart_with};
 pub use deprecated::Deprecated;
+pub use encoding::{BodyEncoding, QueryStyle};
+#[cfg(feature = "client-gen")]
+pub use error::{GenError, Slot};
 #[cfg(feature = "client-gen")]
-pub use gen::generate;
+pub use gen::{
+    base_symbols,
+    generate,
+    generate_multi,
+    generate_multi_with,
+    generate_ref,
+    generate_ref_with,
+    generate_with,
+    warnings,
+};
 pub use method::{Method, MethodUnknown};
-pub use types::{generator, Kind, RequestInfo, Requests, Tag};
+#[cfg(feature = "client-gen")]
+pub use mock::{generate_mock, generate_mock_with};
+pub use multipart::{file, files, FILE_FORMAT};
+#[cfg(feature = "client-gen")]
+pub use naming::{DefaultNames, NameStrategy};
+#[cfg(feature = "openapi")]
+pub use openapi::{from_openapi, to_openapi};
+#[cfg(feature = "python-gen")]
+pub use python::{generate_python, generate_python_with};
+pub use sensitive::{sensitive, SENSITIVE_KEYWORD};
+pub use stability::Stability;
+pub use status::StatusCodes;
+pub use types::{
+    generator,
+    settings,
+    Kind,
+    PageConfig,
+    ReconnectPolicy,
+    RequestInfo,
+    Requests,
+    SchemaDocs,
+    SchemaSettingsFn,
+    Tag,
+};
 
 #[cfg(test)]
 mod tests {
@@ -36,9 +118,19 @@ mod tests {
 
         let out = generate(Requests {
             requests: vec![info],
+            ..Default::default()
         })
         .unwrap();
 
         std::fs::write("sse.ts", out).unwrap();
     }
+
+    #[test]
+    fn input_schema() {
+        let schema = Requests::input_schema();
+
+        assert!(schema.definitions.contains_key("RequestInfo"));
+        assert!(schema.definitions.contains_key("Method"));
+        assert!(schema.definitions.contains_key("Kind"));
+    }
 }
@@ -15,19 +15,31 @@ fn main() {
 
 #[cfg(all(feature = "client-gen", feature = "binary"))]
 mod binary {
-    use std::{error::Error as StdError, fs, io::Read, path::PathBuf};
+    use std::{
+        collections::HashMap,
+        error::Error as StdError,
+        fs,
+        io::Read,
+        path::{Path, PathBuf},
+    };
 
     use clap::Parser;
-    use schemars_client_gen::{generate, RequestInfo, Requests};
+    use schemars_client_gen::{
+        generate,
+        generate_multi,
+        warnings,
+        GenerateConfig,
+        Requests,
+    };
 
     /// Create a client.ts file from
     #[derive(Parser, Debug)]
     #[command(version, about, long_about = None)]
     struct Args {
-        /// The input json file to generate the client from.
+        /// The input json files to generate the client from, merged in order.
         /// If no file is provided, it is read from stdin.
         #[arg(short, long)]
-        file: Option<PathBuf>,
+        file: Vec<PathBuf>,
 
         /// The output file to generate the client into.
         /// If no file is provided, it is output to stdout.
@@ -35,6 +47,12 @@ mod binary {
         #[arg(short, long)]
         output_file: Option<PathBuf>,
 
+        /// The output directory to generate one file per tag into, instead of
+        /// a single file.
+        /// Is ignored when test_only is set.
+        #[arg(short = 'd', long, conflicts_with = "output_file")]
+        output_dir: Option<PathBuf>,
+
         /// If the input contains does not contain "wrapper" object
         #[arg(short, long, default_value_t = false)]
         plain: bool,
@@ -42,12 +60,36 @@ mod binary {
         /// If the input should only be tested
         #[arg(short, long, default_value_t = false)]
         test_only: bool,
+
+        /// If the output file or directory should only be checked to be up to
+        /// date, failing if it is not or if the directory contains `.ts` files
+        /// which are not generated anymore. Nothing is written.
+        #[arg(short, long, default_value_t = false)]
+        check: bool,
+
+        /// If routes and tag base urls defined by several input files should
+        /// fail instead of only warning, the first definition being kept.
+        #[arg(short, long, default_value_t = false)]
+        strict: bool,
+
+        /// If the JSON schema of the input format should be output to stdout
+        /// instead of generating a client.
+        #[arg(long, default_value_t = false)]
+        emit_input_schema: bool,
     }
 
     pub(super) fn main() -> Result<(), Box<dyn StdError>> {
         let args = Args::parse();
 
-        if let Some(ref file) = args.file {
+        if args.emit_input_schema {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&Requests::input_schema())?
+            );
+            return Ok(());
+        }
+
+        for file in &args.file {
             if !file.exists() {
                 return Err(String::from("Provided input path does not exist").into());
             }
@@ -63,43 +105,226 @@ mod binary {
                     return Err(String::from("Provided output path is not a file").into());
                 }
             }
+
+            if let Some(ref dir) = args.output_dir {
+                if dir.exists() && !dir.is_dir() {
+                    return Err(
+                        String::from("Provided output path is not a directory").into()
+                    );
+                }
+            }
         }
 
-        let input = match args.file {
-            Some(file) => fs::read_to_string(file)?,
-            None => {
+        // the name of each input next to its content
+        let inputs = match args.file.is_empty() {
+            false => args
+                .file
+                .iter()
+                .map(|file| Ok((file.display().to_string(), fs::read_to_string(file)?)))
+                .collect::<Result<Vec<(String, String)>, std::io::Error>>()?,
+            true => {
                 let mut input = Vec::new();
                 let stdin = std::io::stdin();
                 let mut handle = stdin.lock();
                 handle.read_to_end(&mut input)?;
-                String::from_utf8(input)?
+                vec![("stdin".to_string(), String::from_utf8(input)?)]
             },
         };
 
-        let json: Vec<RequestInfo> = match args.plain {
-            true => serde_json::from_str(&input)?,
-            false => {
-                let json: Requests = serde_json::from_str(&input)?;
-                json.requests
-            },
-        };
+        let mut json = Requests::default();
+        // the input each request is read from
+        let mut sources = Vec::new();
+        for (source, input) in inputs {
+            let next: Requests = match args.plain {
+                true => Requests {
+                    requests: serde_json::from_str(&input)?,
+                    ..Default::default()
+                },
+                false => serde_json::from_str(&input)?,
+            };
+            sources.extend(next.requests.iter().map(|_| source.clone()));
+            merge(&mut json, next, args.strict)?;
+        }
+        dedup(&mut json, &sources, args.strict)?;
+
+        for warning in warnings(&json, &GenerateConfig::default()) {
+            eprintln!("Warning: {warning}");
+        }
 
         if args.test_only {
             return Ok(());
         };
 
-        let out = generate(Requests { requests: json })?;
+        let outputs = match (&args.output_dir, args.output_file) {
+            (Some(dir), _) => generate_multi(json)?
+                .into_iter()
+                .map(|(file, out)| (dir.join(file), out))
+                .collect::<Vec<(PathBuf, String)>>(),
+            (None, Some(file)) => vec![(file, generate(json)?)],
+            (None, None) if args.check => {
+                return Err(String::from(
+                    "Checking requires an output file or directory",
+                )
+                .into());
+            },
+            (None, None) => {
+                println!("{}", generate(json)?);
+                eprintln!("Success!");
+                return Ok(());
+            },
+        };
+
+        if args.check {
+            let mut stale = outputs
+                .iter()
+                .filter(|(file, out)| !up_to_date(file, out))
+                .count();
+            if let Some(dir) = &args.output_dir {
+                stale += obsolete(dir, &outputs)?;
+            }
+            if stale > 0 {
+                return Err(format!("{stale} file(s) are not up to date").into());
+            }
+
+            eprintln!("Up to date!");
+
+            return Ok(());
+        }
 
-        match args.output_file {
-            Some(file) => fs::write(file, out)?,
-            None => println!("{out}"),
+        for (file, out) in outputs {
+            if let Some(dir) = file.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(file, out)?;
         }
 
         eprintln!("Success!");
 
         Ok(())
     }
+
+    /// Appends the requests and tag base urls of another input file, keeping
+    /// the tag base urls already defined, which is an error if `strict`.
+    fn merge(
+        json: &mut Requests,
+        next: Requests,
+        strict: bool,
+    ) -> Result<(), Box<dyn StdError>> {
+        for (tag, url) in &next.tag_base_urls {
+            if json
+                .tag_base_urls
+                .get(tag)
+                .is_some_and(|existing| existing != url)
+            {
+                let duplicate = format!(
+                    "The base url of tag {tag} is defined by several input files"
+                );
+                match strict {
+                    true => return Err(duplicate.into()),
+                    false => eprintln!("Warning: {duplicate}"),
+                }
+            }
+        }
+
+        json.extend(next);
+        Ok(())
+    }
+
+    /// Removes the routes defined more than once but the first definition,
+    /// reporting the inputs defining them, which is an error if `strict`.
+    fn dedup(
+        json: &mut Requests,
+        sources: &[String],
+        strict: bool,
+    ) -> Result<(), Box<dyn StdError>> {
+        let mut firsts = HashMap::new();
+        for (i, info) in json.requests.iter().enumerate() {
+            let key = (info.path.as_str(), info.method, info.tag.as_str());
+            let first = *firsts.entry(key).or_insert(i);
+            if first == i {
+                continue;
+            }
+
+            let route = format!("{} {} of tag {}", info.method, info.path, info.tag);
+            let duplicate = match sources[first] == sources[i] {
+                true => format!("{route} is defined several times in {}", sources[i]),
+                false => format!(
+                    "{route} is defined by several input files, {} and {}",
+                    sources[first], sources[i]
+                ),
+            };
+            match strict {
+                true => return Err(duplicate.into()),
+                false => eprintln!("Warning: {duplicate}"),
+            }
+        }
+
+        json.dedup();
+        Ok(())
+    }
+
+    /// Counts the `.ts` files in the output directory which are not generated
+    /// anymore, e.g. of a removed tag, reporting each.
+    fn obsolete(
+        dir: &Path,
+        outputs: &[(PathBuf, String)],
+    ) -> Result<usize, Box<dyn StdError>> {
+        if !dir.is_dir() {
+            return Ok(0);
+        }
+
+        let mut obsolete = 0;
+        for entry in fs::read_dir(dir)? {
+            let file = entry?.path();
+            if file.extension().is_some_and(|ext| ext == "ts")
+                && !outputs.iter().any(|(output, _)| output == &file)
+            {
+                eprintln!("{} is not generated anymore", file.display());
+                obsolete += 1;
+            }
+        }
+
+        Ok(obsolete)
+    }
+
+    /// Whether the file matches the generated output, trailing line endings
+    /// apart. Reports the first differing line otherwise.
+    fn up_to_date(file: &Path, out: &str) -> bool {
+        let Ok(existing) = fs::read_to_string(file) else {
+            eprintln!("{} is missing", file.display());
+            return false;
+        };
+
+        let existing = existing.trim_end_matches(['\r', '\n']);
+        let out = out.trim_end_matches(['\r', '\n']);
+        if existing == out {
+            return true;
+        }
+
+        let mut existing_lines = existing.lines();
+        let mut out_lines = out.lines();
+        for line in 1.. {
+            let (old, new) = (existing_lines.next(), out_lines.next());
+            if old.is_none() && new.is_none() {
+                eprintln!("{} differs in its line endings", file.display());
+                break;
+            }
+            if old != new {
+                eprintln!("{} differs at line {line}:", file.display());
+                eprintln!("- {}", old.unwrap_or_default());
+                eprintln!("+ {}", new.unwrap_or_default());
+                break;
+            }
+        }
+
+        false
+    }
 }
 
 #[cfg(all(feature = "client-gen", feature = "binary"))]
-fn main() -> Result<(), Box<dyn std::error::Error>> { binary::main() }
+fn main() {
+    if let Err(err) = binary::main() {
+        eprintln!("Error: {err}");
+        std::process::exit(1);
+    }
+}
@@ -3,9 +3,10 @@ use std::{
     str::FromStr,
 };
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, JsonSchema, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING-KEBAB-CASE")]
 pub enum Method {
     Options,
@@ -39,6 +40,17 @@ impl Method {
             Method::Patch => "PATCH",
         }
     }
+
+    /// Whether repeating the request has the same effect as sending it once.
+    pub fn is_idempotent(&self) -> bool {
+        !matches!(self, Method::Post | Method::Patch | Method::Connect)
+    }
+
+    /// Whether the method is about the connection rather than a resource, e.g.
+    /// CORS preflights.
+    pub fn is_meta(&self) -> bool {
+        matches!(self, Method::Options | Method::Trace | Method::Connect)
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -54,7 +66,7 @@ impl FromStr for Method {
     type Err = MethodUnknown;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
+        match s.to_ascii_uppercase().as_str() {
             "OPTIONS" => Ok(Self::Options),
             "GET" => Ok(Self::Get),
             "POST" => Ok(Self::Post),
@@ -103,3 +115,175 @@ mod actix {
         }
     }
 }
+
+// poem re-exports the `http` crate as `poem::http`
+#[cfg(any(feature = "axum", feature = "poem"))]
+mod axum {
+    use http::Method as HttpMethod;
+
+    use super::{Method, MethodUnknown};
+
+    impl TryFrom<HttpMethod> for Method {
+        type Error = MethodUnknown;
+
+        fn try_from(value: HttpMethod) -> Result<Self, Self::Error> {
+            if value == HttpMethod::OPTIONS {
+                Ok(Self::Options)
+            } else if value == HttpMethod::GET {
+                Ok(Self::Get)
+            } else if value == HttpMethod::POST {
+                Ok(Self::Post)
+            } else if value == HttpMethod::PUT {
+                Ok(Self::Put)
+            } else if value == HttpMethod::DELETE {
+                Ok(Self::Delete)
+            } else if value == HttpMethod::HEAD {
+                Ok(Self::Head)
+            } else if value == HttpMethod::TRACE {
+                Ok(Self::Trace)
+            } else if value == HttpMethod::CONNECT {
+                Ok(Self::Connect)
+            } else if value == HttpMethod::PATCH {
+                Ok(Self::Patch)
+            } else {
+                Err(MethodUnknown)
+            }
+        }
+    }
+
+    impl From<Method> for HttpMethod {
+        fn from(value: Method) -> Self {
+            match value {
+                Method::Options => HttpMethod::OPTIONS,
+                Method::Get => HttpMethod::GET,
+                Method::Post => HttpMethod::POST,
+                Method::Put => HttpMethod::PUT,
+                Method::Delete => HttpMethod::DELETE,
+                Method::Head => HttpMethod::HEAD,
+                Method::Trace => HttpMethod::TRACE,
+                Method::Connect => HttpMethod::CONNECT,
+                Method::Patch => HttpMethod::PATCH,
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use http::Method as HttpMethod;
+
+        use crate::Method;
+
+        #[test]
+        fn round_trip() {
+            for method in [
+                Method::Options,
+                Method::Get,
+                Method::Post,
+                Method::Put,
+                Method::Delete,
+                Method::Head,
+                Method::Trace,
+                Method::Connect,
+                Method::Patch,
+            ] {
+                assert_eq!(Method::try_from(HttpMethod::from(method)).unwrap(), method);
+            }
+
+            let unknown = HttpMethod::from_bytes(b"PURGE").unwrap();
+            assert!(Method::try_from(unknown).is_err());
+        }
+    }
+}
+
+#[cfg(feature = "warp")]
+mod warp {
+    use warp::http::Method as WarpMethod;
+
+    use super::{Method, MethodUnknown};
+
+    impl TryFrom<WarpMethod> for Method {
+        type Error = MethodUnknown;
+
+        fn try_from(value: WarpMethod) -> Result<Self, Self::Error> {
+            if value == WarpMethod::OPTIONS {
+                Ok(Self::Options)
+            } else if value == WarpMethod::GET {
+                Ok(Self::Get)
+            } else if value == WarpMethod::POST {
+                Ok(Self::Post)
+            } else if value == WarpMethod::PUT {
+                Ok(Self::Put)
+            } else if value == WarpMethod::DELETE {
+                Ok(Self::Delete)
+            } else if value == WarpMethod::HEAD {
+                Ok(Self::Head)
+            } else if value == WarpMethod::TRACE {
+                Ok(Self::Trace)
+            } else if value == WarpMethod::CONNECT {
+                Ok(Self::Connect)
+            } else if value == WarpMethod::PATCH {
+                Ok(Self::Patch)
+            } else {
+                Err(MethodUnknown)
+            }
+        }
+    }
+
+    impl From<Method> for WarpMethod {
+        fn from(value: Method) -> Self {
+            match value {
+                Method::Options => WarpMethod::OPTIONS,
+                Method::Get => WarpMethod::GET,
+                Method::Post => WarpMethod::POST,
+                Method::Put => WarpMethod::PUT,
+                Method::Delete => WarpMethod::DELETE,
+                Method::Head => WarpMethod::HEAD,
+                Method::Trace => WarpMethod::TRACE,
+                Method::Connect => WarpMethod::CONNECT,
+                Method::Patch => WarpMethod::PATCH,
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use warp::http::Method as WarpMethod;
+
+        use crate::Method;
+
+        #[test]
+        fn round_trip() {
+            for method in [
+                Method::Options,
+                Method::Get,
+                Method::Post,
+                Method::Put,
+                Method::Delete,
+                Method::Head,
+                Method::Trace,
+                Method::Connect,
+                Method::Patch,
+            ] {
+                assert_eq!(Method::try_from(WarpMethod::from(method)).unwrap(), method);
+            }
+
+            let unknown = WarpMethod::from_bytes(b"PURGE").unwrap();
+            assert!(Method::try_from(unknown).is_err());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Method;
+
+    #[test]
+    fn from_str_ignores_case() {
+        assert_eq!("get".parse::<Method>().unwrap(), Method::Get);
+        assert_eq!("Patch".parse::<Method>().unwrap(), Method::Patch);
+        assert_eq!("OPTIONS".parse::<Method>().unwrap(), Method::Options);
+        assert!("purge".parse::<Method>().is_err());
+
+        assert_eq!("Patch".parse::<Method>().unwrap().to_string(), "PATCH");
+    }
+}
@@ -0,0 +1,194 @@
+use std::{collections::BTreeMap, error::Error as StdError};
+
+use crate::{
+    config::{GenerateConfig, Layout, Target},
+    gen::{finish, first_upper, format_js, included},
+    namespace::{self, Namespaces},
+    naming,
+    types::{RequestInfo, Requests},
+    Stability,
+};
+
+pub fn generate_mock(requests: &Requests) -> Result<String, Box<dyn StdError>> {
+    generate_mock_with(requests, &GenerateConfig::default())
+}
+
+/// Generates a mock of the client generated with the same config, importing
+/// its types from `mock_import`. Its functions take the same arguments and
+/// resolve to the fixture set for them, e.g. `mock.users.fixtures.getList`,
+/// rejecting without one. Event sources, websockets and NDJSON streams are
+/// left out.
+pub fn generate_mock_with(
+    requests: &Requests,
+    config: &GenerateConfig,
+) -> Result<String, Box<dyn StdError>> {
+    if config.target == Target::Angular {
+        return Err("Mocks are not supported by the angular target".into());
+    }
+
+    let requests = requests
+        .requests
+        .iter()
+        .filter(|v| included(v, config))
+        .filter(|v| !config.exclude_internal || v.stability != Stability::Internal)
+        .collect::<Vec<&RequestInfo>>();
+
+    let mut names = naming::resolve(&requests, config)?;
+    if config.layout != Layout::Namespace {
+        names = naming::flatten(&requests, &names)?;
+    }
+
+    // the fixture fields and functions of each tag
+    let mut tags = BTreeMap::<&str, (Vec<String>, Vec<String>)>::new();
+    for (v, name) in requests.iter().zip(&names) {
+        if v.res_body.is_sse() || v.res_body.is_websocket() || v.res_body.is_ndjson() {
+            continue;
+        }
+
+        let scope = match config.layout {
+            Layout::Namespace => format!("client.{}.", namespace::path(&v.tag)),
+            Layout::Flat | Layout::Prefixed => "client.".to_string(),
+        };
+        let (fields, functions) = tags.entry(&v.tag).or_default();
+
+        let body = match v.res_body.is_some() && !v.no_content {
+            true => {
+                let res = format!("{scope}{}Res", first_upper(name));
+                fields.push(format!("{name}?: {res};"));
+                format!(
+                    "): Promise<{res}> {{
+        return fixtures.{name} === undefined
+            ? Promise.reject(new Error('No fixture for {} {}'))
+            : Promise.resolve(fixtures.{name});",
+                    v.method, v.path
+                )
+            },
+            false => "): Promise<void> {\n        return Promise.resolve();".to_string(),
+        };
+
+        functions.push(format!(
+            "    export function {name}(..._args: Parameters<typeof {scope}{name}>{body}
+    }}\n"
+        ));
+    }
+
+    let client_import = match config.layout {
+        Layout::Namespace | Layout::Prefixed =>
+            format!("import {{ client }} from '{}';\n", config.mock_import),
+        Layout::Flat => format!("import * as client from '{}';\n", config.mock_import),
+    };
+
+    let out = match config.layout {
+        Layout::Namespace => {
+            let mut nested = Namespaces::default();
+            for (tag, (fields, functions)) in tags {
+                nested.insert(
+                    tag,
+                    format!(
+                        "    export const fixtures: {{ {} }} = {{}};\n\n{}",
+                        fields.join(" "),
+                        functions.join("\n")
+                    ),
+                )?;
+            }
+
+            format!(
+                "{client_import}\nexport namespace mock {{\n{}\n}}",
+                nested.render()
+            )
+        },
+        Layout::Flat | Layout::Prefixed => {
+            let (fields, functions) = tags.into_values().fold(
+                (Vec::new(), Vec::new()),
+                |(mut all_fields, mut all_functions), (fields, functions)| {
+                    all_fields.extend(fields);
+                    all_functions.extend(functions);
+                    (all_fields, all_functions)
+                },
+            );
+
+            let body = format!(
+                "export const fixtures: {{ {} }} = {{}};\n\n{}",
+                fields.join(" "),
+                functions.join("\n")
+            );
+            match config.layout {
+                Layout::Prefixed =>
+                    format!("{client_import}\nexport namespace mock {{\n{body}\n}}"),
+                _ => format!("{client_import}\n{body}"),
+            }
+        },
+    };
+
+    Ok(finish(format_js(&out, config)?, config))
+}
+
+#[cfg(test)]
+mod tests {
+    use schemars::JsonSchema;
+
+    use crate::{
+        generate_mock,
+        generate_mock_with,
+        GenerateConfig,
+        Layout,
+        Method,
+        RequestInfo,
+        Requests,
+    };
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct User {
+        name: String,
+    }
+
+    fn requests() -> Requests {
+        Requests::default()
+            .with(|| {
+                RequestInfo::new("/api/users/get", Method::Get, "users")
+                    .with_res_schema::<User>()
+            })
+            .with(|| RequestInfo::new("/api/users/delete", Method::Delete, "users"))
+            .with(|| {
+                RequestInfo::new("/api/users/events", Method::Get, "users")
+                    .with_sse::<User>()
+            })
+    }
+
+    #[test]
+    fn fixtures() {
+        let out = generate_mock(&requests()).unwrap();
+        let compact = out.split_whitespace().collect::<String>();
+
+        assert!(out.contains("import { client } from './client';"));
+        assert!(out.contains("export namespace mock {"));
+        assert!(compact.contains("exportconstfixtures:{getGet?:client.users.GetGetRes"));
+        assert!(compact.contains(
+            "exportfunctiongetGet(..._args:Parameters<typeofclient.users.getGet>):\
+             Promise<client.users.GetGetRes>"
+        ));
+        assert!(compact.contains("Promise.resolve(fixtures.getGet)"));
+        assert!(compact.contains(
+            "deleteDelete(..._args:Parameters<typeofclient.users.deleteDelete>):\
+             Promise<void>"
+        ));
+        assert!(!out.contains("getEvents"));
+    }
+
+    #[test]
+    fn flat_fixtures() {
+        let out = generate_mock_with(&requests(), &GenerateConfig {
+            layout: Layout::Flat,
+            mock_import: "./api".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        let compact = out.split_whitespace().collect::<String>();
+
+        assert!(out.contains("import * as client from './api';"));
+        assert!(!out.contains("namespace"));
+        assert!(compact.contains("usersGetGet?:client.UsersGetGetRes"));
+        assert!(compact.contains("Parameters<typeofclient.usersGetGet>"));
+    }
+}
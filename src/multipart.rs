@@ -0,0 +1,92 @@
+#[cfg(feature = "client-gen")]
+use schemars::schema::SingleOrVec;
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{ArrayValidation, InstanceType, Schema, SchemaObject},
+};
+
+#[cfg(feature = "client-gen")]
+use crate::typescript;
+
+/// The string format marking a field of a multipart body as a file, typed as
+/// `Blob` in generated clients.
+pub const FILE_FORMAT: &str = "binary";
+
+/// The schema of a file field of a multipart body, to be used as
+/// `#[schemars(schema_with = "schemars_client_gen::file")]`.
+pub fn file(_: &mut SchemaGenerator) -> Schema {
+    Schema::Object(SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        format: Some(FILE_FORMAT.to_string()),
+        ..Default::default()
+    })
+}
+
+/// The schema of several files appended under the same name, typed as
+/// `Blob[]`.
+pub fn files(gen: &mut SchemaGenerator) -> Schema {
+    Schema::Object(SchemaObject {
+        instance_type: Some(InstanceType::Array.into()),
+        array: Some(Box::new(ArrayValidation {
+            items: Some(file(gen).into()),
+            ..Default::default()
+        })),
+        ..Default::default()
+    })
+}
+
+#[cfg(feature = "client-gen")]
+pub(crate) fn is_file(schema: &SchemaObject) -> bool {
+    schema.format.as_deref() == Some(FILE_FORMAT)
+        && schema
+            .instance_type
+            .as_ref()
+            .is_some_and(|t| t.contains(&InstanceType::String))
+}
+
+/// Splits the file fields off an object schema, returning the schema of the
+/// remaining fields and the TypeScript type of the files, e.g.
+/// `{ avatar: Blob; documents?: Blob[] }`.
+#[cfg(feature = "client-gen")]
+pub(crate) fn split_files(schema: &SchemaObject) -> (SchemaObject, Option<String>) {
+    let mut rest = schema.clone();
+    let Some(object) = &mut rest.object else {
+        return (rest, None);
+    };
+
+    let mut files = Vec::new();
+    object.properties.retain(|name, property| {
+        let Schema::Object(property) = property else {
+            return true;
+        };
+
+        let is_files = matches!(
+            property.array.as_ref().and_then(|a| a.items.as_ref()),
+            Some(SingleOrVec::Single(item))
+                if matches!(&**item, Schema::Object(item) if is_file(item))
+        );
+        let ty = match (is_file(property), is_files) {
+            (true, _) => "Blob",
+            (_, true) => "Blob[]",
+            _ => return true,
+        };
+
+        let optional = match object.required.contains(name) {
+            true => "",
+            false => "?",
+        };
+        files.push(format!("{}{optional}: {ty};", typescript::key(name)));
+        false
+    });
+
+    for name in object.required.clone() {
+        if !object.properties.contains_key(&name) {
+            object.required.remove(&name);
+        }
+    }
+
+    match files.is_empty() {
+        true => (rest, None),
+        false => (rest, Some(format!("{{ {} }}", files.join(" ")))),
+    }
+}
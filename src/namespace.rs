@@ -0,0 +1,147 @@
+use std::{collections::BTreeMap, error::Error as StdError};
+
+use crate::gen::first_upper;
+
+/// Words that can not be used as namespace or binding names in TypeScript.
+const RESERVED: &[&str] = &[
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "import",
+    "in",
+    "instanceof",
+    "new",
+    "null",
+    "return",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "implements",
+    "interface",
+    "let",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "static",
+    "yield",
+    "await",
+];
+
+pub(crate) fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+
+    (first.is_ascii_alphabetic() || first == '_' || first == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+        && !RESERVED.contains(&s)
+}
+
+/// The name of the namespace of a tag segment, reserved words being prefixed,
+/// e.g. `function` becomes `nFunction`.
+fn escape(segment: &str) -> String {
+    match RESERVED.contains(&segment) {
+        true => format!("n{}", first_upper(segment)),
+        false => segment.to_string(),
+    }
+}
+
+/// The path of the namespace of a tag, e.g. `admin.nDefault` for
+/// `admin.default`.
+pub(crate) fn path(tag: &str) -> String {
+    tag.split('.')
+        .map(escape)
+        .collect::<Vec<String>>()
+        .join(".")
+}
+
+/// Namespaces nested by the `.` separated segments of their tags, e.g.
+/// `admin.users` becomes `users` inside of `admin`.
+#[derive(Default)]
+pub(crate) struct Namespaces<'a> {
+    doc: Option<String>,
+    content: Vec<String>,
+    children: BTreeMap<&'a str, Namespaces<'a>>,
+}
+
+impl<'a> Namespaces<'a> {
+    pub(crate) fn insert(
+        &mut self,
+        tag: &'a str,
+        content: String,
+    ) -> Result<(), Box<dyn StdError>> {
+        self.get(tag)?.content.push(content);
+        Ok(())
+    }
+
+    /// Sets the doc comment emitted before the namespace of a tag.
+    pub(crate) fn document(
+        &mut self,
+        tag: &'a str,
+        doc: String,
+    ) -> Result<(), Box<dyn StdError>> {
+        self.get(tag)?.doc = Some(doc);
+        Ok(())
+    }
+
+    fn get(&mut self, tag: &'a str) -> Result<&mut Self, Box<dyn StdError>> {
+        let mut namespace = self;
+        for segment in tag.split('.') {
+            if !is_identifier(segment) && !RESERVED.contains(&segment) {
+                return Err(format!(
+                    "Segment `{segment}` of tag {tag} is not a valid identifier"
+                )
+                .into());
+            }
+
+            namespace = namespace.children.entry(segment).or_default();
+        }
+
+        Ok(namespace)
+    }
+
+    /// Renders the nested namespaces, without the content of the root itself.
+    pub(crate) fn render(&self) -> String {
+        self.children
+            .iter()
+            .map(|(name, namespace)| {
+                let mut s = namespace.doc.clone().unwrap_or_default();
+                s.push_str(&format!("export namespace {} {{\n", escape(name)));
+                s.push_str(&namespace.content.join("\n"));
+                if !namespace.children.is_empty() {
+                    s.push('\n');
+                    s.push_str(&namespace.render());
+                }
+                s.push_str("\n}");
+                s
+            })
+            .collect::<Vec<String>>()
+            .join("\n\n")
+    }
+}
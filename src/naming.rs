@@ -0,0 +1,215 @@
+use std::{
+    borrow::Borrow,
+    collections::{BTreeMap, HashSet},
+    error::Error as StdError,
+    fmt::Debug,
+};
+
+use crate::{
+    config::GenerateConfig,
+    gen::first_upper,
+    namespace::is_identifier,
+    types::RequestInfo,
+};
+
+/// Chooses the names of the generated functions, the names of their types
+/// being derived from them.
+pub trait NameStrategy: Debug + Send + Sync {
+    fn name(&self, info: &RequestInfo) -> String;
+}
+
+/// Names routes by their method and path without the stripped prefixes and
+/// tag, e.g. `GET /api/users/{id}/posts` tagged `users` becomes
+/// `getByIdPosts`. Routes with an operation id are named by it instead, e.g.
+/// `list-users` becomes `listUsers`.
+#[derive(Debug, Clone)]
+pub struct DefaultNames {
+    /// Prefixes stripped in order from the path without its leading `/`, each
+    /// only if present. Case-sensitive, an empty list disables stripping.
+    pub strip_prefixes: Vec<String>,
+    /// Whether the tag is stripped from the path after the prefixes.
+    pub strip_tag: bool,
+    /// Whether the casing within path segments is kept, e.g. `userID` becoming
+    /// `UserID` instead of `Userid`.
+    pub preserve_case: bool,
+}
+
+impl Default for DefaultNames {
+    fn default() -> Self {
+        DefaultNames {
+            strip_prefixes: vec!["api/".to_string()],
+            strip_tag: true,
+            preserve_case: false,
+        }
+    }
+}
+
+impl NameStrategy for DefaultNames {
+    fn name(&self, info: &RequestInfo) -> String {
+        if let Some(operation_id) = &info.operation_id {
+            return operation_name(operation_id);
+        }
+
+        let start = info.method.to_string().to_lowercase();
+
+        let mut path = info.path.strip_prefix('/').unwrap_or(&info.path);
+        for prefix in &self.strip_prefixes {
+            path = path.strip_prefix(prefix.as_str()).unwrap_or(path);
+        }
+        if self.strip_tag {
+            path = path.strip_prefix(&format!("{}/", info.tag)).unwrap_or(path);
+        }
+
+        format!("{start}{}", camel_case(path, self.preserve_case))
+    }
+}
+
+/// The function names of the routes, in order. Names which are no valid
+/// identifiers, e.g. starting with a digit, are an error. Routes of the same
+/// tag sharing a name are an error, unless `disambiguate_names` is enabled,
+/// which suffixes all but the first of them with a number.
+pub(crate) fn resolve(
+    requests: &[impl Borrow<RequestInfo>],
+    config: &GenerateConfig,
+) -> Result<Vec<String>, Box<dyn StdError>> {
+    let requests = requests
+        .iter()
+        .map(Borrow::borrow)
+        .collect::<Vec<&RequestInfo>>();
+    let names = requests
+        .iter()
+        .map(|info| config.names.name(info))
+        .collect::<Vec<String>>();
+
+    if let Some((info, name)) = requests
+        .iter()
+        .zip(&names)
+        .find(|(_, name)| !is_identifier(name))
+    {
+        return Err(format!(
+            "The function name `{name}` of {} {} is not a valid identifier",
+            info.method, info.path
+        )
+        .into());
+    }
+
+    let mut routes = BTreeMap::<(&str, &str), Vec<&RequestInfo>>::new();
+    for (info, name) in requests.iter().zip(&names) {
+        routes.entry((&info.tag, name)).or_default().push(info);
+    }
+
+    if !config.disambiguate_names {
+        if let Some(((tag, name), routes)) = routes.iter().find(|(_, r)| r.len() > 1) {
+            let routes = routes
+                .iter()
+                .map(|info| format!("{} {}", info.method, info.path))
+                .collect::<Vec<String>>()
+                .join(", ");
+            return Err(format!(
+                "Routes of tag {tag} share the function name `{name}`: {routes}"
+            )
+            .into());
+        }
+        return Ok(names);
+    }
+
+    let mut taken = requests
+        .iter()
+        .zip(&names)
+        .map(|(info, name)| (info.tag.as_str(), name.clone()))
+        .collect::<HashSet<(&str, String)>>();
+    let mut seen = HashSet::new();
+
+    Ok(requests
+        .iter()
+        .zip(&names)
+        .map(|(info, name)| {
+            if seen.insert((info.tag.as_str(), name.as_str())) {
+                return name.clone();
+            }
+
+            let name = (2..)
+                .map(|i| format!("{name}{i}"))
+                .find(|name| !taken.contains(&(info.tag.as_str(), name.clone())))
+                .unwrap();
+            taken.insert((info.tag.as_str(), name.clone()));
+            name
+        })
+        .collect())
+}
+
+/// The names of the routes as flat module exports, prefixed with their tag,
+/// e.g. `getList` tagged `admin.users` becomes `adminUsersGetList`. Names
+/// shared across tags are an error.
+pub(crate) fn flatten(
+    requests: &[impl Borrow<RequestInfo>],
+    names: &[String],
+) -> Result<Vec<String>, Box<dyn StdError>> {
+    let mut seen = HashSet::new();
+    let mut flat = Vec::new();
+
+    for (info, name) in requests.iter().map(Borrow::borrow).zip(names) {
+        let mut segments = info.tag.split('.');
+        let mut prefix = segments.next().unwrap_or_default().to_string();
+        prefix.extend(segments.filter(|s| !s.is_empty()).map(first_upper));
+
+        let name = match prefix.is_empty() {
+            true => name.clone(),
+            false => format!("{prefix}{}", first_upper(name)),
+        };
+        if !is_identifier(&name) {
+            return Err(format!(
+                "The export `{name}` of {} {} is not a valid identifier",
+                info.method, info.path
+            )
+            .into());
+        }
+        if !seen.insert(name.clone()) {
+            return Err(format!(
+                "The export `{name}` of {} {} is taken by another route",
+                info.method, info.path
+            )
+            .into());
+        }
+        flat.push(name);
+    }
+
+    Ok(flat)
+}
+
+fn operation_name(operation_id: &str) -> String {
+    let mut segments = operation_id
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|segment| !segment.is_empty());
+
+    let Some(first) = segments.next() else {
+        return operation_id.to_string();
+    };
+
+    let mut name = first[..1].to_lowercase() + &first[1..];
+    name.extend(segments.map(first_upper));
+    name
+}
+
+fn camel_case(path: &str, preserve_case: bool) -> String {
+    // `{id}` becomes `ById`
+    let path = path
+        .split('/')
+        .map(|segment| {
+            match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(placeholder) => format!("by_{placeholder}"),
+                None => segment.to_string(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("/");
+
+    path.split(&['-', '/', '_'][..])
+        .map(|segment| match preserve_case {
+            true => segment.to_string(),
+            false => segment.to_lowercase(),
+        })
+        .map(first_upper)
+        .collect::<Vec<String>>()
+        .join("")
+}
@@ -0,0 +1,202 @@
+use std::borrow::Cow;
+
+use schemars::schema::{InstanceType, Schema, SchemaObject, SingleOrVec};
+use serde_json::Value;
+
+use crate::{
+    gen::schemas,
+    types::{Kind, RequestInfo},
+};
+
+/// Normalizes the encodings of `Option<T>` reaching the zod translation, see
+/// [`normalize`].
+pub(crate) fn normalize_request(info: &mut RequestInfo) {
+    for kind in [
+        &mut info.path_params,
+        &mut info.req_params,
+        &mut info.req_headers,
+        &mut info.req_body,
+        &mut info.res_body,
+        &mut info.res_headers,
+    ] {
+        match kind {
+            Kind::None | Kind::Any | Kind::Text | Kind::Binary => {},
+            Kind::Schema(schema)
+            | Kind::TextSchema(schema)
+            | Kind::SSE(schema)
+            | Kind::Ndjson(schema) => normalize(&mut schema.schema),
+            Kind::SSEEvents(events) => events
+                .values_mut()
+                .for_each(|schema| normalize(&mut schema.schema)),
+            Kind::StatusUnion(bodies) => bodies
+                .iter_mut()
+                .for_each(|(_, schema)| normalize(&mut schema.schema)),
+            Kind::Websocket {
+                client_msg,
+                server_msg,
+            } => {
+                normalize(&mut client_msg.schema);
+                normalize(&mut server_msg.schema);
+            },
+        }
+    }
+
+    for (_, schema) in &mut info.error_schemas {
+        normalize(&mut schema.schema);
+    }
+}
+
+/// The request with normalized schemas, borrowed if they already are.
+pub(crate) fn normalized(info: &RequestInfo) -> Cow<'_, RequestInfo> {
+    if schemas(info)
+        .iter()
+        .all(|(_, root)| is_normalized(&root.schema))
+    {
+        return Cow::Borrowed(info);
+    }
+
+    let mut info = info.clone();
+    normalize_request(&mut info);
+    Cow::Owned(info)
+}
+
+/// schemars marks `Option<Enum>` as nullable by adding `null` to the type
+/// only, which the enum values then reject, so `null` is added to them too.
+/// `anyOf: [T, null]` with an already nullable `T` is collapsed into `T`.
+pub(crate) fn normalize(schema: &mut SchemaObject) {
+    if has_null_type(schema) {
+        if let Some(values) = &mut schema.enum_values {
+            if !values.contains(&Value::Null) {
+                values.push(Value::Null);
+            }
+        }
+    }
+
+    if let Some(object) = &mut schema.object {
+        for property in object.properties.values_mut() {
+            normalize_schema(property);
+        }
+        if let Some(additional) = &mut object.additional_properties {
+            normalize_schema(additional);
+        }
+    }
+
+    if let Some(items) = schema.array.as_mut().and_then(|a| a.items.as_mut()) {
+        match items {
+            SingleOrVec::Single(item) => normalize_schema(item),
+            SingleOrVec::Vec(items) => items.iter_mut().for_each(normalize_schema),
+        }
+    }
+
+    if let Some(subschemas) = &mut schema.subschemas {
+        let variants = [
+            &mut subschemas.all_of,
+            &mut subschemas.any_of,
+            &mut subschemas.one_of,
+        ];
+        for variant in variants.into_iter().flatten().flatten() {
+            normalize_schema(variant);
+        }
+    }
+
+    if let Some(inner) = redundant_null_variant(schema) {
+        let metadata = schema.metadata.take();
+        *schema = inner;
+        if metadata.is_some() {
+            schema.metadata = metadata;
+        }
+    }
+}
+
+/// Whether [`normalize`] would leave the schema unchanged.
+fn is_normalized(schema: &SchemaObject) -> bool {
+    if has_null_type(schema)
+        && schema
+            .enum_values
+            .as_ref()
+            .is_some_and(|values| !values.contains(&Value::Null))
+    {
+        return false;
+    }
+
+    let properties = schema.object.iter().flat_map(|object| {
+        object
+            .properties
+            .values()
+            .chain(object.additional_properties.as_deref())
+    });
+    let items = schema
+        .array
+        .iter()
+        .flat_map(|array| array.items.iter())
+        .flat_map(|items| match items {
+            SingleOrVec::Single(item) => std::slice::from_ref(&**item),
+            SingleOrVec::Vec(items) => items.as_slice(),
+        });
+    let variants = schema
+        .subschemas
+        .iter()
+        .flat_map(|s| [&s.all_of, &s.any_of, &s.one_of])
+        .flatten()
+        .flatten();
+
+    properties
+        .chain(items)
+        .chain(variants)
+        .all(|schema| match schema {
+            Schema::Object(schema) => is_normalized(schema),
+            Schema::Bool(_) => true,
+        })
+        && redundant_null_variant(schema).is_none()
+}
+
+/// Skips the `null` variant of `Option<T>`, encoded as `anyOf: [T, null]`.
+pub(crate) fn unwrap_nullable(schema: &SchemaObject) -> &SchemaObject {
+    let Some(variants) = schema
+        .subschemas
+        .as_ref()
+        .and_then(|s| s.any_of.as_ref().or(s.one_of.as_ref()))
+    else {
+        return schema;
+    };
+
+    let mut not_null = variants.iter().filter_map(|variant| match variant {
+        Schema::Object(variant) if !is_null(variant) => Some(variant),
+        _ => None,
+    });
+
+    match (not_null.next(), not_null.next()) {
+        (Some(variant), None) => unwrap_nullable(variant),
+        _ => schema,
+    }
+}
+
+fn normalize_schema(schema: &mut Schema) {
+    if let Schema::Object(schema) = schema {
+        normalize(schema);
+    }
+}
+
+fn redundant_null_variant(schema: &SchemaObject) -> Option<SchemaObject> {
+    let variants = schema.subschemas.as_ref()?.any_of.as_ref()?;
+
+    match variants.as_slice() {
+        [Schema::Object(inner), Schema::Object(null)] if is_null(null) =>
+            has_null_type(inner).then(|| inner.clone()),
+        _ => None,
+    }
+}
+
+fn has_null_type(schema: &SchemaObject) -> bool {
+    schema
+        .instance_type
+        .as_ref()
+        .is_some_and(|t| t.contains(&InstanceType::Null))
+}
+
+fn is_null(schema: &SchemaObject) -> bool {
+    matches!(
+        &schema.instance_type,
+        Some(SingleOrVec::Single(t)) if **t == InstanceType::Null
+    )
+}
@@ -0,0 +1,728 @@
+use std::{collections::BTreeMap, error::Error as StdError};
+
+use schemars::schema::{RootSchema, SchemaObject};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    deprecated::Deprecated,
+    encoding::BodyEncoding,
+    method::Method,
+    status,
+    types::{Kind, RequestInfo, Requests},
+};
+
+/// Describes the routes as an OpenAPI 3.1 document, the definitions of all
+/// schemas being collected under `components/schemas`. Websockets and server
+/// side events are described by the `x-websocket`, `x-sse` and `x-sse-events`
+/// extensions. `CONNECT` routes are left out, OpenAPI not supporting them.
+pub fn to_openapi(requests: &Requests) -> Value {
+    let mut paths = Map::new();
+    let mut components = Map::new();
+
+    for info in &requests.requests {
+        if info.method == Method::Connect {
+            continue;
+        }
+
+        let operation = operation(info, &mut components);
+        if let Value::Object(item) = paths
+            .entry(info.path.clone())
+            .or_insert_with(|| Value::Object(Map::new()))
+        {
+            item.insert(info.method.as_str().to_lowercase(), operation);
+        }
+    }
+
+    json!({
+        "openapi": "3.1.0",
+        "info": { "title": "API", "version": "0.0.0" },
+        "paths": paths,
+        "components": { "schemas": components },
+    })
+}
+
+/// Reads the routes of an OpenAPI 3 document, the inverse of [`to_openapi`].
+/// References are inlined, recursive schemas are not supported. Operations are
+/// tagged by their first tag and named by their `operationId`, if any.
+pub fn from_openapi(spec: &Value) -> Result<Requests, Box<dyn StdError>> {
+    let Some(paths) = spec.get("paths").and_then(Value::as_object) else {
+        return Err("The OpenAPI document has no paths".into());
+    };
+
+    let mut requests = Vec::new();
+    for (path, item) in paths {
+        let item = resolve(spec, item, &mut Vec::new())?;
+        let Value::Object(item) = item else {
+            return Err(format!("The path item of {path} is not an object").into());
+        };
+
+        let shared = item.get("parameters").cloned().unwrap_or(json!([]));
+        for (method, operation) in &item {
+            let Ok(method) = method.parse::<Method>() else {
+                continue;
+            };
+            requests.push(
+                import_operation(path, method, operation, &shared)
+                    .map_err(|e| format!("{method} {path}: {e}"))?,
+            );
+        }
+    }
+
+    Ok(Requests {
+        requests,
+        ..Default::default()
+    })
+}
+
+fn import_operation(
+    path: &str,
+    method: Method,
+    operation: &Value,
+    shared: &Value,
+) -> Result<RequestInfo, Box<dyn StdError>> {
+    let tag = operation
+        .pointer("/tags/0")
+        .and_then(Value::as_str)
+        .unwrap_or("api");
+    let mut info = RequestInfo::new("", method, "");
+    info.path = path.to_string();
+    info.tag = tag.to_string();
+    let text = |key: &str| {
+        operation
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+    info.operation_id = text("operationId");
+    info.summary = text("summary");
+    info.description = text("description");
+    if operation.get("deprecated") == Some(&Value::Bool(true)) {
+        info.deprecated = Deprecated::Simple(true);
+    }
+
+    // operation parameters override the ones of the path item by name and location
+    let mut parameters = BTreeMap::new();
+    for parameter in [shared, &operation["parameters"]]
+        .into_iter()
+        .filter_map(Value::as_array)
+        .flatten()
+    {
+        let location = parameter["in"].as_str().unwrap_or_default();
+        let name = parameter["name"].as_str().unwrap_or_default();
+        parameters.insert((location.to_string(), name.to_string()), parameter.clone());
+    }
+    info.path_params = import_parameters(&parameters, "path")?;
+    info.req_params = import_parameters(&parameters, "query")?;
+    info.req_headers = import_parameters(&parameters, "header")?;
+
+    if let Some(content) = operation.pointer("/requestBody/content") {
+        let (content_type, media) = preferred(content);
+        info.req_body = match content_type {
+            "application/json" => import_schema(&media["schema"])?,
+            "application/x-www-form-urlencoded" => {
+                info.body_encoding = BodyEncoding::UrlEncoded;
+                import_schema(&media["schema"])?
+            },
+            "multipart/form-data" => {
+                info.body_encoding = BodyEncoding::Multipart;
+                import_schema(&media["schema"])?
+            },
+            "text/plain" => Kind::Text,
+            _ => Kind::Any,
+        };
+    }
+
+    let responses = operation
+        .get("responses")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    for (key, response) in &responses {
+        let Some(codes) = status::parse(key) else {
+            continue;
+        };
+        let description = response["description"].as_str().unwrap_or_default();
+        for &code in codes.iter().filter(|code| !(200..300).contains(*code)) {
+            info.error_codes.push((code, description.to_string()));
+            // the body of a class like `5XX` is not parsed for each of its codes
+            if let (Some(schema), 1) = (
+                response.pointer("/content/application~1json/schema"),
+                codes.len(),
+            ) {
+                info.error_schemas.push((code, root(schema)?));
+            }
+        }
+    }
+
+    if let Some(websocket) = operation.get("x-websocket") {
+        info.res_body = Kind::Websocket {
+            client_msg: root(&websocket["client"])?,
+            server_msg: root(&websocket["server"])?,
+        };
+        return Ok(info);
+    }
+
+    let successes = responses
+        .iter()
+        .filter(|(status, _)| status.starts_with('2'))
+        .collect::<Vec<_>>();
+    let success = successes
+        .first()
+        .map(|(status, response)| (status.as_str(), *response));
+    if let Some(Value::Object(headers)) = success.and_then(|(_, r)| r.get("headers")) {
+        let headers = headers
+            .iter()
+            .map(|(name, header)| (("header".to_string(), name.clone()), header.clone()))
+            .collect();
+        info.res_headers = import_parameters(&headers, "header")?;
+    }
+    // several json responses become a union of their statuses
+    let bodies = successes
+        .iter()
+        .filter_map(|(status, response)| {
+            let schema = response.pointer("/content/application~1json/schema")?;
+            Some((status.parse::<u16>().ok()?, schema))
+        })
+        .collect::<Vec<_>>();
+    if bodies.len() > 1 {
+        info.res_body = Kind::StatusUnion(
+            bodies
+                .into_iter()
+                .map(|(code, schema)| Ok((code, root(schema)?)))
+                .collect::<Result<_, Box<dyn StdError>>>()?,
+        );
+        return Ok(info);
+    }
+
+    match success {
+        None => {},
+        Some(("204", _)) => info.no_content = true,
+        Some((_, response)) => {
+            let Some(content) = response.get("content") else {
+                return Ok(info);
+            };
+            let (content_type, media) = preferred(content);
+            info.res_body = match content_type {
+                "application/json" => import_schema(&media["schema"])?,
+                "text/event-stream" => match operation.get("x-sse-events") {
+                    Some(Value::Object(events)) => Kind::SSEEvents(
+                        events
+                            .iter()
+                            .map(|(event, schema)| Ok((event.clone(), root(schema)?)))
+                            .collect::<Result<_, Box<dyn StdError>>>()?,
+                    ),
+                    _ => Kind::SSE(root(operation.get("x-sse").unwrap_or(&json!({})))?),
+                },
+                "application/x-ndjson" => Kind::Ndjson(root(&media["schema"])?),
+                "text/plain" => Kind::Text,
+                "application/octet-stream" => Kind::Binary,
+                t if t.starts_with("image/") => Kind::Binary,
+                _ => Kind::Any,
+            };
+        },
+    }
+
+    Ok(info)
+}
+
+/// The JSON media type of the content if any, the first one otherwise.
+fn preferred(content: &Value) -> (&str, &Value) {
+    let Some(content) = content.as_object() else {
+        return ("", &Value::Null);
+    };
+
+    content
+        .get_key_value("application/json")
+        .or_else(|| content.iter().next())
+        .map(|(content_type, media)| (content_type.as_str(), media))
+        .unwrap_or(("", &Value::Null))
+}
+
+/// Collects the parameters of one location into an object schema.
+fn import_parameters(
+    parameters: &BTreeMap<(String, String), Value>,
+    location: &str,
+) -> Result<Kind, Box<dyn StdError>> {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for ((_, name), parameter) in parameters.iter().filter(|((l, _), _)| l == location) {
+        properties.insert(
+            name.clone(),
+            parameter.get("schema").cloned().unwrap_or(json!({})),
+        );
+        if location == "path" || parameter["required"] == true {
+            required.push(name.clone());
+        }
+    }
+
+    match properties.is_empty() {
+        true => Ok(Kind::None),
+        false => import_schema(&json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        })),
+    }
+}
+
+fn import_schema(schema: &Value) -> Result<Kind, Box<dyn StdError>> {
+    Ok(Kind::Schema(root(schema)?))
+}
+
+fn root(schema: &Value) -> Result<RootSchema, Box<dyn StdError>> {
+    Ok(RootSchema {
+        meta_schema: None,
+        schema: serde_json::from_value::<SchemaObject>(schema.clone())?,
+        definitions: Default::default(),
+    })
+}
+
+/// Inlines the `$ref`s of the value, pointing into the whole document.
+fn resolve(
+    spec: &Value,
+    value: &Value,
+    seen: &mut Vec<String>,
+) -> Result<Value, Box<dyn StdError>> {
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::String(reference)) = object.get("$ref") {
+                if seen.contains(reference) {
+                    return Err(format!("The reference {reference} is recursive").into());
+                }
+                let Some(target) = reference
+                    .strip_prefix('#')
+                    .and_then(|pointer| spec.pointer(pointer))
+                else {
+                    return Err(
+                        format!("The reference {reference} cannot be resolved").into()
+                    );
+                };
+
+                seen.push(reference.clone());
+                let resolved = resolve(spec, target, seen);
+                seen.pop();
+                return resolved;
+            }
+
+            object
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), resolve(spec, value, seen)?)))
+                .collect::<Result<Map<String, Value>, _>>()
+                .map(Value::Object)
+        },
+        Value::Array(items) => items
+            .iter()
+            .map(|item| resolve(spec, item, seen))
+            .collect::<Result<Vec<Value>, _>>()
+            .map(Value::Array),
+        _ => Ok(value.clone()),
+    }
+}
+
+fn operation(info: &RequestInfo, components: &mut Map<String, Value>) -> Value {
+    let mut operation = Map::new();
+    operation.insert("tags".to_string(), json!([info.tag]));
+    if let Some(operation_id) = &info.operation_id {
+        operation.insert("operationId".to_string(), json!(operation_id));
+    }
+    if let Some(summary) = &info.summary {
+        operation.insert("summary".to_string(), json!(summary));
+    }
+    if let Some(description) = &info.description {
+        operation.insert("description".to_string(), json!(description));
+    }
+
+    let parameters = [
+        (&info.path_params, "path"),
+        (&info.req_params, "query"),
+        (&info.req_headers, "header"),
+    ]
+    .into_iter()
+    .flat_map(|(kind, location)| parameters(kind, location, components))
+    .collect::<Vec<Value>>();
+    if !parameters.is_empty() {
+        operation.insert("parameters".to_string(), Value::Array(parameters));
+    }
+
+    let body = match &info.req_body {
+        Kind::Schema(root) => {
+            let content_type = match info.body_encoding {
+                BodyEncoding::Json => "application/json",
+                BodyEncoding::UrlEncoded => "application/x-www-form-urlencoded",
+                BodyEncoding::Multipart => "multipart/form-data",
+            };
+            Some(json!({ content_type: { "schema": schema(root, components) } }))
+        },
+        Kind::Text => Some(json!({ "text/plain": { "schema": { "type": "string" } } })),
+        Kind::Any => Some(json!({ "*/*": {} })),
+        _ => None,
+    };
+    if let Some(content) = body {
+        operation.insert(
+            "requestBody".to_string(),
+            json!({ "required": true, "content": content }),
+        );
+    }
+
+    let mut responses = Map::new();
+    let (status, response) = match &info.res_body {
+        Kind::None if info.no_content => (204, json!({ "description": "No Content" })),
+        Kind::None => (200, json!({ "description": "OK" })),
+        Kind::Any => (200, content(info, "*/*", json!({}))),
+        Kind::Text => (
+            200,
+            content(info, "text/plain", json!({ "type": "string" })),
+        ),
+        Kind::TextSchema(root) =>
+            (200, content(info, "text/plain", schema(root, components))),
+        Kind::Binary => (200, content(info, "application/octet-stream", json!({}))),
+        Kind::Schema(root) => (
+            200,
+            content(info, "application/json", schema(root, components)),
+        ),
+        Kind::Ndjson(root) => (
+            200,
+            content(info, "application/x-ndjson", schema(root, components)),
+        ),
+        Kind::SSE(root) => {
+            operation.insert("x-sse".to_string(), schema(root, components));
+            (200, content(info, "text/event-stream", json!({})))
+        },
+        Kind::SSEEvents(events) => {
+            let events = events
+                .iter()
+                .map(|(event, root)| (event.clone(), schema(root, components)))
+                .collect::<Map<String, Value>>();
+            operation.insert("x-sse-events".to_string(), Value::Object(events));
+            (200, content(info, "text/event-stream", json!({})))
+        },
+        Kind::Websocket {
+            client_msg,
+            server_msg,
+        } => {
+            operation.insert(
+                "x-websocket".to_string(),
+                json!({
+                    "client": schema(client_msg, components),
+                    "server": schema(server_msg, components),
+                }),
+            );
+            (101, json!({ "description": "Switching Protocols" }))
+        },
+        Kind::StatusUnion(bodies) => {
+            let mut bodies = bodies.iter().map(|(code, root)| {
+                let schema = schema(root, components);
+                (*code, content(info, "application/json", schema))
+            });
+            // the headers are declared on the first status
+            let first = bodies
+                .next()
+                .unwrap_or((200, json!({ "description": "OK" })));
+            for (code, response) in bodies {
+                responses.insert(code.to_string(), response);
+            }
+            first
+        },
+    };
+    let mut response = response;
+    let headers = self::parameters(&info.res_headers, "header", components)
+        .into_iter()
+        .map(|header| {
+            let name = header["name"].as_str().unwrap_or_default().to_string();
+            let header =
+                json!({ "required": header["required"], "schema": header["schema"] });
+            (name, header)
+        })
+        .collect::<Map<String, Value>>();
+    if !headers.is_empty() {
+        response["headers"] = Value::Object(headers);
+    }
+    responses.insert(status.to_string(), response);
+
+    // full classes are described by a range key, e.g. `5XX`
+    for (codes, description) in info.error_ranges() {
+        let label = status::label(&codes);
+        if label.ends_with("xx") {
+            responses.insert(label.to_uppercase(), json!({ "description": description }));
+        } else {
+            for code in codes {
+                responses.insert(code.to_string(), json!({ "description": description }));
+            }
+        }
+    }
+    for (code, root) in &info.error_schemas {
+        let schema = schema(root, components);
+        let response = responses
+            .entry(code.to_string())
+            .or_insert_with(|| json!({ "description": "Error" }));
+        response["content"] = json!({ "application/json": { "schema": schema } });
+    }
+    operation.insert("responses".to_string(), Value::Object(responses));
+
+    if info.deprecated.is() {
+        operation.insert("deprecated".to_string(), Value::Bool(true));
+    }
+
+    Value::Object(operation)
+}
+
+/// A successful response, listing the additional content types of the route
+/// without a schema.
+fn content(info: &RequestInfo, content_type: &str, schema: Value) -> Value {
+    let mut content = Map::new();
+    content.insert(content_type.to_string(), json!({ "schema": schema }));
+    for content_type in &info.res_content_types {
+        content.insert(content_type.clone(), json!({}));
+    }
+
+    json!({ "description": "OK", "content": content })
+}
+
+fn parameters(
+    kind: &Kind,
+    location: &str,
+    components: &mut Map<String, Value>,
+) -> Vec<Value> {
+    let Kind::Schema(root) = kind else {
+        return Vec::new();
+    };
+    definitions(root, components);
+
+    let Some(object) = &root.schema.object else {
+        return Vec::new();
+    };
+
+    object
+        .properties
+        .iter()
+        .map(|(name, property)| {
+            json!({
+                "name": name,
+                "in": location,
+                "required": location == "path" || object.required.contains(name),
+                "schema": value(property),
+            })
+        })
+        .collect()
+}
+
+fn schema(root: &RootSchema, components: &mut Map<String, Value>) -> Value {
+    definitions(root, components);
+    value(&root.schema)
+}
+
+fn definitions(root: &RootSchema, components: &mut Map<String, Value>) {
+    for (name, definition) in &root.definitions {
+        components.insert(name.clone(), value(definition));
+    }
+}
+
+/// Serializes a schema, pointing its references to definitions at the
+/// components.
+fn value<T: Serialize>(schema: &T) -> Value {
+    let mut value = serde_json::to_value(schema).expect("schemas serialize to json");
+    rewrite_refs(&mut value);
+    value
+}
+
+fn rewrite_refs(value: &mut Value) {
+    match value {
+        Value::Object(object) =>
+            for (key, value) in object.iter_mut() {
+                match (key.as_str(), value) {
+                    ("$ref", Value::String(reference)) => {
+                        if let Some(name) = reference.strip_prefix("#/definitions/") {
+                            *reference = format!("#/components/schemas/{name}");
+                        }
+                    },
+                    (_, value) => rewrite_refs(value),
+                }
+            },
+        Value::Array(items) => items.iter_mut().for_each(rewrite_refs),
+        _ => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use schemars::JsonSchema;
+    use serde_json::json;
+
+    use crate::{from_openapi, to_openapi, Kind, Method, RequestInfo, Requests};
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct User {
+        name: String,
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct UserPath {
+        id: u32,
+    }
+
+    #[test]
+    fn paths() {
+        let spec = to_openapi(&Requests {
+            requests: vec![
+                RequestInfo::new("/api/users/{id}", Method::Get, "users")
+                    .with_summary("Reads a user")
+                    .with_path_params::<UserPath>()
+                    .with_res_schema::<User>()
+                    .with_error(404, "Not found"),
+                RequestInfo::new("/api/users/{id}", Method::Delete, "users")
+                    .with_path_params::<UserPath>()
+                    .with_empty_res(),
+            ],
+            ..Default::default()
+        });
+
+        assert_eq!(spec["openapi"], "3.1.0");
+
+        let get = &spec["paths"]["/api/users/{id}"]["get"];
+        assert_eq!(get["tags"][0], "users");
+        assert_eq!(get["summary"], "Reads a user");
+        assert_eq!(get["parameters"][0]["in"], "path");
+        assert_eq!(get["parameters"][0]["required"], true);
+        assert_eq!(
+            get["responses"]["200"]["content"]["application/json"]["schema"]
+                ["properties"]["name"]["type"],
+            "string"
+        );
+        assert_eq!(get["responses"]["404"]["description"], "Not found");
+
+        let delete = &spec["paths"]["/api/users/{id}"]["delete"];
+        assert!(delete["responses"]["204"].is_object());
+    }
+
+    #[test]
+    fn import() {
+        let spec = json!({
+            "openapi": "3.1.0",
+            "paths": {
+                "/users/{id}": {
+                    "parameters": [
+                        { "name": "id", "in": "path", "schema": { "type": "integer" } }
+                    ],
+                    "get": {
+                        "operationId": "getUser",
+                        "tags": ["users"],
+                        "parameters": [
+                            { "name": "full", "in": "query", "schema": { "type": "boolean" } }
+                        ],
+                        "responses": {
+                            "200": {
+                                "description": "OK",
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/User" }
+                                    }
+                                }
+                            },
+                            "404": { "description": "Not found" },
+                            "default": { "description": "Error" }
+                        }
+                    },
+                    "delete": {
+                        "deprecated": true,
+                        "responses": { "204": { "description": "No Content" } }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "properties": { "name": { "type": "string" } },
+                        "required": ["name"]
+                    }
+                }
+            }
+        });
+
+        let requests = from_openapi(&spec).unwrap().requests;
+        assert_eq!(requests.len(), 2);
+
+        let delete = &requests[0];
+        assert_eq!(delete.method, Method::Delete);
+        assert_eq!(delete.tag, "api");
+        assert!(delete.no_content);
+        assert!(delete.deprecated.is());
+
+        let get = &requests[1];
+        assert_eq!(get.operation_id.as_deref(), Some("getUser"));
+        assert_eq!(get.tag, "users");
+        assert_eq!(get.error_codes, vec![(404, "Not found".to_string())]);
+
+        let Kind::Schema(path) = &get.path_params else {
+            panic!("path params are not imported");
+        };
+        assert!(path.schema.object.as_ref().unwrap().required.contains("id"));
+        let Kind::Schema(query) = &get.req_params else {
+            panic!("query params are not imported");
+        };
+        assert!(query.schema.object.as_ref().unwrap().required.is_empty());
+        let Kind::Schema(body) = &get.res_body else {
+            panic!("the response body is not imported");
+        };
+        assert!(body
+            .schema
+            .object
+            .as_ref()
+            .unwrap()
+            .properties
+            .contains_key("name"));
+    }
+
+    #[test]
+    fn import_recursive_reference() {
+        let spec = json!({
+            "paths": {
+                "/nodes": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "description": "OK",
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/Node" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Node": {
+                        "type": "object",
+                        "properties": { "next": { "$ref": "#/components/schemas/Node" } }
+                    }
+                }
+            }
+        });
+
+        assert!(from_openapi(&spec).is_err());
+    }
+
+    #[test]
+    fn error_classes() {
+        let spec = to_openapi(&Requests {
+            requests: vec![RequestInfo::new("/api/users", Method::Get, "users")
+                .with_error(404, "Not found")
+                .with_error(500..=599, "Server error")],
+            ..Default::default()
+        });
+
+        let responses = &spec["paths"]["/api/users"]["get"]["responses"];
+        assert_eq!(responses["5XX"]["description"], "Server error");
+        assert_eq!(responses["404"]["description"], "Not found");
+        assert!(responses.get("500").is_none());
+
+        let requests = from_openapi(&spec).unwrap().requests;
+        assert_eq!(requests[0].error_codes.len(), 101);
+    }
+}
@@ -0,0 +1,67 @@
+use crate::types::RequestInfo;
+
+/// The names of the `{name}` placeholders of a path, in order.
+pub(crate) fn placeholders(path: &str) -> Vec<&str> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+        .collect()
+}
+
+/// The path as the content of a template literal, its placeholders
+/// interpolated from `params`.
+fn interpolated(info: &RequestInfo, params: &str) -> String {
+    info.path
+        .split('/')
+        .map(|segment| {
+            match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(name) =>
+                    format!("${{encodeURIComponent(String({params}['{name}']))}}"),
+                None => segment.to_string(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("/")
+}
+
+/// The `{name}Path` function of a route with path params, building its path
+/// from the params parsed with `{name}PathParamsSchema` if `parse` is set.
+pub(crate) fn function(
+    info: &RequestInfo,
+    name: &str,
+    struct_name: &str,
+    parse: bool,
+) -> String {
+    match parse {
+        true => format!(
+            "    const {name}Path = (pathParams: {struct_name}PathParams) => {{
+        const parsed = options.unsafe ? pathParams : \
+             {name}PathParamsSchema.parse(pathParams);
+        return `{}`;
+    }};\n\n",
+            interpolated(info, "parsed")
+        ),
+        false => format!(
+            "    const {name}Path = (pathParams: {struct_name}PathParams) => `{}`;\n\n",
+            interpolated(info, "pathParams")
+        ),
+    }
+}
+
+/// The path as the content of a template literal. Paths of routes without
+/// path params are kept as they are, the others are built by their
+/// [`function`].
+pub(crate) fn template(info: &RequestInfo, name: &str) -> String {
+    match info.path_params.is_none() {
+        true => info.path.clone(),
+        false => format!("${{{name}Path(pathParams)}}"),
+    }
+}
+
+/// The path as a string expression, a call of its [`function`] for routes
+/// with path params.
+pub(crate) fn expr(info: &RequestInfo, name: &str) -> String {
+    match info.path_params.is_none() {
+        true => format!("'{}'", info.path),
+        false => format!("{name}Path(pathParams)"),
+    }
+}
@@ -0,0 +1,680 @@
+use std::{collections::BTreeMap, error::Error as StdError};
+
+use schemars::schema::{InstanceType, Schema, SchemaObject, SingleOrVec};
+use serde_json::Value;
+
+use crate::{
+    clients::{self, ident, pascal_case},
+    config::GenerateConfig,
+    deprecated::Deprecated,
+    error::{GenError, Slot},
+    gen::{finish, first_upper, included},
+    naming,
+    nullable::{normalize_request, unwrap_nullable},
+    stability::Stability,
+    status,
+    types::{Kind, RequestInfo, Requests},
+};
+
+const CLIENT: &str = r#"from typing import Any, Dict, List, Literal, NoReturn, Optional, Union
+from urllib.parse import quote
+
+import httpx
+from pydantic import BaseModel, ConfigDict, Field, TypeAdapter
+
+
+def _dump(ty: Any, value: Any) -> Any:
+    return TypeAdapter(ty).dump_python(value, mode="json", by_alias=True, exclude_none=True)
+
+
+def _load(ty: Any, value: Any) -> Any:
+    return TypeAdapter(ty).validate_python(value)
+
+
+def _strings(values: Optional[Dict[str, Any]]) -> Dict[str, Any]:
+    """Leaves out nones, lists are sent once per item."""
+
+    def string(value: Any) -> str:
+        if isinstance(value, bool):
+            return "true" if value else "false"
+        return str(value)
+
+    return {
+        name: [string(v) for v in value] if isinstance(value, list) else string(value)
+        for name, value in (values or {}).items()
+        if value is not None
+    }
+
+
+class BaseApiClient:
+    def __init__(
+        self,
+        base_url: str,
+        *,
+        base_urls: Optional[Dict[str, str]] = None,
+        headers: Optional[Dict[str, str]] = None,
+        client: Optional[httpx.Client] = None,
+    ) -> None:
+        self.base_url = base_url
+        # base urls of single tags, falling back to `base_url`
+        self.base_urls = base_urls or {}
+        # sent with every request
+        self.headers = headers or {}
+        self._client = client or httpx.Client()
+
+    def close(self) -> None:
+        self._client.close()
+
+    def send(
+        self,
+        tag: str,
+        method: str,
+        path: str,
+        *,
+        params: Optional[Dict[str, Any]] = None,
+        headers: Optional[Dict[str, Any]] = None,
+        json: Any = None,
+        content: Optional[str] = None,
+        timeout: Optional[float] = None,
+    ) -> httpx.Response:
+        """Raises `httpx.HTTPStatusError` for responses with a status outside of 2xx."""
+        base = self.base_urls.get(tag, self.base_url).rstrip("/")
+        extra: Dict[str, Any] = {} if timeout is None else {"timeout": timeout}
+        if content is not None:
+            headers = {"Content-Type": "text/plain", **(headers or {})}
+
+        res = self._client.request(
+            method,
+            base + path,
+            params=_strings(params),
+            headers={**self.headers, **_strings(headers)},
+            json=json,
+            content=content,
+            **extra,
+        )
+        res.raise_for_status()
+        return res
+"#;
+
+const KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class",
+    "continue", "def", "del", "elif", "else", "except", "finally", "for", "from",
+    "global", "if", "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass",
+    "raise", "return", "try", "while", "with", "yield",
+];
+
+pub fn generate_python(requests: Requests) -> Result<String, Box<dyn StdError>> {
+    generate_python_with(requests, &GenerateConfig::default())
+}
+
+/// Generates a Python client based on `httpx`, an `ApiClient` with an
+/// attribute per tag holding its methods, and a pydantic model per object
+/// schema, validating the responses. Of the config, the method names, the line
+/// endings and `exclude_internal` are used. Routes the client can not call yet
+/// raise a `NotImplementedError`, e.g. event sources.
+pub fn generate_python_with(
+    Requests {
+        mut requests,
+        tag_base_urls,
+    }: Requests,
+    config: &GenerateConfig,
+) -> Result<String, Box<dyn StdError>> {
+    requests.retain(|v| included(v, config));
+    requests.iter_mut().for_each(normalize_request);
+    if config.exclude_internal {
+        requests.retain(|v| v.stability != Stability::Internal);
+    }
+
+    let names = naming::resolve(&requests, config)?;
+
+    let mut models = Models::default();
+    let mut tags = BTreeMap::<&str, Vec<String>>::new();
+    for (v, name) in requests.iter().zip(&names) {
+        let method = method(v, name, &mut models)?;
+        tags.entry(&v.tag).or_default().push(method);
+    }
+
+    let mut out = CLIENT.to_string();
+
+    if !tag_base_urls.is_empty() {
+        out.push_str(
+            "\n\n# the base urls of the tags, e.g. for `ApiClient(base_urls=...)`\n",
+        );
+        out.push_str("TAG_BASE_URLS = {\n");
+        for (tag, url) in &tag_base_urls {
+            out.push_str(&format!("    {}: {},\n", string(tag), string(url)));
+        }
+        out.push_str("}\n");
+    }
+
+    for model in &models.models {
+        out.push_str("\n\n");
+        out.push_str(model);
+    }
+
+    for (tag, methods) in &tags {
+        out.push_str(&format!("\n\nclass {}Api:\n", pascal_case(tag)));
+        out.push_str("    def __init__(self, client: \"ApiClient\") -> None:\n");
+        out.push_str("        self._client = client\n");
+        for method in methods {
+            out.push('\n');
+            out.push_str(method);
+        }
+    }
+
+    out.push_str("\n\nclass ApiClient(BaseApiClient):\n");
+    out.push_str("    def __init__(self, base_url: str, **kwargs: Any) -> None:\n");
+    out.push_str("        super().__init__(base_url, **kwargs)\n");
+    for tag in tags.keys() {
+        out.push_str(&format!(
+            "        self.{} = {}Api(self)\n",
+            ident(&snake_case(tag), KEYWORDS),
+            pascal_case(tag)
+        ));
+    }
+
+    Ok(finish(out, config))
+}
+
+/// Generates the method of a route, adding the models of its schemas.
+fn method(
+    v: &RequestInfo,
+    name: &str,
+    models: &mut Models,
+) -> Result<String, Box<dyn StdError>> {
+    let struct_name = first_upper(name);
+    let name = ident(&snake_case(name), KEYWORDS);
+    if let Some(what) = clients::unimplemented(v) {
+        return Ok(stub(v, &name, what));
+    }
+
+    let mut args = vec!["self".to_string()];
+    let mut send = vec![string(&v.tag), string(v.method.as_str()), path(v)];
+    let mut body = String::new();
+
+    match &v.path_params {
+        Kind::None => {},
+        Kind::Any => {
+            args.push("path_params: Dict[str, str]".to_string());
+            body.push_str("        path = path_params\n");
+        },
+        Kind::Schema(schema) => {
+            let ty = models.root(&schema.schema, &format!("{struct_name}PathParams"));
+            args.push(format!("path_params: {ty}"));
+            body.push_str(&format!("        path = _dump({ty}, path_params)\n"));
+        },
+        kind => return Err(GenError::unexpected_kind(v, Slot::PathParams, kind).into()),
+    }
+
+    match &v.req_body {
+        Kind::None => {},
+        Kind::Any => {
+            args.push("req: Any".to_string());
+            send.push("json=req".to_string());
+        },
+        Kind::Text => {
+            args.push("req: str".to_string());
+            send.push("content=req".to_string());
+        },
+        Kind::Schema(schema) => {
+            let ty = models.root(&schema.schema, &format!("{struct_name}Req"));
+            args.push(format!("req: {ty}"));
+            send.push(format!("json=_dump({ty}, req)"));
+        },
+        kind => return Err(GenError::unexpected_kind(v, Slot::Body, kind).into()),
+    }
+
+    for (kind, slot, suffix, arg) in [
+        (&v.req_params, Slot::Params, "Params", "params"),
+        (&v.req_headers, Slot::Headers, "Headers", "headers"),
+    ] {
+        match kind {
+            Kind::None => {},
+            Kind::Any => {
+                args.push(format!("{arg}: Dict[str, str]"));
+                send.push(format!("{arg}={arg}"));
+            },
+            Kind::Schema(schema) => {
+                let ty = models.root(&schema.schema, &format!("{struct_name}{suffix}"));
+                args.push(format!("{arg}: {ty}"));
+                send.push(format!("{arg}=_dump({ty}, {arg})"));
+            },
+            kind => return Err(GenError::unexpected_kind(v, slot, kind).into()),
+        }
+    }
+
+    if let Some(timeout_ms) = v.timeout_ms {
+        send.push(format!("timeout={}", f64::from(timeout_ms) / 1000.0));
+    }
+
+    let (returns, result) = match &v.res_body {
+        Kind::None if v.no_content => ("None".to_string(), None),
+        Kind::None => ("httpx.Response".to_string(), Some("res".to_string())),
+        Kind::Any => ("Any".to_string(), Some("res.json()".to_string())),
+        Kind::Text | Kind::TextSchema(_) =>
+            ("str".to_string(), Some("res.text".to_string())),
+        Kind::Binary => ("bytes".to_string(), Some("res.content".to_string())),
+        Kind::Schema(schema) => {
+            let ty = models.root(&schema.schema, &format!("{struct_name}Res"));
+            let result = format!("_load({ty}, res.json())");
+            (ty, Some(result))
+        },
+        Kind::Websocket { .. }
+        | Kind::SSE(_)
+        | Kind::SSEEvents(_)
+        | Kind::StatusUnion(_)
+        | Kind::Ndjson(_) => unreachable!(),
+    };
+
+    let call = format!(
+        "self._client.send(\n            {},\n        )",
+        send.join(",\n            ")
+    );
+    match result {
+        Some(result) =>
+            body.push_str(&format!("        res = {call}\n        return {result}\n")),
+        None => body.push_str(&format!("        {call}\n")),
+    }
+
+    Ok(format!(
+        "    def {name}({}) -> {returns}:\n{}{body}",
+        args.join(", "),
+        doc(v)
+    ))
+}
+
+/// A method throwing instead of calling the route, see
+/// [`clients::unimplemented`].
+fn stub(v: &RequestInfo, name: &str, what: &str) -> String {
+    format!(
+        "    def {name}(self, *args: Any, **kwargs: Any) -> NoReturn:\n{}        raise \
+         NotImplementedError({})\n",
+        doc(v),
+        string(&format!(
+            "{what} are not supported by the python client yet"
+        ))
+    )
+}
+
+/// The route, its deprecation and its error responses as a docstring.
+fn doc(v: &RequestInfo) -> String {
+    let mut lines = vec![format!("`{} {}`", v.method.as_str(), v.path)];
+
+    match &v.deprecated {
+        Deprecated::Simple(false) => {},
+        Deprecated::Simple(true) => lines.push("\nDeprecated.".to_string()),
+        Deprecated::WithInfo(path, method, _) => lines.push(format!(
+            "\nDeprecated, use `{} {path}` instead.",
+            method.as_str()
+        )),
+        Deprecated::WithReason { .. } => lines.push(format!(
+            "\nDeprecated: {}.",
+            v.deprecated.reason().unwrap_or_default()
+        )),
+    }
+
+    if !v.error_codes.is_empty() {
+        lines.push("\nError responses:".to_string());
+        for (codes, info) in v.error_ranges() {
+            lines.push(format!("- {}: {info}", status::label(&codes)));
+        }
+    }
+
+    let doc = lines
+        .join("\n")
+        .replace('\\', "\\\\")
+        .replace("\"\"\"", "\\\"\\\"\\\"");
+    format!(
+        "        \"\"\"{}\n        \"\"\"\n",
+        doc.replace('\n', "\n        ").replace("        \n", "\n")
+    )
+}
+
+/// The path as a string literal, an f-string with its placeholders
+/// interpolated from the dumped path params.
+fn path(v: &RequestInfo) -> String {
+    if v.path_params.is_none() {
+        return string(&v.path);
+    }
+
+    let path = v
+        .path
+        .split('/')
+        .map(|segment| {
+            match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                // the quotes of the f-string can not be reused before python 3.12
+                Some(name) => format!("{{quote(str(path['{name}']), safe='')}}"),
+                None => escape(segment).replace('{', "{{").replace('}', "}}"),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("/");
+
+    format!("f\"{path}\"")
+}
+
+/// Collects the models of object schemas, named after the route slot and the
+/// path of properties leading to them.
+#[derive(Default)]
+struct Models {
+    models: Vec<String>,
+    names: Vec<String>,
+}
+
+impl Models {
+    fn root(&mut self, schema: &SchemaObject, name: &str) -> String {
+        self.object_type(schema, name)
+    }
+
+    fn schema_type(&mut self, schema: &Schema, name: &str) -> String {
+        match schema {
+            Schema::Bool(_) => "Any".to_string(),
+            Schema::Object(schema) => self.object_type(schema, name),
+        }
+    }
+
+    fn object_type(&mut self, schema: &SchemaObject, name: &str) -> String {
+        let inner = unwrap_nullable(schema);
+        if !std::ptr::eq(inner, schema) {
+            return optional(self.object_type(inner, name));
+        }
+
+        if let Some(values) = &schema.enum_values {
+            return literal(values);
+        }
+        if let Some(value) = &schema.const_value {
+            return literal(std::slice::from_ref(value));
+        }
+
+        if let Some(subschemas) = &schema.subschemas {
+            if let Some(variants) =
+                subschemas.any_of.as_ref().or(subschemas.one_of.as_ref())
+            {
+                let variants = variants
+                    .iter()
+                    .enumerate()
+                    .map(|(i, variant)| {
+                        self.schema_type(variant, &format!("{name}{}", i + 1))
+                    })
+                    .collect::<Vec<String>>();
+                return format!("Union[{}]", variants.join(", "));
+            }
+            return match subschemas.all_of.as_deref() {
+                Some([single]) => self.schema_type(single, name),
+                _ => "Any".to_string(),
+            };
+        }
+
+        match &schema.instance_type {
+            Some(SingleOrVec::Single(t)) => self.instance_type(schema, **t, name),
+            Some(SingleOrVec::Vec(types)) => {
+                let not_null = types
+                    .iter()
+                    .filter(|t| **t != InstanceType::Null)
+                    .map(|t| self.instance_type(schema, *t, name))
+                    .collect::<Vec<String>>();
+                let ty = match not_null.as_slice() {
+                    [ty] => ty.clone(),
+                    _ => format!("Union[{}]", not_null.join(", ")),
+                };
+                match types.contains(&InstanceType::Null) {
+                    true => optional(ty),
+                    false => ty,
+                }
+            },
+            None if schema.object.is_some() =>
+                self.instance_type(schema, InstanceType::Object, name),
+            None => "Any".to_string(),
+        }
+    }
+
+    fn instance_type(
+        &mut self,
+        schema: &SchemaObject,
+        instance_type: InstanceType,
+        name: &str,
+    ) -> String {
+        match instance_type {
+            InstanceType::Null => "None".to_string(),
+            InstanceType::Boolean => "bool".to_string(),
+            InstanceType::Integer => "int".to_string(),
+            InstanceType::Number => "float".to_string(),
+            InstanceType::String => "str".to_string(),
+            InstanceType::Array =>
+                match schema.array.as_ref().and_then(|a| a.items.as_ref()) {
+                    Some(SingleOrVec::Single(item)) => format!(
+                        "List[{}]",
+                        self.schema_type(item, &format!("{name}Item"))
+                    ),
+                    _ => "List[Any]".to_string(),
+                },
+            InstanceType::Object => match &schema.object {
+                Some(object) if !object.properties.is_empty() => self.model(schema, name),
+                Some(object) => match &object.additional_properties {
+                    Some(value) => format!(
+                        "Dict[str, {}]",
+                        self.schema_type(value, &format!("{name}Value"))
+                    ),
+                    None => "Dict[str, Any]".to_string(),
+                },
+                None => "Dict[str, Any]".to_string(),
+            },
+        }
+    }
+
+    fn model(&mut self, schema: &SchemaObject, name: &str) -> String {
+        let mut model_name = name.to_string();
+        let mut n = 2;
+        while self.names.contains(&model_name) {
+            model_name = format!("{name}{n}");
+            n += 1;
+        }
+        self.names.push(model_name.clone());
+
+        let object = schema.object.as_ref().expect("models are objects");
+        let mut aliased = false;
+        let mut fields = Vec::new();
+        for (key, property) in &object.properties {
+            let field = ident(&snake_case(key), KEYWORDS);
+            let ty =
+                self.schema_type(property, &format!("{model_name}{}", pascal_case(key)));
+            let required = object.required.contains(key);
+
+            let mut options = Vec::new();
+            if !required {
+                options.push("None".to_string());
+            }
+            if &field != key {
+                aliased = true;
+                options.push(format!("alias={}", string(key)));
+            }
+            if let Some(description) = description(property) {
+                options.push(format!("description={}", string(description)));
+            }
+
+            fields.push(match (required, options.as_slice()) {
+                (true, []) => format!("    {field}: {ty}\n"),
+                (false, [_]) => format!("    {field}: {} = None\n", optional(ty)),
+                (true, _) =>
+                    format!("    {field}: {ty} = Field({})\n", options.join(", ")),
+                (false, _) => format!(
+                    "    {field}: {} = Field({})\n",
+                    optional(ty),
+                    options.join(", ")
+                ),
+            });
+        }
+
+        let mut model = format!("class {model_name}(BaseModel):\n");
+        if aliased {
+            model.push_str("    model_config = ConfigDict(populate_by_name=True)\n\n");
+        }
+        model.push_str(&fields.concat());
+
+        self.models.push(model);
+        model_name
+    }
+}
+
+fn optional(ty: String) -> String {
+    match ty.starts_with("Optional[") || ty == "Any" || ty == "None" {
+        true => ty,
+        false => format!("Optional[{ty}]"),
+    }
+}
+
+fn literal(values: &[Value]) -> String {
+    let literals = values
+        .iter()
+        .map(|value| match value {
+            Value::Null => Some("None".to_string()),
+            Value::Bool(true) => Some("True".to_string()),
+            Value::Bool(false) => Some("False".to_string()),
+            Value::Number(n) => Some(n.to_string()),
+            Value::String(s) => Some(string(s)),
+            _ => None,
+        })
+        .collect::<Option<Vec<String>>>();
+
+    match literals {
+        Some(literals) => format!("Literal[{}]", literals.join(", ")),
+        None => "Any".to_string(),
+    }
+}
+
+fn description(schema: &Schema) -> Option<&str> {
+    let Schema::Object(schema) = schema else {
+        return None;
+    };
+
+    schema.metadata.as_ref()?.description.as_deref()
+}
+
+/// A Python string literal.
+fn string(s: &str) -> String { format!("\"{}\"", escape(s)) }
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Splits words at separators and before upper case letters following lower
+/// case ones, e.g. `getById` becomes `get_by_id`.
+fn snake_case(s: &str) -> String {
+    let mut out = String::new();
+    let mut previous: Option<char> = None;
+    for c in s.chars() {
+        if !c.is_ascii_alphanumeric() {
+            previous = None;
+            continue;
+        }
+        let boundary = match previous {
+            None => !out.is_empty(),
+            Some(p) =>
+                c.is_ascii_uppercase() && (p.is_ascii_lowercase() || p.is_ascii_digit()),
+        };
+        if boundary {
+            out.push('_');
+        }
+        out.push(c.to_ascii_lowercase());
+        previous = Some(c);
+    }
+
+    match out.chars().next() {
+        Some(first) if first.is_ascii_digit() => format!("v_{out}"),
+        Some(_) => out,
+        None => "value".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use schemars::JsonSchema;
+
+    use crate::{generate_python, GenError, Method, RequestInfo, Requests, Slot};
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct UserPath {
+        id: u32,
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    #[serde(rename_all = "camelCase")]
+    struct Address {
+        street_name: String,
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct User {
+        name: String,
+        age: Option<u8>,
+        addresses: Vec<Address>,
+    }
+
+    fn gen(requests: Vec<RequestInfo>) -> String {
+        generate_python(Requests {
+            requests,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn models() {
+        let out = gen(vec![RequestInfo::new(
+            "/api/users/{id}",
+            Method::Put,
+            "users",
+        )
+        .with_path_params::<UserPath>()
+        .with_req_body::<User>()
+        .with_res_schema::<User>()]);
+
+        assert!(out.contains("        self.users = UsersApi(self)\n"));
+        assert!(out.contains(
+            "    def put_by_id(self, path_params: PutByIdPathParams, req: PutByIdReq) \
+             -> PutByIdRes:\n"
+        ));
+        assert!(out.contains("f\"/api/users/{quote(str(path['id']), safe='')}\""));
+        assert!(out.contains("json=_dump(PutByIdReq, req)"));
+        assert!(out.contains("return _load(PutByIdRes, res.json())"));
+
+        assert!(out.contains("class PutByIdReqAddressesItem(BaseModel):\n"));
+        assert!(out.contains("    street_name: str = Field(alias=\"streetName\")\n"));
+        assert!(out.contains("    age: Optional[int] = None\n"));
+        assert!(out.contains("    addresses: List[PutByIdReqAddressesItem]\n"));
+    }
+
+    #[test]
+    fn streams_are_unimplemented() {
+        let out = gen(vec![
+            RequestInfo::new("/api/events", Method::Get, "events").with_sse::<User>()
+        ]);
+
+        assert!(out.contains(
+            "    def get_events(self, *args: Any, **kwargs: Any) -> NoReturn:"
+        ));
+    }
+
+    #[test]
+    fn unexpected_kind_error() {
+        let mut info = RequestInfo::new("/api/events", Method::Get, "events");
+        info.req_headers = RequestInfo::new("/api/events", Method::Get, "events")
+            .with_sse::<User>()
+            .res_body;
+
+        let err = generate_python(Requests {
+            requests: vec![info],
+            ..Default::default()
+        })
+        .unwrap_err();
+        let err = err.downcast_ref::<GenError>().unwrap();
+
+        assert_eq!(err.path, "/api/events");
+        assert_eq!(err.slot, Slot::Headers);
+    }
+}
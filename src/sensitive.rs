@@ -0,0 +1,62 @@
+#[cfg(feature = "client-gen")]
+use schemars::schema::SchemaObject;
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
+use serde_json::Value;
+
+#[cfg(feature = "client-gen")]
+use crate::walk::{walk, Step};
+
+/// The schema extension keyword marking a field as sensitive, e.g. a password
+/// or token. Generated clients list these fields per route in
+/// `...SensitiveFields` so logging and telemetry can redact them.
+pub const SENSITIVE_KEYWORD: &str = "x-sensitive";
+
+/// Generates the schema of `T` marked as sensitive, to be used as
+/// `#[schemars(schema_with = "schemars_client_gen::sensitive::<String>")]`.
+pub fn sensitive<T: JsonSchema>(gen: &mut SchemaGenerator) -> Schema {
+    let mut schema = gen.subschema_for::<T>().into_object();
+    schema
+        .extensions
+        .insert(SENSITIVE_KEYWORD.to_string(), Value::Bool(true));
+    Schema::Object(schema)
+}
+
+/// Lists the paths of all fields marked as sensitive, nested fields joined by
+/// `.` and array items as `[]`.
+#[cfg(feature = "client-gen")]
+pub(crate) fn sensitive_fields(schema: &SchemaObject) -> Vec<String> {
+    if is_sensitive(schema) {
+        return vec![String::new()];
+    }
+
+    let mut out = Vec::new();
+    walk(schema, &mut |path, schema| {
+        // fields of maps and tuples are not listed
+        if path
+            .iter()
+            .any(|step| matches!(step, Step::AdditionalProperties | Step::TupleItem(_)))
+        {
+            return false;
+        }
+        let Schema::Object(schema) = schema else {
+            return false;
+        };
+        if !is_sensitive(schema) {
+            return true;
+        }
+
+        out.push(path.iter().fold(String::new(), |field, step| match step {
+            Step::Property(name) if field.is_empty() => name.to_string(),
+            Step::Property(name) => format!("{field}.{name}"),
+            Step::Items => format!("{field}[]"),
+            _ => field,
+        }));
+        false
+    });
+    out
+}
+
+#[cfg(feature = "client-gen")]
+fn is_sensitive(schema: &SchemaObject) -> bool {
+    schema.extensions.get(SENSITIVE_KEYWORD) == Some(&Value::Bool(true))
+}
@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+
+/// Hoists schemas defined identically by several routes into shared constants,
+/// e.g. `const sharedSchema0 = z.object(...)`, which the constants of the
+/// routes then reference. Returns the shared constants.
+pub(crate) fn share(codes: &mut [&mut String]) -> String {
+    let mut counts = HashMap::<String, usize>::new();
+    let mut order = Vec::new();
+    for code in codes.iter() {
+        for (_, zod) in code.lines().filter_map(definition) {
+            let count = counts.entry(zod.to_string()).or_default();
+            if *count == 0 {
+                order.push(zod.to_string());
+            }
+            *count += 1;
+        }
+    }
+
+    let mut out = String::new();
+    let mut names = HashMap::new();
+    for zod in order.into_iter().filter(|zod| counts[zod] > 1) {
+        let name = format!("sharedSchema{}", names.len());
+        out.push_str(&format!("const {name} = {zod};\n"));
+        names.insert(zod, name);
+    }
+
+    if names.is_empty() {
+        return out;
+    }
+
+    for code in codes.iter_mut() {
+        **code = code
+            .split_inclusive('\n')
+            .map(|line| {
+                let shared = definition(line.trim_end_matches('\n'))
+                    .and_then(|(ident, zod)| Some((ident, names.get(zod)?)));
+                match shared {
+                    Some((ident, name)) => format!("    const {ident} = {name};\n"),
+                    None => line.to_string(),
+                }
+            })
+            .collect();
+    }
+
+    out.push('\n');
+    out
+}
+
+/// The name and zod of a single line schema constant of a route.
+fn definition(line: &str) -> Option<(&str, &str)> {
+    let (ident, zod) = line
+        .strip_prefix("    const ")?
+        .strip_suffix(';')?
+        .split_once(" = ")?;
+
+    ident.ends_with("Schema").then_some((ident, zod))
+}
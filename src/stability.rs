@@ -0,0 +1,26 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// How stable the API of a route is, rendered as a JSDoc tag.
+#[derive(
+    Debug, Copy, Clone, Default, Eq, PartialEq, JsonSchema, Serialize, Deserialize,
+)]
+pub enum Stability {
+    #[default]
+    Stable,
+    Beta,
+    Internal,
+    Experimental,
+}
+
+impl Stability {
+    /// The JSDoc tag of the stability, `None` for stable routes.
+    pub fn tag(&self) -> Option<&'static str> {
+        match self {
+            Stability::Stable => None,
+            Stability::Beta => Some("@beta"),
+            Stability::Internal => Some("@internal"),
+            Stability::Experimental => Some("@experimental"),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::ops::RangeInclusive;
+
+/// Well-known status codes by their reason phrase.
+const NAMES: &[(&str, u16)] = &[
+    ("bad request", 400),
+    ("unauthorized", 401),
+    ("payment required", 402),
+    ("forbidden", 403),
+    ("not found", 404),
+    ("method not allowed", 405),
+    ("not acceptable", 406),
+    ("request timeout", 408),
+    ("conflict", 409),
+    ("gone", 410),
+    ("precondition failed", 412),
+    ("payload too large", 413),
+    ("unsupported media type", 415),
+    ("unprocessable entity", 422),
+    ("too many requests", 429),
+    ("internal server error", 500),
+    ("not implemented", 501),
+    ("bad gateway", 502),
+    ("service unavailable", 503),
+    ("gateway timeout", 504),
+];
+
+/// The status codes of an error response, a single code, an inclusive range
+/// or a string like `404`, `5xx` or `not found`.
+pub trait StatusCodes {
+    fn codes(&self) -> Vec<u16>;
+}
+
+impl StatusCodes for u16 {
+    fn codes(&self) -> Vec<u16> { vec![*self] }
+}
+
+impl StatusCodes for RangeInclusive<u16> {
+    fn codes(&self) -> Vec<u16> { self.clone().collect() }
+}
+
+impl StatusCodes for &str {
+    fn codes(&self) -> Vec<u16> {
+        parse(self).unwrap_or_else(|| panic!("Unknown status code {self}"))
+    }
+}
+
+/// Parses a code, a class like `5xx` or a reason phrase in any case and with
+/// any separators, e.g. `NOT_FOUND`.
+pub(crate) fn parse(s: &str) -> Option<Vec<u16>> {
+    if let Ok(code) = s.parse::<u16>() {
+        return Some(vec![code]);
+    }
+
+    if let Some(class) = s
+        .strip_suffix("xx")
+        .or_else(|| s.strip_suffix("XX"))
+        .and_then(|class| class.parse::<u16>().ok())
+        .filter(|class| (1..=5).contains(class))
+    {
+        return Some((class * 100..=class * 100 + 99).collect());
+    }
+
+    let name = s
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    NAMES
+        .iter()
+        .find(|(phrase, _)| phrase.replace(' ', "") == name)
+        .map(|(_, code)| vec![*code])
+}
+
+/// The label of consecutive codes, e.g. `404`, `5xx` or `502-504`.
+pub(crate) fn label(codes: &RangeInclusive<u16>) -> String {
+    let (start, end) = (*codes.start(), *codes.end());
+    if start == end {
+        start.to_string()
+    } else if start % 100 == 0 && end == start + 99 {
+        format!("{}xx", start / 100)
+    } else {
+        format!("{start}-{end}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{label, parse, StatusCodes};
+
+    #[test]
+    fn codes() {
+        assert_eq!(404.codes(), vec![404]);
+        assert_eq!((502..=504).codes(), vec![502, 503, 504]);
+        assert_eq!("5xx".codes().len(), 100);
+        assert_eq!("4XX".codes()[0], 400);
+        assert_eq!("Not Found".codes(), vec![404]);
+        assert_eq!("TOO_MANY_REQUESTS".codes(), vec![429]);
+        assert_eq!(parse("6xx"), None);
+        assert_eq!(parse("teapot"), None);
+    }
+
+    #[test]
+    fn labels() {
+        assert_eq!(label(&(404..=404)), "404");
+        assert_eq!(label(&(500..=599)), "5xx");
+        assert_eq!(label(&(502..=504)), "502-504");
+    }
+}
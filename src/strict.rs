@@ -0,0 +1,79 @@
+use schemars::schema::{Schema, SchemaObject};
+
+use crate::walk::{walk, Step};
+
+/// The schema information the zod translation keeps, everything else is
+/// dropped.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Kept {
+    pub descriptions: bool,
+    pub defaults: bool,
+    pub bounds: bool,
+}
+
+/// Lists every piece of schema information lost in the zod translation,
+/// prefixed by the field it belongs to.
+pub(crate) fn dropped(schema: &SchemaObject, kept: Kept, field: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    check(schema, kept, field, &mut out);
+    walk(schema, &mut |path, schema| {
+        if let Schema::Object(schema) = schema {
+            let field = path
+                .iter()
+                .fold(field.to_string(), |field, step| match step {
+                    Step::Property(name) => format!("{field}.{name}"),
+                    Step::AdditionalProperties => format!("{field}[key]"),
+                    Step::Items => format!("{field}[]"),
+                    Step::TupleItem(i) => format!("{field}[{i}]"),
+                    Step::Variant(_) => field,
+                });
+            check(schema, kept, &field, &mut out);
+        }
+        true
+    });
+    out
+}
+
+fn check(schema: &SchemaObject, kept: Kept, field: &str, out: &mut Vec<String>) {
+    if let Some(metadata) = &schema.metadata {
+        if !kept.descriptions && metadata.description.is_some() {
+            out.push(format!("{field}: description"));
+        }
+        if !kept.defaults && metadata.default.is_some() {
+            out.push(format!("{field}: default"));
+        }
+        if !metadata.examples.is_empty() {
+            out.push(format!("{field}: examples"));
+        }
+    }
+
+    if !kept.bounds {
+        if let Some(number) = &schema.number {
+            if number.minimum.is_some() || number.exclusive_minimum.is_some() {
+                out.push(format!("{field}: minimum"));
+            }
+            if number.maximum.is_some() || number.exclusive_maximum.is_some() {
+                out.push(format!("{field}: maximum"));
+            }
+            if number.multiple_of.is_some() {
+                out.push(format!("{field}: multipleOf"));
+            }
+        }
+        if let Some(string) = &schema.string {
+            if string.min_length.is_some() {
+                out.push(format!("{field}: minLength"));
+            }
+            if string.max_length.is_some() {
+                out.push(format!("{field}: maxLength"));
+            }
+        }
+        if let Some(array) = &schema.array {
+            if array.min_items.is_some() {
+                out.push(format!("{field}: minItems"));
+            }
+            if array.max_items.is_some() {
+                out.push(format!("{field}: maxItems"));
+            }
+        }
+    }
+}
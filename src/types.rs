@@ -1,42 +1,82 @@
 use std::{
-    fmt::{Display, Formatter},
+    collections::{BTreeMap, HashSet},
+    fmt::{Debug, Display, Formatter},
     mem,
+    ops::RangeInclusive,
+    sync::Arc,
 };
 
 use schemars::{
     gen::{SchemaGenerator, SchemaSettings},
-    schema::RootSchema,
+    schema::{InstanceType, RootSchema},
     JsonSchema,
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{deprecated::Deprecated, method::Method};
+use crate::{
+    deprecated::Deprecated,
+    encoding::{BodyEncoding, QueryStyle},
+    method::Method,
+    stability::Stability,
+    status::StatusCodes,
+};
 
 #[derive(Debug, Clone, Default, JsonSchema, Serialize, Deserialize)]
 pub enum Kind {
     #[default]
     None,
     Any,
+    /// A plain `text/plain` string, neither serialized nor parsed as JSON.
+    Text,
+    /// A `text/plain` string validated with a string schema, e.g. with a
+    /// pattern.
+    TextSchema(RootSchema),
+    /// Binary data, e.g. file downloads, read as a `Blob`.
+    Binary,
     Schema(RootSchema),
     Websocket {
         client_msg: RootSchema,
         server_msg: RootSchema,
     },
     SSE(RootSchema),
+    /// Server side events dispatched by their event name, each with its own
+    /// schema.
+    SSEEvents(BTreeMap<String, RootSchema>),
+    /// Bodies varying by the status of the response, each parsed with its own
+    /// schema.
+    StatusUnion(Vec<(u16, RootSchema)>),
+    /// Newline delimited JSON, streamed and parsed line by line.
+    Ndjson(RootSchema),
 }
 
 impl Kind {
     pub fn is_none(&self) -> bool { matches!(self, Kind::None) }
 
     pub fn is_some(&self) -> bool {
-        matches!(self, Kind::Any | Kind::Schema(_) | Kind::Websocket { .. })
+        matches!(
+            self,
+            Kind::Any
+                | Kind::Text
+                | Kind::TextSchema(_)
+                | Kind::Binary
+                | Kind::Schema(_)
+                | Kind::Websocket { .. }
+                | Kind::StatusUnion(_)
+                | Kind::Ndjson(_)
+        )
     }
 
     pub fn is_schema(&self) -> bool { matches!(self, Kind::Schema(_)) }
 
+    pub fn is_text(&self) -> bool { matches!(self, Kind::Text) }
+
+    pub fn is_binary(&self) -> bool { matches!(self, Kind::Binary) }
+
     pub fn is_websocket(&self) -> bool { matches!(self, Kind::Websocket { .. }) }
 
-    pub fn is_sse(&self) -> bool { matches!(self, Kind::SSE(_)) }
+    pub fn is_sse(&self) -> bool { matches!(self, Kind::SSE(_) | Kind::SSEEvents(_)) }
+
+    pub fn is_ndjson(&self) -> bool { matches!(self, Kind::Ndjson(_)) }
 
     fn replace(&mut self, new: Kind) -> Kind { mem::replace(self, new) }
 
@@ -48,9 +88,13 @@ impl Display for Kind {
         write!(f, "{}", match self {
             Kind::None => "none",
             Kind::Any => "any",
+            Kind::Text | Kind::TextSchema(_) => "text",
+            Kind::Binary => "binary",
             Kind::Schema(_) => "defined",
             Kind::Websocket { .. } => "websocket",
-            Kind::SSE(_) => "server side events",
+            Kind::SSE(_) | Kind::SSEEvents(_) => "server side events",
+            Kind::StatusUnion(_) => "status union",
+            Kind::Ndjson(_) => "ndjson",
         })
     }
 }
@@ -63,17 +107,171 @@ impl Tag for &'static str {
     fn tag_name(&self) -> &'static str { self }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Describes how a cursor paginated list endpoint advances from one page to the
+/// next.
+#[derive(Debug, Clone, JsonSchema, Serialize, Deserialize)]
+pub struct PageConfig {
+    /// The query parameter the cursor is sent in.
+    pub cursor_param: String,
+    /// The response field holding the cursor of the next page, missing or
+    /// `null` on the last page.
+    pub next_cursor_field: String,
+    /// The response field holding the items of the current page.
+    pub items_field: String,
+}
+
+impl PageConfig {
+    pub fn new(cursor_param: &str, next_cursor_field: &str, items_field: &str) -> Self {
+        PageConfig {
+            cursor_param: cursor_param.to_string(),
+            next_cursor_field: next_cursor_field.to_string(),
+            items_field: items_field.to_string(),
+        }
+    }
+}
+
+/// How an event source or websocket reconnects after a transport error, the
+/// delay doubling from `base_delay_ms` up to `max_delay_ms` with every attempt.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, JsonSchema, Serialize, Deserialize)]
+pub struct ReconnectPolicy {
+    pub max_retries: u32,
+    pub base_delay_ms: u32,
+    pub max_delay_ms: u32,
+}
+
+impl ReconnectPolicy {
+    pub fn new(max_retries: u32, base_delay_ms: u32, max_delay_ms: u32) -> Self {
+        ReconnectPolicy {
+            max_retries,
+            base_delay_ms,
+            max_delay_ms,
+        }
+    }
+}
+
+/// The descriptions of the schemas of a route, taken from the doc comments of
+/// the types before their metadata is cleared.
+#[derive(Debug, Clone, Default, Eq, PartialEq, JsonSchema, Serialize, Deserialize)]
+pub struct SchemaDocs {
+    pub path_params: Option<String>,
+    pub req_params: Option<String>,
+    pub req_headers: Option<String>,
+    pub req_body: Option<String>,
+    pub res_body: Option<String>,
+}
+
+#[derive(Debug, Clone, JsonSchema, Serialize, Deserialize)]
 pub struct RequestInfo {
     pub path: String,
     pub method: Method,
     pub tag: String,
+    /// The values of the `{name}` placeholders of the path.
+    #[serde(default)]
+    pub path_params: Kind,
     pub req_body: Kind,
     pub req_params: Kind,
+    /// Headers sent with every request, flat strings, numbers or booleans.
+    #[serde(default)]
+    pub req_headers: Kind,
     pub res_body: Kind,
+    /// Headers read from successful responses, flat like the request headers.
+    #[serde(default)]
+    pub res_headers: Kind,
     pub deprecated: Deprecated,
     #[serde(default)]
     pub error_codes: Vec<(u16, String)>,
+    /// The bodies of error responses, parsed into the rejection of the
+    /// generated function.
+    #[serde(default)]
+    pub error_schemas: Vec<(u16, RootSchema)>,
+    #[serde(default)]
+    pub pagination: Option<PageConfig>,
+    /// Content types the response can have besides the one of `res_body`, in
+    /// order of preference.
+    #[serde(default)]
+    pub res_content_types: Vec<String>,
+    /// Overrides the `Accept` header derived from the response content types.
+    #[serde(default)]
+    pub accept: Option<String>,
+    /// Whether an `exists...` helper resolving to a boolean is generated
+    /// besides the regular function.
+    #[serde(default)]
+    pub existence_check: bool,
+    /// Whether a `longPoll...` async generator repeatedly calling the route is
+    /// generated besides the regular function.
+    #[serde(default)]
+    pub long_poll: bool,
+    /// Whether the route responds without a body, resolving to `void` instead
+    /// of the raw `Response`.
+    #[serde(default)]
+    pub no_content: bool,
+    /// Milliseconds after which the request is aborted with a `TimeoutError`,
+    /// overridable per call.
+    #[serde(default)]
+    pub timeout_ms: Option<u32>,
+    /// How often failed requests are retried with exponential backoff,
+    /// overridable per call. Network errors and 5xx responses fail.
+    #[serde(default)]
+    pub retries: Option<u8>,
+    #[serde(default)]
+    pub body_encoding: BodyEncoding,
+    /// Overrides the query style of the config for the params of the route.
+    #[serde(default)]
+    pub query_style: Option<QueryStyle>,
+    /// The OpenAPI `operationId`, naming the generated function by default.
+    #[serde(default)]
+    pub operation_id: Option<String>,
+    #[serde(default)]
+    pub stability: Stability,
+    #[serde(default)]
+    pub docs: SchemaDocs,
+    /// A short summary of the route, heading its documentation.
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Whether the route is generated, e.g. disabled for routes only called by
+    /// other services.
+    #[serde(default = "yes")]
+    pub add_to_client: bool,
+    /// Whether cookies are sent with the requests of the route, disabled e.g.
+    /// for third-party endpoints.
+    #[serde(default = "yes")]
+    pub credentials: bool,
+    /// The subprotocols offered by the websocket of the route.
+    #[serde(default)]
+    pub ws_protocols: Vec<String>,
+    /// The query parameter the websocket of the route sends the token of
+    /// `options.wsToken` in, browsers not allowing headers in the handshake.
+    #[serde(default)]
+    pub ws_token_param: Option<String>,
+    /// Overrides `options.reconnect` for the event source or websocket of the
+    /// route.
+    #[serde(default)]
+    pub reconnect: Option<ReconnectPolicy>,
+    /// Overrides [`settings`] for the schemas of subsequent `with_*` calls.
+    #[serde(skip)]
+    pub schema_settings: Option<SchemaSettingsFn>,
+}
+
+/// Builds the [`SchemaSettings`] of a route, a function rather than the
+/// settings, which are not `Sync`.
+#[derive(Clone)]
+pub struct SchemaSettingsFn(pub Arc<dyn Fn() -> SchemaSettings + Send + Sync>);
+
+impl Debug for SchemaSettingsFn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SchemaSettingsFn")
+    }
+}
+
+fn yes() -> bool { true }
+
+fn take_description(root: &mut RootSchema) -> Option<String> {
+    root.schema
+        .metadata
+        .take()
+        .and_then(|metadata| metadata.description)
 }
 
 pub fn settings(option_add_null_type: bool) -> SchemaSettings {
@@ -95,24 +293,112 @@ impl RequestInfo {
             path: path.to_string(),
             method,
             tag: tag.tag_name().to_string(),
+            path_params: Kind::None,
             req_body: Kind::None,
             req_params: Kind::None,
+            req_headers: Kind::None,
             res_body: Kind::None,
+            res_headers: Kind::None,
             deprecated: Deprecated::default(),
             error_codes: Vec::new(),
+            error_schemas: Vec::new(),
+            pagination: None,
+            res_content_types: Vec::new(),
+            accept: None,
+            existence_check: false,
+            long_poll: false,
+            no_content: false,
+            timeout_ms: None,
+            retries: None,
+            body_encoding: BodyEncoding::Json,
+            query_style: None,
+            operation_id: None,
+            stability: Stability::Stable,
+            docs: SchemaDocs::default(),
+            summary: None,
+            description: None,
+            add_to_client: true,
+            credentials: true,
+            ws_protocols: Vec::new(),
+            ws_token_param: None,
+            reconnect: None,
+            schema_settings: None,
         }
     }
 
-    pub fn with_error(mut self, code: u16, desc: &'static str) -> Self {
-        self.error_codes.push((code, desc.to_string()));
+    /// Generates the schemas of the subsequent `with_*` calls with the result
+    /// of `settings`, e.g. with another `meta_schema`. Only
+    /// `option_add_null_type` is still set per schema. Schemas referencing
+    /// definitions, e.g. without `inline_subschemas`, fail to generate.
+    pub fn with_schema_settings(
+        mut self,
+        settings: impl Fn() -> SchemaSettings + Send + Sync + 'static,
+    ) -> Self {
+        assert!(
+            self.schema_settings
+                .replace(SchemaSettingsFn(Arc::new(settings)))
+                .is_none(),
+            "Schema settings already present"
+        );
+
+        self
+    }
+
+    /// Documents the error response of one or more codes, e.g. `404`,
+    /// `500..=599` or `"5xx"`.
+    pub fn with_error(mut self, codes: impl StatusCodes, desc: &'static str) -> Self {
+        self.error_codes.extend(
+            codes
+                .codes()
+                .into_iter()
+                .map(|code| (code, desc.to_string())),
+        );
+        self
+    }
+
+    /// The error codes sorted, consecutive codes with the same description
+    /// being merged.
+    pub(crate) fn error_ranges(&self) -> Vec<(RangeInclusive<u16>, &str)> {
+        let mut codes = self
+            .error_codes
+            .iter()
+            .map(|(code, desc)| (*code, desc.as_str()))
+            .collect::<Vec<(u16, &str)>>();
+        codes.sort_by_key(|(code, _)| *code);
+
+        let mut ranges = Vec::<(RangeInclusive<u16>, &str)>::new();
+        for (code, desc) in codes {
+            match ranges.last_mut() {
+                Some((range, last))
+                    if range.end().checked_add(1) == Some(code) && *last == desc =>
+                    *range = *range.start()..=code,
+                _ => ranges.push((code..=code, desc)),
+            }
+        }
+
+        ranges
+    }
+
+    /// Parses the body of `code` responses as `E`, passed to the `error`
+    /// callback of the `PromiseWrapper`.
+    pub fn with_error_schema<E: JsonSchema>(mut self, code: u16) -> Self {
+        let mut res = self.generator(true).into_root_schema_for::<E>();
+        res.schema.metadata = None;
+
+        assert!(
+            self.error_schemas.iter().all(|(c, _)| *c != code),
+            "Error schema already present"
+        );
+        self.error_schemas.push((code, res));
+
         self
     }
 
     pub fn with_req_params<T: JsonSchema>(mut self) -> Self {
-        let gen = generator(settings(false));
+        let gen = self.generator(false);
 
         let mut res = gen.into_root_schema_for::<T>();
-        res.schema.metadata = None;
+        self.docs.req_params = take_description(&mut res);
 
         assert!(
             self.req_params.replace(Kind::Schema(res)).is_none(),
@@ -122,11 +408,39 @@ impl RequestInfo {
         self
     }
 
+    pub fn with_path_params<T: JsonSchema>(mut self) -> Self {
+        let gen = self.generator(false);
+
+        let mut res = gen.into_root_schema_for::<T>();
+        self.docs.path_params = take_description(&mut res);
+
+        assert!(
+            self.path_params.replace(Kind::Schema(res)).is_none(),
+            "Path params schema already present"
+        );
+
+        self
+    }
+
+    pub fn with_req_headers<T: JsonSchema>(mut self) -> Self {
+        let gen = self.generator(false);
+
+        let mut res = gen.into_root_schema_for::<T>();
+        self.docs.req_headers = take_description(&mut res);
+
+        assert!(
+            self.req_headers.replace(Kind::Schema(res)).is_none(),
+            "Request headers schema already present"
+        );
+
+        self
+    }
+
     pub fn with_req_body<T: JsonSchema>(mut self) -> Self {
-        let gen = generator(settings(true));
+        let gen = self.generator(true);
 
         let mut res = gen.into_root_schema_for::<T>();
-        res.schema.metadata = None;
+        self.docs.req_body = take_description(&mut res);
 
         assert!(
             self.req_body.replace(Kind::Schema(res)).is_none(),
@@ -136,6 +450,44 @@ impl RequestInfo {
         self
     }
 
+    /// Sends `T` url encoded, its fields have to be strings, numbers or
+    /// booleans.
+    pub fn with_urlencoded_req<T: JsonSchema>(mut self) -> Self {
+        self = self.with_req_body::<T>();
+        self.body_encoding = BodyEncoding::UrlEncoded;
+        self
+    }
+
+    /// Sends `T` as `multipart/form-data`, its fields marked with
+    /// [`crate::file`] or [`crate::files`] being uploaded as files and arrays
+    /// appended once per item.
+    pub fn with_multipart_req<T: JsonSchema>(mut self) -> Self {
+        self = self.with_req_body::<T>();
+        self.body_encoding = BodyEncoding::Multipart;
+        self
+    }
+
+    /// Serializes the params of the route with `style` instead of the query
+    /// style of the config.
+    pub fn with_query_style(mut self, style: QueryStyle) -> Self {
+        self.query_style = Some(style);
+        self
+    }
+
+    /// Sends `T` as the request body regardless of the method, unlike
+    /// `with_req_schema` which sends it as params for GET, HEAD and DELETE.
+    ///
+    /// **Bodies on GET and HEAD are not standard.** Servers and proxies may
+    /// drop them, and the `Request` of spec compliant fetch implementations
+    /// (browsers, node) rejects them, so the generated function only works
+    /// with a permissive `options.fetch` and `Request`.
+    /// [`RequestInfo::validate`] reports such routes.
+    pub fn with_req_body_forced<T: JsonSchema>(self) -> Self { self.with_req_body::<T>() }
+
+    /// Sends `T` as params for GET, HEAD and DELETE and as the body for every
+    /// other method. [`RequestInfo::with_req_params`] and
+    /// [`RequestInfo::with_req_body`] place it explicitly instead, e.g. for
+    /// DELETE routes with a body.
     pub fn with_req_schema<T: JsonSchema>(self) -> Self {
         if self.request_default_params() {
             self.with_req_params::<T>()
@@ -145,9 +497,10 @@ impl RequestInfo {
     }
 
     pub fn with_res_schema<T: JsonSchema>(mut self) -> Self {
-        let mut res = generator(settings(true)).into_root_schema_for::<T>();
-        res.schema.metadata = None;
+        let mut res = self.generator(true).into_root_schema_for::<T>();
+        self.docs.res_body = take_description(&mut res);
 
+        assert!(!self.no_content, "Response already declared empty");
         assert!(
             self.res_body.replace(Kind::Schema(res)).is_none(),
             "Response schema already present"
@@ -156,6 +509,56 @@ impl RequestInfo {
         self
     }
 
+    /// Reads `T` from the headers of successful responses, exposed by
+    /// `PromiseWrapper.headers()`.
+    pub fn with_res_headers<T: JsonSchema>(mut self) -> Self {
+        let mut res = self.generator(true).into_root_schema_for::<T>();
+        res.schema.metadata = None;
+
+        assert!(
+            self.res_headers.replace(Kind::Schema(res)).is_none(),
+            "Response headers schema already present"
+        );
+
+        self
+    }
+
+    /// Adds the response body of the `code` status, the response being a union
+    /// of the bodies of all added statuses. Other statuses are errors.
+    pub fn with_status_res<T: JsonSchema>(mut self, code: u16) -> Self {
+        let mut res = self.generator(true).into_root_schema_for::<T>();
+        res.schema.metadata = None;
+
+        assert!(!self.no_content, "Response already declared empty");
+        match &mut self.res_body {
+            Kind::None => self.res_body = Kind::StatusUnion(vec![(code, res)]),
+            Kind::StatusUnion(bodies) => {
+                assert!(
+                    bodies.iter().all(|(c, _)| *c != code),
+                    "Status {code} already present"
+                );
+                bodies.push((code, res));
+            },
+            _ => panic!("Response schema already present"),
+        }
+
+        self
+    }
+
+    /// Streams the response as newline delimited JSON, each line being a `T`.
+    pub fn with_ndjson_res<T: JsonSchema>(mut self) -> Self {
+        let mut res = self.generator(true).into_root_schema_for::<T>();
+        self.docs.res_body = take_description(&mut res);
+
+        assert!(!self.no_content, "Response already declared empty");
+        assert!(
+            self.res_body.replace(Kind::Ndjson(res)).is_none(),
+            "Response schema already present"
+        );
+
+        self
+    }
+
     pub fn with_any_req_body(mut self) -> Self {
         assert!(
             self.req_body.replace(Kind::Any).is_none(),
@@ -174,6 +577,7 @@ impl RequestInfo {
         self
     }
 
+    /// Like [`RequestInfo::with_req_schema`], placed by the method.
     pub fn with_any_req(self) -> Self {
         if self.request_default_params() {
             self.with_any_req_params()
@@ -183,6 +587,7 @@ impl RequestInfo {
     }
 
     pub fn with_any_res(mut self) -> Self {
+        assert!(!self.no_content, "Response already declared empty");
         assert!(
             self.res_body.replace(Kind::Any).is_none(),
             "Response schema already present"
@@ -191,15 +596,64 @@ impl RequestInfo {
         self
     }
 
+    pub fn with_text_body(mut self) -> Self {
+        assert!(
+            self.req_body.replace(Kind::Text).is_none(),
+            "Request body schema already present"
+        );
+
+        self
+    }
+
+    pub fn with_text_res(mut self) -> Self {
+        assert!(!self.no_content, "Response already declared empty");
+        assert!(
+            self.res_body.replace(Kind::Text).is_none(),
+            "Response schema already present"
+        );
+
+        self
+    }
+
+    /// Reads the response as `text/plain`, validated with the string schema of
+    /// `T`, e.g. a token with a pattern.
+    pub fn with_text_res_schema<T: JsonSchema>(mut self) -> Self {
+        let mut res = self.generator(true).into_root_schema_for::<T>();
+        self.docs.res_body = take_description(&mut res);
+
+        assert!(
+            res.schema.instance_type == Some(InstanceType::String.into()),
+            "Text response schemas must be strings"
+        );
+        assert!(!self.no_content, "Response already declared empty");
+        assert!(
+            self.res_body.replace(Kind::TextSchema(res)).is_none(),
+            "Response schema already present"
+        );
+
+        self
+    }
+
+    pub fn with_binary_res(mut self) -> Self {
+        assert!(!self.no_content, "Response already declared empty");
+        assert!(
+            self.res_body.replace(Kind::Binary).is_none(),
+            "Response schema already present"
+        );
+
+        self
+    }
+
 
     pub fn with_sse<Message: JsonSchema>(mut self) -> Self {
         if self.method != Method::Get {
             panic!("RequestInfo with websockets can only be GET requests");
         }
 
-        let mut res = generator(settings(true)).into_root_schema_for::<Message>();
-        res.schema.metadata = None;
+        let mut res = self.generator(true).into_root_schema_for::<Message>();
+        self.docs.res_body = take_description(&mut res);
 
+        assert!(!self.no_content, "Response already declared empty");
         assert!(
             self.res_body.replace(Kind::SSE(res)).is_none(),
             "Response schema already present"
@@ -208,16 +662,42 @@ impl RequestInfo {
         self
     }
 
+    /// Adds a named event to the server side events of the route, messages
+    /// being parsed with the schema of their event.
+    pub fn with_sse_event<Message: JsonSchema>(mut self, event: &str) -> Self {
+        if self.method != Method::Get {
+            panic!("RequestInfo with server side events can only be GET requests");
+        }
+
+        let mut res = self.generator(true).into_root_schema_for::<Message>();
+        res.schema.metadata = None;
+
+        assert!(!self.no_content, "Response already declared empty");
+        match &mut self.res_body {
+            Kind::None =>
+                self.res_body =
+                    Kind::SSEEvents(BTreeMap::from([(event.to_string(), res)])),
+            Kind::SSEEvents(events) => assert!(
+                events.insert(event.to_string(), res).is_none(),
+                "Event {event} already present"
+            ),
+            _ => panic!("Response schema already present"),
+        }
+
+        self
+    }
+
     pub fn with_websocket<Client: JsonSchema, Server: JsonSchema>(mut self) -> Self {
         if self.method != Method::Get {
             panic!("RequestInfo with websockets can only be GET requests");
         }
 
-        let mut client_msg = generator(settings(true)).into_root_schema_for::<Client>();
+        let mut client_msg = self.generator(true).into_root_schema_for::<Client>();
         client_msg.schema.metadata = None;
-        let mut server_msg = generator(settings(true)).into_root_schema_for::<Server>();
+        let mut server_msg = self.generator(true).into_root_schema_for::<Server>();
         server_msg.schema.metadata = None;
 
+        assert!(!self.no_content, "Response already declared empty");
         assert!(
             self.res_body
                 .replace(Kind::Websocket {
@@ -231,6 +711,132 @@ impl RequestInfo {
         self
     }
 
+    /// Offers the subprotocols in the handshake of the websocket, e.g.
+    /// `graphql-transport-ws`.
+    pub fn with_ws_protocols(mut self, protocols: &[&str]) -> Self {
+        assert!(
+            self.ws_protocols.is_empty(),
+            "Websocket protocols already present"
+        );
+        self.ws_protocols = protocols.iter().map(|v| v.to_string()).collect();
+        self
+    }
+
+    /// Sends the token returned by `options.wsToken` as the query parameter
+    /// `param` when the websocket connects.
+    pub fn with_ws_token_param(mut self, param: &str) -> Self {
+        assert!(
+            self.ws_token_param.replace(param.to_string()).is_none(),
+            "Websocket token param already present"
+        );
+        self
+    }
+
+    /// Reconnects the event source or websocket of the route by the policy,
+    /// instead of `options.reconnect`.
+    pub fn with_reconnect(mut self, policy: ReconnectPolicy) -> Self {
+        assert!(
+            self.reconnect.replace(policy).is_none(),
+            "Reconnect policy already present"
+        );
+        self
+    }
+
+    pub fn with_pagination(mut self, config: PageConfig) -> Self {
+        assert!(
+            self.pagination.replace(config).is_none(),
+            "Pagination already present"
+        );
+
+        self
+    }
+
+    pub fn with_res_content_type(mut self, content_type: &str) -> Self {
+        self.res_content_types.push(content_type.to_string());
+        self
+    }
+
+    pub fn with_accept(mut self, accept: &str) -> Self {
+        assert!(
+            self.accept.replace(accept.to_string()).is_none(),
+            "Accept header already present"
+        );
+
+        self
+    }
+
+    /// Additionally generates `exists...`, resolving to `true` for 2xx
+    /// responses and to `false` for 404 responses, rejecting with the
+    /// response for every other status.
+    pub fn as_existence_check(mut self) -> Self {
+        self.existence_check = true;
+        self
+    }
+
+    pub fn long_poll(mut self) -> Self {
+        self.long_poll = true;
+        self
+    }
+
+    pub fn with_summary(mut self, summary: &str) -> Self {
+        self.summary = Some(summary.to_string());
+        self
+    }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    pub fn with_operation_id(mut self, operation_id: &str) -> Self {
+        self.operation_id = Some(operation_id.to_string());
+        self
+    }
+
+    pub fn with_timeout_ms(mut self, timeout_ms: u32) -> Self {
+        self.timeout_ms = Some(timeout_ms);
+        self
+    }
+
+    pub fn with_retries(mut self, count: u8) -> Self {
+        if !self.method.is_idempotent() {
+            panic!(
+                "Retries only apply to idempotent methods, not {}",
+                self.method
+            );
+        }
+
+        self.retries = Some(count);
+        self
+    }
+
+    /// Resolves to `void` on success, e.g. for `204 No Content` responses.
+    /// Setting a response schema afterwards panics.
+    pub fn with_empty_res(mut self) -> Self {
+        assert!(self.res_body.is_none(), "Response schema already present");
+
+        self.no_content = true;
+        self
+    }
+
+    /// Leaves the route out of the generated clients.
+    pub fn with_excluded_from_client(mut self) -> Self {
+        self.add_to_client = false;
+        self
+    }
+
+    /// Whether cookies are sent with the requests of the route, `true` by
+    /// default.
+    pub fn with_credentials(mut self, credentials: bool) -> Self {
+        self.credentials = credentials;
+        self
+    }
+
+    pub fn with_stability(mut self, stability: Stability) -> Self {
+        self.stability = stability;
+        self
+    }
+
     pub fn with_deprecation_note(mut self, new_route: &RequestInfo) -> Self {
         if self.deprecated.is() {
             panic!("RequestInfo already has a response schema");
@@ -244,14 +850,51 @@ impl RequestInfo {
         self
     }
 
+    /// Deprecates the route with a free-text reason, e.g. `use the v2
+    /// endpoint`, and optionally the version it is deprecated since.
+    pub fn with_deprecation_reason(mut self, reason: &str, since: Option<&str>) -> Self {
+        assert!(!self.deprecated.is(), "RequestInfo is already deprecated");
+
+        self.deprecated = Deprecated::WithReason {
+            reason: reason.to_string(),
+            since: since.map(str::to_string),
+        };
+        self
+    }
+
+    /// Lists the non-standard parts of the route, which are still generated.
+    pub fn validate(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        if matches!(self.method, Method::Get | Method::Head) && !self.req_body.is_none() {
+            warnings.push(format!(
+                "{} {} has a request body, which is non-standard for {}",
+                self.method, self.path, self.method
+            ));
+        }
+
+        warnings
+    }
+
+    fn generator(&self, option_add_null_type: bool) -> SchemaGenerator {
+        let mut settings = match &self.schema_settings {
+            Some(SchemaSettingsFn(settings)) => settings(),
+            None => return generator(settings(option_add_null_type)),
+        };
+        settings.option_add_null_type = option_add_null_type;
+        generator(settings)
+    }
+
     fn request_default_params(&self) -> bool {
         matches!(self.method, Method::Get | Method::Head | Method::Delete)
     }
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, JsonSchema, Serialize, Deserialize)]
 pub struct Requests {
     pub requests: Vec<RequestInfo>,
+    #[serde(default)]
+    pub tag_base_urls: BTreeMap<String, String>,
 }
 
 impl Requests {
@@ -260,8 +903,86 @@ impl Requests {
         self
     }
 
+    pub fn with_tag_base_url(mut self, tag: impl Tag, url: &str) -> Self {
+        self.tag_base_urls
+            .insert(tag.tag_name().to_string(), url.to_string());
+        self
+    }
+
+    /// Appends the requests of `other` in order, keeping the tag base urls
+    /// already set.
+    pub fn extend(&mut self, other: Requests) {
+        self.requests.extend(other.requests);
+        for (tag, url) in other.tag_base_urls {
+            self.tag_base_urls.entry(tag).or_insert(url);
+        }
+    }
+
+    /// Removes requests with the path, method and tag of an earlier one.
+    pub fn dedup(&mut self) {
+        let mut seen = HashSet::<(String, Method, String)>::new();
+        self.requests.retain(|info| {
+            seen.insert((info.path.clone(), info.method, info.tag.clone()))
+        });
+    }
+
+    /// The JSON schema of the input format, e.g. for validating and
+    /// completing hand-written input files.
+    pub fn input_schema() -> RootSchema {
+        SchemaGenerator::new(SchemaSettings::draft07()).into_root_schema_for::<Requests>()
+    }
+
     // pub fn with_raw(mut self, info: RequestInfo) -> Self {
     //     self.requests.push(info);
     //     self
     // }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{RequestInfo, Requests};
+    use crate::{Method, Stability};
+
+    #[test]
+    fn extend_and_dedup() {
+        let mut requests = Requests::default()
+            .with(|| RequestInfo::new("/api/users", Method::Get, "users"))
+            .with(|| RequestInfo::new("/api/users", Method::Post, "users"))
+            .with_tag_base_url("users", "/users");
+
+        requests.extend(
+            Requests::default()
+                .with(|| RequestInfo::new("/api/posts", Method::Get, "posts"))
+                .with(|| {
+                    RequestInfo::new("/api/users", Method::Get, "users")
+                        .with_stability(Stability::Beta)
+                })
+                .with_tag_base_url("users", "/other")
+                .with_tag_base_url("posts", "/posts"),
+        );
+
+        let routes = |requests: &Requests| {
+            requests
+                .requests
+                .iter()
+                .map(|info| format!("{} {}", info.method, info.path))
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(routes(&requests), [
+            "GET /api/users",
+            "POST /api/users",
+            "GET /api/posts",
+            "GET /api/users"
+        ]);
+        assert_eq!(requests.tag_base_urls["users"], "/users");
+        assert_eq!(requests.tag_base_urls["posts"], "/posts");
+
+        requests.dedup();
+        assert_eq!(routes(&requests), [
+            "GET /api/users",
+            "POST /api/users",
+            "GET /api/posts"
+        ]);
+        assert_eq!(requests.requests[0].stability, Stability::Stable);
+    }
+}
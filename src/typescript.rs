@@ -0,0 +1,154 @@
+use schemars::schema::{InstanceType, Schema, SchemaObject, SingleOrVec};
+use serde_json::Value;
+
+use crate::{multipart::is_file, namespace::is_identifier};
+
+/// Declares `name` as the TypeScript type of the schema, an interface for
+/// object schemas.
+pub(crate) fn declaration(name: &str, schema: &SchemaObject) -> String {
+    match is_object(schema) {
+        true => format!("    export interface {name} {}\n\n", object(schema)),
+        false => format!("    export type {name} = {};\n\n", ts_type(schema)),
+    }
+}
+
+/// Translates a schema into the TypeScript type of the values it accepts.
+pub(crate) fn ts_type(schema: &SchemaObject) -> String {
+    if let Some(value) = &schema.const_value {
+        return value.to_string();
+    }
+
+    if let Some(values) = &schema.enum_values {
+        return values
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<String>>()
+            .join(" | ");
+    }
+
+    if let Some(subschemas) = &schema.subschemas {
+        if let Some(variants) = subschemas.any_of.as_ref().or(subschemas.one_of.as_ref())
+        {
+            return join(variants, " | ");
+        }
+        if let Some(variants) = &subschemas.all_of {
+            return join(variants, " & ");
+        }
+    }
+
+    match &schema.instance_type {
+        Some(SingleOrVec::Single(t)) => instance_type(schema, **t),
+        Some(SingleOrVec::Vec(types)) => types
+            .iter()
+            .map(|t| instance_type(schema, *t))
+            .collect::<Vec<String>>()
+            .join(" | "),
+        None if schema.object.is_some() => object(schema),
+        None => "unknown".to_string(),
+    }
+}
+
+fn instance_type(schema: &SchemaObject, instance_type: InstanceType) -> String {
+    match instance_type {
+        InstanceType::Null => "null".to_string(),
+        InstanceType::Boolean => "boolean".to_string(),
+        InstanceType::Number | InstanceType::Integer => "number".to_string(),
+        InstanceType::String if is_file(schema) => "Blob".to_string(),
+        InstanceType::String => "string".to_string(),
+        InstanceType::Array => array(schema),
+        InstanceType::Object => object(schema),
+    }
+}
+
+fn array(schema: &SchemaObject) -> String {
+    match schema.array.as_ref().and_then(|a| a.items.as_ref()) {
+        Some(SingleOrVec::Single(item)) => format!("{}[]", grouped(schema_type(item))),
+        Some(SingleOrVec::Vec(items)) => format!("[{}]", join(items, ", ")),
+        None => "unknown[]".to_string(),
+    }
+}
+
+fn object(schema: &SchemaObject) -> String {
+    let Some(object) = &schema.object else {
+        return "Record<string, unknown>".to_string();
+    };
+
+    let mut fields = object
+        .properties
+        .iter()
+        .map(|(name, property)| {
+            let optional = match object.required.contains(name) {
+                true => "",
+                false => "?",
+            };
+            format!(
+                "{}{}{optional}: {};",
+                doc(property),
+                key(name),
+                schema_type(property)
+            )
+        })
+        .collect::<Vec<String>>();
+
+    if let Some(additional) = &object.additional_properties {
+        fields.push(format!("[key: string]: {};", schema_type(additional)));
+    }
+
+    format!("{{ {} }}", fields.join(" "))
+}
+
+/// The name of a property, quoted unless it is an identifier.
+pub(crate) fn key(name: &str) -> String {
+    match is_identifier(name) {
+        true => name.to_string(),
+        false => Value::String(name.to_string()).to_string(),
+    }
+}
+
+fn doc(schema: &Schema) -> String {
+    let Schema::Object(schema) = schema else {
+        return String::new();
+    };
+
+    schema
+        .metadata
+        .as_ref()
+        .and_then(|m| m.description.as_ref())
+        .map(|description| format!("/** {} */ ", description.replace("*/", "*\\/")))
+        .unwrap_or_default()
+}
+
+fn schema_type(schema: &Schema) -> String {
+    match schema {
+        Schema::Bool(true) => "unknown".to_string(),
+        Schema::Bool(false) => "never".to_string(),
+        Schema::Object(schema) => ts_type(schema),
+    }
+}
+
+fn join(schemas: &[Schema], separator: &str) -> String {
+    schemas
+        .iter()
+        .map(|schema| grouped(schema_type(schema)))
+        .collect::<Vec<String>>()
+        .join(separator)
+}
+
+fn is_object(schema: &SchemaObject) -> bool {
+    schema.object.is_some()
+        && schema.subschemas.is_none()
+        && schema.enum_values.is_none()
+        && match &schema.instance_type {
+            None => true,
+            Some(SingleOrVec::Single(t)) => **t == InstanceType::Object,
+            Some(SingleOrVec::Vec(_)) => false,
+        }
+}
+
+/// Parenthesizes unions and intersections, e.g. for array items.
+fn grouped(ty: String) -> String {
+    match ty.contains(" | ") || ty.contains(" & ") {
+        true => format!("({ty})"),
+        false => ty,
+    }
+}
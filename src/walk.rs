@@ -0,0 +1,112 @@
+use schemars::schema::{Schema, SchemaObject, SingleOrVec};
+
+/// A step from a schema to one of its direct subschemas.
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum Step<'a> {
+    Property(&'a str),
+    AdditionalProperties,
+    /// The schema of all items of an array.
+    Items,
+    /// The schema of one item of a tuple.
+    TupleItem(usize),
+    /// A variant of `allOf`, `anyOf` and `oneOf`, counted in this order.
+    Variant(usize),
+}
+
+/// Visits the subschemas of `schema` depth first, each with the steps leading
+/// to it. The subschemas of a visited schema are skipped if `visit` returns
+/// `false`.
+pub(crate) fn walk<'a>(
+    schema: &'a SchemaObject,
+    visit: &mut impl FnMut(&[Step<'a>], &'a Schema) -> bool,
+) {
+    walk_at(schema, &mut Vec::new(), visit);
+}
+
+fn walk_at<'a>(
+    schema: &'a SchemaObject,
+    path: &mut Vec<Step<'a>>,
+    visit: &mut impl FnMut(&[Step<'a>], &'a Schema) -> bool,
+) {
+    for (step, child) in children(schema) {
+        path.push(step);
+        if visit(path, child) {
+            if let Schema::Object(child) = child {
+                walk_at(child, path, visit);
+            }
+        }
+        path.pop();
+    }
+}
+
+fn children(schema: &SchemaObject) -> Vec<(Step<'_>, &Schema)> {
+    let mut out = Vec::new();
+
+    if let Some(object) = &schema.object {
+        for (name, property) in &object.properties {
+            out.push((Step::Property(name), property));
+        }
+        if let Some(additional) = &object.additional_properties {
+            out.push((Step::AdditionalProperties, &**additional));
+        }
+    }
+
+    match schema.array.as_ref().and_then(|a| a.items.as_ref()) {
+        Some(SingleOrVec::Single(item)) => out.push((Step::Items, &**item)),
+        Some(SingleOrVec::Vec(items)) => out.extend(
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| (Step::TupleItem(i), item)),
+        ),
+        None => {},
+    }
+
+    if let Some(subschemas) = &schema.subschemas {
+        let variants = [&subschemas.all_of, &subschemas.any_of, &subschemas.one_of];
+        for (i, variant) in variants.into_iter().flatten().flatten().enumerate() {
+            out.push((Step::Variant(i), variant));
+        }
+    }
+
+    out
+}
+
+/// The subschema at the end of `path`, as visited by [`walk`], if it is no
+/// boolean schema.
+pub(crate) fn object_at_mut<'s>(
+    schema: &'s mut SchemaObject,
+    path: &[Step],
+) -> Option<&'s mut SchemaObject> {
+    let Some((step, rest)) = path.split_first() else {
+        return Some(schema);
+    };
+
+    let child = match *step {
+        Step::Property(name) => schema.object.as_mut()?.properties.get_mut(name)?,
+        Step::AdditionalProperties =>
+            &mut **schema.object.as_mut()?.additional_properties.as_mut()?,
+        Step::Items => match schema.array.as_mut()?.items.as_mut()? {
+            SingleOrVec::Single(item) => &mut **item,
+            SingleOrVec::Vec(_) => return None,
+        },
+        Step::TupleItem(i) => match schema.array.as_mut()?.items.as_mut()? {
+            SingleOrVec::Vec(items) => items.get_mut(i)?,
+            SingleOrVec::Single(_) => return None,
+        },
+        Step::Variant(i) => {
+            let subschemas = schema.subschemas.as_mut()?;
+            let variants = [
+                &mut subschemas.all_of,
+                &mut subschemas.any_of,
+                &mut subschemas.one_of,
+            ];
+            variants.into_iter().flatten().flatten().nth(i)?
+        },
+    };
+
+    match child {
+        Schema::Object(child) => object_at_mut(child, rest),
+        Schema::Bool(_) => None,
+    }
+}